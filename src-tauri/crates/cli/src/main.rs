@@ -0,0 +1,879 @@
+//! 命令行工具：查询钻孔、计算轨迹、批量导出、绘制轨迹图、导出 KML
+//!
+//! 退出码：0 成功；1 运行错误（数据库无法打开、写文件失败等）；2 参数或数据校验未通过。
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use drilltrack_core::calc;
+use drilltrack_core::i18n::{self, t, tf, Locale, Msg};
+use drilltrack_core::models::data::DataList;
+use drilltrack_core::models::repo::{Repo, RepoFilter};
+use drilltrack_core::services::dxf::{Collar, DxfHole, DxfOptions};
+use drilltrack_core::services::export::{self, CsvOptions, ExportFormat, ExportOptions};
+use drilltrack_core::services::pdf::{PdfOptions, SignatureBlock};
+use drilltrack_core::services::plot::{PlotFormat, PlotOptions, PlotView};
+use drilltrack_core::services::projection::{Ellipsoid, Projection};
+use drilltrack_core::services::templates::ReportTemplate;
+use drilltrack_core::services::{backup, db};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "drilltrack-cli",
+    version,
+    about = "钻孔轨迹仪数据处理命令行工具"
+)]
+struct Cli {
+    /// 数据库文件路径（只读打开；结构版本较旧时需先用桌面程序打开一次以完成升级）
+    #[arg(
+        long,
+        env = "DRILLTRACK_DB",
+        global = true,
+        default_value = "database.db"
+    )]
+    db: PathBuf,
+
+    /// 提示信息与导出文件中文字的语言：zh-CN 或 en-US（PNG 轨迹图中的标注总是英文）
+    #[arg(
+        long,
+        env = "DRILLTRACK_LOCALE",
+        global = true,
+        default_value = "zh-CN"
+    )]
+    locale: Locale,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 列出钻孔
+    List {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// 以 JSON 输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 计算并输出钻孔轨迹
+    Calc {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// 磁偏角（度），东偏为正
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        declination: f64,
+
+        /// 以 JSON 输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 导出一个或多个钻孔
+    Export {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// 导出格式，可重复指定或用逗号分隔
+        #[arg(short, long, value_enum, value_delimiter = ',', default_value = "xlsx")]
+        format: Vec<FormatArg>,
+
+        /// 输出目录，不存在时自动创建
+        #[arg(short, long, default_value = ".")]
+        out: PathBuf,
+
+        /// 磁偏角（度），东偏为正
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        declination: f64,
+
+        /// 坐标保留的小数位数
+        #[arg(long, default_value_t = ExportOptions::default().decimal_places)]
+        decimal_places: usize,
+
+        /// Excel 报表模板（JSON 文件），不指定时使用内置模板
+        #[arg(long)]
+        template: Option<PathBuf>,
+
+        /// 孔口坐标（JSON 文件），形如 {"1": {"east": 0, "north": 0, "elevation": 0}}
+        #[arg(long)]
+        collars: Option<PathBuf>,
+
+        /// DXF 深度标注间隔（米），为 0 时标注每个测点
+        #[arg(long, default_value_t = DxfOptions::default().label_interval)]
+        label_interval: f64,
+
+        /// CSV 字段分隔符，单个字符或 tab
+        #[arg(long, default_value = ",")]
+        csv_delimiter: String,
+
+        /// CSV 小数点：. 或 ,
+        #[arg(long, default_value_t = '.')]
+        csv_decimal: char,
+
+        /// PDF 报告页眉中的公司名称
+        #[arg(long)]
+        company: Option<String>,
+
+        /// PDF 报告页眉中的公司标志（JPEG 或 PNG）
+        #[arg(long)]
+        logo: Option<PathBuf>,
+
+        /// PDF 报告的签字栏，可重复指定，如 --signature 测量人 --signature 审核人；
+        /// 不指定时为测量人、审核人、批准人
+        #[arg(long = "signature", conflicts_with = "no_signatures")]
+        signatures: Vec<String>,
+
+        /// PDF 报告不印签字栏
+        #[arg(long)]
+        no_signatures: bool,
+
+        /// svg、png 格式的轨迹图参数
+        #[command(flatten)]
+        plot: PlotArgs,
+
+        /// kml 格式的孔口坐标投影
+        #[command(flatten)]
+        projection: ProjectionArgs,
+    },
+    /// 把一个或多个钻孔画到同一张轨迹图
+    Plot {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// 输出文件，按扩展名输出 SVG 或 PNG
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// 磁偏角（度），东偏为正
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        declination: f64,
+
+        /// 孔口坐标（JSON 文件），形如 {"1": {"east": 0, "north": 0, "elevation": 0}}
+        #[arg(long)]
+        collars: Option<PathBuf>,
+
+        #[command(flatten)]
+        plot: PlotArgs,
+    },
+    /// 把一个或多个钻孔导出到同一个 KML 文件，按矿井、工作面分文件夹
+    Kml {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// 输出文件
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// 磁偏角（度），东偏为正
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        declination: f64,
+
+        /// 孔口坐标（JSON 文件），形如 {"1": {"east": 500000, "north": 4000000, "elevation": 120}}；
+        /// 没有孔口坐标的钻孔不导出
+        #[arg(long)]
+        collars: PathBuf,
+
+        #[command(flatten)]
+        projection: ProjectionArgs,
+    },
+}
+
+/// 孔口坐标的投影参数
+#[derive(Debug, Args)]
+struct ProjectionArgs {
+    /// 中央子午线经度（度），如 3° 带第 39 带为 117
+    #[arg(long, allow_negative_numbers = true)]
+    central_meridian: Option<f64>,
+
+    /// 参考椭球
+    #[arg(long, value_enum, default_value = "cgcs2000")]
+    ellipsoid: EllipsoidArg,
+
+    /// 中央子午线上的比例因子，UTM 为 0.9996
+    #[arg(long, default_value_t = 1.0)]
+    scale_factor: f64,
+
+    /// 假东距（米）；东坐标含带号时应计入带号，如 39500000
+    #[arg(long, default_value_t = 500_000.0)]
+    false_easting: f64,
+
+    /// 假北距（米）
+    #[arg(long, default_value_t = 0.0)]
+    false_northing: f64,
+}
+
+impl ProjectionArgs {
+    /// 未指定中央子午线时没有投影
+    fn to_projection(&self) -> Option<Projection> {
+        self.central_meridian.map(|central_meridian| Projection {
+            ellipsoid: self.ellipsoid.into(),
+            central_meridian,
+            scale_factor: self.scale_factor,
+            false_easting: self.false_easting,
+            false_northing: self.false_northing,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EllipsoidArg {
+    Cgcs2000,
+    Wgs84,
+    Beijing54,
+    Xian80,
+}
+
+impl From<EllipsoidArg> for Ellipsoid {
+    fn from(value: EllipsoidArg) -> Self {
+        match value {
+            EllipsoidArg::Cgcs2000 => Ellipsoid::Cgcs2000,
+            EllipsoidArg::Wgs84 => Ellipsoid::Wgs84,
+            EllipsoidArg::Beijing54 => Ellipsoid::Beijing54,
+            EllipsoidArg::Xian80 => Ellipsoid::Xian80,
+        }
+    }
+}
+
+/// 轨迹图参数
+#[derive(Debug, Args)]
+struct PlotArgs {
+    /// 轨迹图视图
+    #[arg(long, value_enum, default_value = "plan")]
+    view: ViewArg,
+
+    /// 图片宽度（像素），200 ~ 8000，宽高乘积不超过 4096×4096
+    #[arg(long, default_value_t = PlotOptions::default().width)]
+    width: u32,
+
+    /// 图片高度（像素）
+    #[arg(long, default_value_t = PlotOptions::default().height)]
+    height: u32,
+
+    /// 剖面图的高程放大倍数
+    #[arg(long, default_value_t = 1.0)]
+    exaggeration: f64,
+
+    /// 不画设计轨迹
+    #[arg(long)]
+    no_design: bool,
+}
+
+impl PlotArgs {
+    fn to_options(&self) -> PlotOptions {
+        PlotOptions {
+            view: self.view.into(),
+            width: self.width,
+            height: self.height,
+            design: !self.no_design,
+            vertical_exaggeration: self.exaggeration,
+            ..PlotOptions::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ViewArg {
+    Plan,
+    Profile,
+    Isometric,
+}
+
+impl From<ViewArg> for PlotView {
+    fn from(value: ViewArg) -> Self {
+        match value {
+            ViewArg::Plan => PlotView::Plan,
+            ViewArg::Profile => PlotView::Profile,
+            ViewArg::Isometric => PlotView::Isometric,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Xlsx,
+    Csv,
+    Dxf,
+    Scr,
+    Json,
+    Geojson,
+    Pdf,
+    Svg,
+    Png,
+    Las,
+    Witsml,
+    Kml,
+}
+
+impl From<FormatArg> for ExportFormat {
+    fn from(value: FormatArg) -> Self {
+        match value {
+            FormatArg::Xlsx => ExportFormat::Xlsx,
+            FormatArg::Csv => ExportFormat::Csv,
+            FormatArg::Dxf => ExportFormat::Dxf,
+            FormatArg::Scr => ExportFormat::Scr,
+            FormatArg::Json => ExportFormat::Json,
+            FormatArg::Geojson => ExportFormat::GeoJson,
+            FormatArg::Pdf => ExportFormat::Pdf,
+            FormatArg::Svg => ExportFormat::Svg,
+            FormatArg::Png => ExportFormat::Png,
+            FormatArg::Las => ExportFormat::Las,
+            FormatArg::Witsml => ExportFormat::Witsml,
+            FormatArg::Kml => ExportFormat::Kml,
+        }
+    }
+}
+
+/// CSV 分隔符参数，`tab` 表示制表符
+fn parse_delimiter(value: &str) -> Result<char> {
+    let mut chars = value.chars();
+    match (value, chars.next(), chars.next()) {
+        ("tab" | "\\t", _, _) => Ok('\t'),
+        (_, Some(c), None) => Ok(c),
+        _ => Err(invalid(t(Msg::CliDelimiterInvalid))),
+    }
+}
+
+/// 钻孔筛选条件
+#[derive(Debug, Args)]
+struct FilterArgs {
+    /// 钻孔 ID，可重复指定或用逗号分隔
+    #[arg(long = "id", value_delimiter = ',')]
+    ids: Vec<i64>,
+
+    /// 矿井名称
+    #[arg(long)]
+    mine: Option<String>,
+
+    /// 工作面名称
+    #[arg(long)]
+    face: Option<String>,
+
+    /// 起始测量日期（含），YYYY-MM-DD
+    #[arg(long)]
+    from: Option<String>,
+
+    /// 截止测量日期（含），YYYY-MM-DD
+    #[arg(long)]
+    to: Option<String>,
+}
+
+/// 参数或数据校验失败，对应退出码 2
+#[derive(Debug)]
+struct ValidationError(String);
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn invalid(message: impl Into<String>) -> anyhow::Error {
+    ValidationError(message.into()).into()
+}
+
+impl FilterArgs {
+    fn to_filter(&self) -> Result<RepoFilter> {
+        for date in [&self.from, &self.to].into_iter().flatten() {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| invalid(tf(Msg::CliDateFormat, &[("date", date)])))?;
+        }
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            if from > to {
+                return Err(invalid(tf(
+                    Msg::CliDateOrder,
+                    &[("from", from), ("to", to)],
+                )));
+            }
+        }
+        Ok(RepoFilter {
+            ids: self.ids.clone(),
+            mine: self.mine.clone(),
+            working_face: self.face.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+        })
+    }
+}
+
+/// 查询符合条件的钻孔；指定了 ID 但不存在时视为校验失败
+async fn query_repos(filter: &FilterArgs) -> Result<Vec<Repo>> {
+    let repo_filter = filter.to_filter()?;
+    let repos = Repo::query_filtered(repo_filter).await?;
+
+    let missing: Vec<String> = filter
+        .ids
+        .iter()
+        .filter(|id| !repos.iter().any(|r| r.id.map(i64::from) == Some(**id)))
+        .map(|id| id.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(invalid(tf(
+            Msg::CliHolesNotFound,
+            &[("ids", &missing.join(", "))],
+        )));
+    }
+    Ok(repos)
+}
+
+/// 写输出文件失败
+fn write_failed(path: &Path, error: std::io::Error) -> anyhow::Error {
+    anyhow!(tf(
+        Msg::CliWriteFailed,
+        &[("path", &path.display()), ("error", &error)],
+    ))
+}
+
+async fn stations_of(repo: &Repo) -> Result<Vec<DataList>> {
+    Ok(db::query_data_list_by_repo_id(repo.id.unwrap_or_default()).await?)
+}
+
+/// 校验测点，问题输出到标准错误；返回是否通过
+fn check_stations(repo: &Repo, stations: &[DataList]) -> bool {
+    let problems = calc::validate_stations(stations);
+    for problem in &problems {
+        eprintln!(
+            "{}",
+            tf(
+                Msg::CliHoleInvalid,
+                &[
+                    ("id", &repo.id.unwrap_or_default()),
+                    ("name", &repo.name),
+                    ("problem", problem),
+                ],
+            )
+        );
+    }
+    problems.is_empty()
+}
+
+async fn run_list(filter: FilterArgs, json: bool) -> Result<()> {
+    let repos = query_repos(&filter).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&repos)?);
+        return Ok(());
+    }
+
+    let header = [
+        Msg::FieldHoleName,
+        Msg::FieldSurveyTime,
+        Msg::FieldMine,
+        Msg::FieldWorkingFace,
+        Msg::FieldDrillingSite,
+        Msg::FieldRig,
+    ]
+    .map(t);
+    println!("ID\t{}", header.join("\t"));
+    for repo in &repos {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            repo.id.unwrap_or_default(),
+            repo.name,
+            repo.mn_time,
+            repo.mine,
+            repo.work,
+            repo.factory,
+            repo.drilling
+        );
+    }
+    eprintln!("{}", tf(Msg::CliHoleCount, &[("count", &repos.len())]));
+    Ok(())
+}
+
+async fn run_calc(filter: FilterArgs, declination: f64, json: bool) -> Result<()> {
+    let repos = query_repos(&filter).await?;
+    if repos.is_empty() {
+        return Err(invalid(t(Msg::CliNoMatchingHoles)));
+    }
+
+    let calc_header = [
+        Msg::ColDepth,
+        Msg::ColPitch,
+        Msg::ColHeading,
+        Msg::ColNorth,
+        Msg::ColEast,
+        Msg::ColElevation,
+        Msg::ColLateral,
+        Msg::ColVertical,
+        Msg::ColDogleg,
+    ]
+    .map(t);
+    let mut failed = 0;
+    let mut results = Vec::new();
+    for repo in &repos {
+        let stations = stations_of(repo).await?;
+        if !check_stations(repo, &stations) {
+            failed += 1;
+            continue;
+        }
+        let points = calc::trajectory(&stations, declination);
+
+        if json {
+            results.push(serde_json::json!({ "repo": repo, "trajectory": points }));
+            continue;
+        }
+        println!(
+            "# {} {} ({})",
+            repo.id.unwrap_or_default(),
+            repo.name,
+            repo.mn_time
+        );
+        println!("{}", calc_header.join("\t"));
+        for p in &points {
+            println!(
+                "{:.2}\t{:.2}\t{:.2}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{:.2}",
+                p.depth,
+                p.pitch,
+                p.heading,
+                p.north,
+                p.east,
+                p.elevation,
+                p.lateral,
+                p.vertical,
+                p.dogleg
+            );
+        }
+        println!();
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    }
+
+    if failed > 0 {
+        return Err(invalid(tf(Msg::CliHolesInvalid, &[("count", &failed)])));
+    }
+    Ok(())
+}
+
+/// 读取孔口坐标文件
+fn load_collars(path: &Path) -> Result<HashMap<i32, Collar>> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        invalid(tf(
+            Msg::CliCollarsReadFailed,
+            &[("path", &path.display()), ("error", &e)],
+        ))
+    })?;
+    serde_json::from_str(&text).map_err(|e| {
+        invalid(tf(
+            Msg::CliCollarsInvalid,
+            &[("path", &path.display()), ("error", &e)],
+        ))
+    })
+}
+
+async fn run_export(
+    filter: FilterArgs,
+    formats: Vec<FormatArg>,
+    out: PathBuf,
+    template: Option<PathBuf>,
+    collars: Option<PathBuf>,
+    mut options: ExportOptions,
+) -> Result<()> {
+    if options.decimal_places > 10 {
+        return Err(invalid(t(Msg::SettingDecimalPlaces)));
+    }
+    if !options.dxf.label_interval.is_finite() || options.dxf.label_interval < 0.0 {
+        return Err(invalid(t(Msg::CliLabelIntervalNegative)));
+    }
+    options.csv.validate().map_err(|e| invalid(e.to_string()))?;
+    if let Some(logo) = &options.pdf.logo {
+        if !std::path::Path::new(logo).is_file() {
+            return Err(invalid(tf(Msg::CliLogoNotFound, &[("path", logo)])));
+        }
+    }
+    options
+        .plot
+        .validate()
+        .map_err(|e| invalid(e.to_string()))?;
+    if let Some(projection) = &options.projection {
+        projection.validate().map_err(|e| invalid(e.to_string()))?;
+    }
+    if let Some(path) = collars {
+        options.collars = load_collars(&path)?;
+    }
+    if let Some(path) = template {
+        options.template = ReportTemplate::load(&path).map_err(|e| invalid(format!("{:#}", e)))?;
+    }
+    let repos = query_repos(&filter).await?;
+    if repos.is_empty() {
+        return Err(invalid(t(Msg::CliNoMatchingHoles)));
+    }
+    std::fs::create_dir_all(&out).map_err(|e| {
+        anyhow!(tf(
+            Msg::CreateDirFailed,
+            &[("path", &out.display()), ("error", &e)],
+        ))
+    })?;
+
+    let mut failed = 0;
+    let mut written = 0;
+    for repo in &repos {
+        let stations = stations_of(repo).await?;
+        if !check_stations(repo, &stations) {
+            failed += 1;
+            continue;
+        }
+        for format in &formats {
+            let format = ExportFormat::from(*format);
+            let path = out.join(export::file_name(repo, format));
+            export::export_hole(repo, &stations, format, &path, &options).await?;
+            println!("{}", path.display());
+            written += 1;
+        }
+    }
+    eprintln!("{}", tf(Msg::CliFilesExported, &[("count", &written)]));
+
+    if failed > 0 {
+        return Err(invalid(tf(Msg::CliHolesNotExported, &[("count", &failed)])));
+    }
+    Ok(())
+}
+
+async fn run_plot(
+    filter: FilterArgs,
+    out: PathBuf,
+    collars: Option<PathBuf>,
+    mut options: ExportOptions,
+) -> Result<()> {
+    let format = match out.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => PlotFormat::Svg,
+        Some(ext) if ext.eq_ignore_ascii_case("png") => PlotFormat::Png,
+        _ => return Err(invalid(t(Msg::CliPlotExtension))),
+    };
+    options
+        .plot
+        .validate()
+        .map_err(|e| invalid(e.to_string()))?;
+    if let Some(path) = collars {
+        options.collars = load_collars(&path)?;
+    }
+    let repos = query_repos(&filter).await?;
+    if repos.is_empty() {
+        return Err(invalid(t(Msg::CliNoMatchingHoles)));
+    }
+
+    let mut failed = 0;
+    let mut valid = Vec::new();
+    for repo in &repos {
+        let stations = stations_of(repo).await?;
+        if check_stations(repo, &stations) {
+            valid.push((repo, stations));
+        } else {
+            failed += 1;
+        }
+    }
+    if valid.is_empty() {
+        return Err(invalid(t(Msg::CliNoHolesToPlot)));
+    }
+    let holes: Vec<DxfHole> = valid
+        .iter()
+        .map(|(repo, stations)| DxfHole {
+            repo,
+            stations,
+            collar: options.collar(repo),
+        })
+        .collect();
+    std::fs::write(&out, export::to_plot(&holes, format, &options)?)
+        .map_err(|e| write_failed(&out, e))?;
+    println!("{}", out.display());
+    eprintln!("{}", tf(Msg::CliHolesPlotted, &[("count", &holes.len())]));
+
+    if failed > 0 {
+        return Err(invalid(tf(Msg::CliHolesNotPlotted, &[("count", &failed)])));
+    }
+    Ok(())
+}
+
+async fn run_kml(
+    filter: FilterArgs,
+    out: PathBuf,
+    collars: PathBuf,
+    mut options: ExportOptions,
+) -> Result<()> {
+    match &options.projection {
+        Some(projection) => projection.validate().map_err(|e| invalid(e.to_string()))?,
+        None => return Err(invalid(t(Msg::CliKmlNeedsProjection))),
+    }
+    options.collars = load_collars(&collars)?;
+    let repos = query_repos(&filter).await?;
+    if repos.is_empty() {
+        return Err(invalid(t(Msg::CliNoMatchingHoles)));
+    }
+
+    let mut failed = 0;
+    let mut valid = Vec::new();
+    for repo in &repos {
+        let stations = stations_of(repo).await?;
+        if !check_stations(repo, &stations) {
+            failed += 1;
+            continue;
+        }
+        match repo.id.and_then(|id| options.collars.get(&id).copied()) {
+            Some(collar) => valid.push((repo, stations, collar)),
+            None => {
+                eprintln!(
+                    "{}",
+                    tf(
+                        Msg::CliHoleNoCollar,
+                        &[("id", &repo.id.unwrap_or_default()), ("name", &repo.name)],
+                    )
+                );
+                failed += 1;
+            }
+        }
+    }
+    if valid.is_empty() {
+        return Err(invalid(t(Msg::ExportNoHoles)));
+    }
+    let holes: Vec<DxfHole> = valid
+        .iter()
+        .map(|(repo, stations, collar)| DxfHole {
+            repo,
+            stations,
+            collar: *collar,
+        })
+        .collect();
+    let kml = export::to_kml(&holes, &options).map_err(|e| invalid(format!("{:#}", e)))?;
+    std::fs::write(&out, kml).map_err(|e| write_failed(&out, e))?;
+    println!("{}", out.display());
+    eprintln!("{}", tf(Msg::CliHolesExported, &[("count", &holes.len())]));
+
+    if failed > 0 {
+        return Err(invalid(tf(Msg::CliHolesSkipped, &[("count", &failed)])));
+    }
+    Ok(())
+}
+
+/// 执行子命令，数据库已打开
+async fn run_command(command: Command, locale: Locale) -> Result<()> {
+    match command {
+        Command::List { filter, json } => run_list(filter, json).await,
+        Command::Calc {
+            filter,
+            declination,
+            json,
+        } => run_calc(filter, declination, json).await,
+        Command::Export {
+            filter,
+            format,
+            out,
+            declination,
+            decimal_places,
+            template,
+            collars,
+            label_interval,
+            csv_delimiter,
+            csv_decimal,
+            company,
+            logo,
+            signatures,
+            no_signatures,
+            plot,
+            projection,
+        } => {
+            let signatures = if no_signatures {
+                Some(Vec::new())
+            } else if signatures.is_empty() {
+                None
+            } else {
+                Some(
+                    signatures
+                        .into_iter()
+                        .map(|label| SignatureBlock { label, name: None })
+                        .collect(),
+                )
+            };
+            let options = ExportOptions {
+                magnetic_declination: declination,
+                decimal_places,
+                locale,
+                dxf: DxfOptions {
+                    label_interval,
+                    ..DxfOptions::default()
+                },
+                csv: CsvOptions {
+                    delimiter: parse_delimiter(&csv_delimiter)?,
+                    decimal_separator: csv_decimal,
+                },
+                pdf: PdfOptions {
+                    company,
+                    logo: logo.map(|path| path.to_string_lossy().to_string()),
+                    signatures,
+                },
+                plot: plot.to_options(),
+                projection: projection.to_projection(),
+                ..ExportOptions::default()
+            };
+            run_export(filter, format, out, template, collars, options).await
+        }
+        Command::Plot {
+            filter,
+            out,
+            declination,
+            collars,
+            plot,
+        } => {
+            let options = ExportOptions {
+                magnetic_declination: declination,
+                locale,
+                plot: plot.to_options(),
+                ..ExportOptions::default()
+            };
+            run_plot(filter, out, collars, options).await
+        }
+        Command::Kml {
+            filter,
+            out,
+            declination,
+            collars,
+            projection,
+        } => {
+            let options = ExportOptions {
+                magnetic_declination: declination,
+                locale,
+                projection: projection.to_projection(),
+                ..ExportOptions::default()
+            };
+            run_kml(filter, out, collars, options).await
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let db_path = cli.db.to_string_lossy().to_string();
+    backup::validate_backup(&db_path).map_err(|e| invalid(e.to_string()))?;
+    db::open_read_only(&db_path).await?;
+
+    let result = run_command(cli.command, cli.locale).await;
+
+    // 命令已失败时保留命令的错误，关闭数据库的错误只作提示
+    match (result, db::close_db().await) {
+        (Err(e), Err(close_error)) => {
+            eprintln!("{}", tf(Msg::CliCloseWarning, &[("error", &close_error)]));
+            Err(e)
+        }
+        (result, closed) => result.and(closed.map_err(Into::into)),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    i18n::set_locale(cli.locale);
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", tf(Msg::CliError, &[("error", &e)]));
+            if e.is::<ValidationError>() {
+                ExitCode::from(2)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
@@ -0,0 +1,509 @@
+//! 钻孔轨迹计算
+//!
+//! 供桌面程序、无界面服务器及导出模块共用。角度单位均为度，长度单位为米。
+
+use crate::i18n::{t, tf, Msg};
+use crate::models::data::DataList;
+use crate::services::settings::ToleranceSettings;
+use serde::{Deserialize, Serialize};
+
+/// 狗腿度的换算长度（度/30米）
+const DOGLEG_COURSE_LENGTH: f64 = 30.0;
+
+fn to_rad(degrees: f64) -> f64 {
+    degrees * std::f64::consts::PI / 180.0
+}
+
+/// 实测方位角换算到设计方位角的基准：减去磁偏角
+///
+/// 与前端 `calculations.ts` 的约定一致，方位差为 实测 − (设计 + 磁偏角)。
+pub fn corrected_heading(heading: f64, magnetic_declination: f64) -> f64 {
+    heading - magnetic_declination
+}
+
+/// 左右位移：L · cos(俯仰角) · sin(改正后方位角 − 设计方位角)
+fn lateral(depth: f64, pitch: f64, corrected_heading: f64, design_heading: f64) -> f64 {
+    depth * to_rad(pitch).cos() * to_rad(corrected_heading - design_heading).sin()
+}
+
+/// 左右位移：实测方位角相对设计方位角（已加磁偏角）的水平偏移，缺失的角度按 0 计
+pub fn lateral_displacement(row: &DataList, magnetic_declination: f64) -> f64 {
+    lateral(
+        row.depth,
+        row.pitch.unwrap_or(0.0),
+        corrected_heading(row.heading.unwrap_or(0.0), magnetic_declination),
+        row.design_heading.unwrap_or(0.0),
+    )
+}
+
+/// 上下位移
+pub fn vertical_displacement(row: &DataList) -> f64 {
+    row.depth * to_rad(row.pitch.unwrap_or(0.0)).sin()
+}
+
+/// 按设计俯仰角计算的上下位移
+pub fn design_vertical_displacement(row: &DataList) -> f64 {
+    row.depth * to_rad(row.design_pitch.unwrap_or(0.0)).sin()
+}
+
+/// 轨迹点，以孔口为原点；北、东、高程单位米，高程向上为正
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    pub depth: f64,
+    pub pitch: f64,
+    // 改正后的方位角（实测减磁偏角），与设计方位角同一基准
+    pub heading: f64,
+    pub north: f64,
+    pub east: f64,
+    pub elevation: f64,
+    pub lateral: f64,
+    pub vertical: f64,
+    pub design_vertical: f64,
+    // 狗腿度（度/30米）
+    pub dogleg: f64,
+}
+
+/// 由俯仰角、方位角得到单位方向向量（北、东、上）
+fn direction(pitch: f64, heading: f64) -> [f64; 3] {
+    let (p, h) = (to_rad(pitch), to_rad(heading));
+    [p.cos() * h.cos(), p.cos() * h.sin(), p.sin()]
+}
+
+/// 最小曲率法计算钻孔轨迹
+///
+/// 孔口视为深度 0、方向与第一个测点相同；缺失的角度沿用上一测点，左右位移也按沿用的角度计算。
+pub fn trajectory(stations: &[DataList], magnetic_declination: f64) -> Vec<TrajectoryPoint> {
+    let mut points = Vec::with_capacity(stations.len());
+    let (mut north, mut east, mut elevation) = (0.0, 0.0, 0.0);
+    let mut prev_depth = 0.0;
+    let mut prev_pitch = stations.first().and_then(|s| s.pitch).unwrap_or(0.0);
+    let mut prev_heading = corrected_heading(
+        stations.first().and_then(|s| s.heading).unwrap_or(0.0),
+        magnetic_declination,
+    );
+
+    for station in stations {
+        let pitch = station.pitch.unwrap_or(prev_pitch);
+        let heading = station
+            .heading
+            .map(|h| corrected_heading(h, magnetic_declination))
+            .unwrap_or(prev_heading);
+
+        let d1 = direction(prev_pitch, prev_heading);
+        let d2 = direction(pitch, heading);
+        let cos_beta = (d1[0] * d2[0] + d1[1] * d2[1] + d1[2] * d2[2]).clamp(-1.0, 1.0);
+        let beta = cos_beta.acos();
+        // 比例因子，夹角很小时取 1 避免除零
+        let ratio = if beta.abs() < 1e-9 {
+            1.0
+        } else {
+            2.0 / beta * (beta / 2.0).tan()
+        };
+        let course = station.depth - prev_depth;
+        north += course / 2.0 * (d1[0] + d2[0]) * ratio;
+        east += course / 2.0 * (d1[1] + d2[1]) * ratio;
+        elevation += course / 2.0 * (d1[2] + d2[2]) * ratio;
+
+        let dogleg = if course > 0.0 {
+            beta.to_degrees() * DOGLEG_COURSE_LENGTH / course
+        } else {
+            0.0
+        };
+
+        points.push(TrajectoryPoint {
+            depth: station.depth,
+            pitch,
+            heading,
+            north,
+            east,
+            elevation,
+            lateral: lateral(
+                station.depth,
+                pitch,
+                heading,
+                station.design_heading.unwrap_or(0.0),
+            ),
+            vertical: vertical_displacement(station),
+            design_vertical: design_vertical_displacement(station),
+            dogleg,
+        });
+
+        prev_depth = station.depth;
+        prev_pitch = pitch;
+        prev_heading = heading;
+    }
+    points
+}
+
+/// 按设计俯仰角、方位角计算的设计轨迹；有测点缺少设计值时为 None
+///
+/// 设计方位角已是改正后的方位角，不再加磁偏角。
+pub fn design_trajectory(stations: &[DataList]) -> Option<Vec<TrajectoryPoint>> {
+    let has_design = !stations.is_empty()
+        && stations
+            .iter()
+            .all(|s| s.design_pitch.is_some() && s.design_heading.is_some());
+    has_design.then(|| {
+        let design: Vec<DataList> = stations
+            .iter()
+            .map(|s| DataList {
+                pitch: s.design_pitch,
+                heading: s.design_heading,
+                ..s.clone()
+            })
+            .collect();
+        trajectory(&design, 0.0)
+    })
+}
+
+/// 剖面展开方位角：第一个测点的设计方位角，没有设计值时取其改正后的实测方位角
+pub fn section_azimuth(stations: &[DataList], magnetic_declination: f64) -> f64 {
+    stations
+        .first()
+        .and_then(|s| {
+            s.design_heading.or_else(|| {
+                s.heading
+                    .map(|h| corrected_heading(h, magnetic_declination))
+            })
+        })
+        .unwrap_or(0.0)
+}
+
+/// 偏差最大值（绝对值）及其所在深度
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Extreme {
+    pub value: f64,
+    pub depth: f64,
+}
+
+impl Extreme {
+    fn update(&mut self, value: f64, depth: f64) {
+        if value.abs() > self.value {
+            self.value = value.abs();
+            self.depth = depth;
+        }
+    }
+}
+
+/// 轨迹偏差统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviationSummary {
+    pub station_count: usize,
+    // 最后一个测点的深度
+    pub surveyed_depth: f64,
+    pub max_lateral: Extreme,
+    // 实测与设计上下位移之差
+    pub max_vertical: Extreme,
+    // 俯仰角、方位角与设计值之差，没有设计值的测点不参与
+    pub max_pitch: Extreme,
+    pub max_heading: Extreme,
+    pub max_dogleg: Extreme,
+    // 终孔（最后一个测点）的左右、上下位移
+    pub final_lateral: f64,
+    pub final_vertical: f64,
+}
+
+/// 偏差检查项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QaItem {
+    Lateral,
+    Vertical,
+    Pitch,
+    Heading,
+}
+
+/// 单项偏差检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QaCheck {
+    pub item: QaItem,
+    pub max: Extreme,
+    pub tolerance: f64,
+    pub passed: bool,
+}
+
+impl DeviationSummary {
+    /// 按容许值逐项检查
+    pub fn qa_checks(&self, tolerance: &ToleranceSettings) -> Vec<QaCheck> {
+        [
+            (QaItem::Lateral, self.max_lateral, tolerance.lateral),
+            (QaItem::Vertical, self.max_vertical, tolerance.vertical),
+            (QaItem::Pitch, self.max_pitch, tolerance.pitch),
+            (QaItem::Heading, self.max_heading, tolerance.heading),
+        ]
+        .into_iter()
+        .map(|(item, max, tolerance)| QaCheck {
+            item,
+            max,
+            tolerance,
+            passed: max.value <= tolerance,
+        })
+        .collect()
+    }
+
+    /// 是否所有检查项都在容许值内
+    pub fn passes(&self, tolerance: &ToleranceSettings) -> bool {
+        self.qa_checks(tolerance).iter().all(|check| check.passed)
+    }
+}
+
+/// 两个方位角之差，归一化到 [-180, 180)
+fn heading_diff(a: f64, b: f64) -> f64 {
+    (a - b + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// 统计各项偏差的最大值
+pub fn summarize(stations: &[DataList], magnetic_declination: f64) -> DeviationSummary {
+    let points = trajectory(stations, magnetic_declination);
+    let mut summary = DeviationSummary {
+        station_count: stations.len(),
+        ..Default::default()
+    };
+
+    for (station, point) in stations.iter().zip(&points) {
+        summary.max_lateral.update(point.lateral, point.depth);
+        summary
+            .max_vertical
+            .update(point.vertical - point.design_vertical, point.depth);
+        summary.max_dogleg.update(point.dogleg, point.depth);
+        if let (Some(pitch), Some(design)) = (station.pitch, station.design_pitch) {
+            summary.max_pitch.update(pitch - design, point.depth);
+        }
+        if let (Some(heading), Some(design)) = (station.heading, station.design_heading) {
+            summary.max_heading.update(
+                heading_diff(corrected_heading(heading, magnetic_declination), design),
+                point.depth,
+            );
+        }
+    }
+    if let Some(last) = points.last() {
+        summary.surveyed_depth = last.depth;
+        summary.final_lateral = last.lateral;
+        summary.final_vertical = last.vertical;
+    }
+    summary
+}
+
+/// 检查测点数据，返回发现的问题；为空表示通过
+pub fn validate_stations(stations: &[DataList]) -> Vec<String> {
+    let mut problems = Vec::new();
+    if stations.is_empty() {
+        problems.push(t(Msg::StationsEmpty).to_string());
+        return problems;
+    }
+
+    let mut prev_depth: Option<f64> = None;
+    for (index, station) in stations.iter().enumerate() {
+        let no = index + 1;
+        if !station.depth.is_finite() || station.depth < 0.0 {
+            problems.push(tf(
+                Msg::StationDepthInvalid,
+                &[("no", &no), ("depth", &station.depth)],
+            ));
+        }
+        if let Some(prev) = prev_depth {
+            if station.depth <= prev {
+                problems.push(tf(
+                    Msg::StationDepthOrder,
+                    &[("no", &no), ("depth", &station.depth), ("previous", &prev)],
+                ));
+            }
+        }
+        prev_depth = Some(station.depth);
+
+        match station.pitch {
+            None => problems.push(tf(Msg::StationPitchMissing, &[("no", &no)])),
+            Some(p) if !(-90.0..=90.0).contains(&p) => {
+                problems.push(tf(Msg::StationPitchRange, &[("no", &no), ("value", &p)]))
+            }
+            _ => {}
+        }
+        match station.heading {
+            None => problems.push(tf(Msg::StationHeadingMissing, &[("no", &no)])),
+            Some(h) if !(0.0..=360.0).contains(&h) => {
+                problems.push(tf(Msg::StationHeadingRange, &[("no", &no), ("value", &h)]))
+            }
+            _ => {}
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn station(depth: f64, pitch: f64, heading: f64) -> DataList {
+        DataList {
+            id: None,
+            uuid: None,
+            time: None,
+            depth,
+            pitch: Some(pitch),
+            roll: None,
+            heading: Some(heading),
+            repo_id: None,
+            design_pitch: None,
+            design_heading: None,
+        }
+    }
+
+    fn designed(depth: f64, pitch: f64, heading: f64, design: (f64, f64)) -> DataList {
+        DataList {
+            design_pitch: Some(design.0),
+            design_heading: Some(design.1),
+            ..station(depth, pitch, heading)
+        }
+    }
+
+    #[test]
+    fn straight_hole_follows_corrected_heading() {
+        let stations = [station(10.0, 0.0, 100.0), station(20.0, 0.0, 100.0)];
+        let points = trajectory(&stations, 10.0);
+
+        assert_eq!(points.len(), 2);
+        let end = &points[1];
+        assert!((end.heading - 90.0).abs() < EPS);
+        assert!((end.east - 20.0).abs() < 1e-6);
+        assert!(end.north.abs() < 1e-6);
+        assert!(end.elevation.abs() < 1e-6);
+        assert!(end.dogleg.abs() < EPS);
+    }
+
+    #[test]
+    fn vertical_hole_descends() {
+        let stations = [station(15.0, -90.0, 0.0), station(30.0, -90.0, 0.0)];
+        let end = trajectory(&stations, 0.0).pop().unwrap();
+        assert!((end.elevation + 30.0).abs() < 1e-6);
+        assert!(end.north.abs() < 1e-6 && end.east.abs() < 1e-6);
+    }
+
+    #[test]
+    fn missing_angles_reuse_previous_station() {
+        let mut gap = station(20.0, 0.0, 0.0);
+        gap.pitch = None;
+        gap.heading = None;
+        let points = trajectory(&[station(10.0, 5.0, 45.0), gap], 0.0);
+        assert!((points[1].pitch - 5.0).abs() < EPS);
+        assert!((points[1].heading - 45.0).abs() < EPS);
+    }
+
+    #[test]
+    fn dogleg_is_scaled_to_course_length() {
+        let stations = [station(30.0, 0.0, 0.0), station(60.0, 0.0, 3.0)];
+        let points = trajectory(&stations, 0.0);
+        assert!((points[1].dogleg - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn declination_is_applied_to_design_heading() {
+        // 实测方位角 100° 与设计方位角 90° 加磁偏角 10° 一致
+        let stations = [designed(50.0, 0.0, 100.0, (0.0, 90.0))];
+        let point = &trajectory(&stations, 10.0)[0];
+        assert!(point.lateral.abs() < 1e-9);
+
+        let summary = summarize(&stations, 10.0);
+        assert!(summary.max_heading.value < EPS);
+        assert!(summary.max_lateral.value < 1e-9);
+
+        let design = design_trajectory(&stations).unwrap();
+        assert!((design[0].heading - 90.0).abs() < EPS);
+        assert!((design[0].east - point.east).abs() < 1e-6);
+        assert!((section_azimuth(&stations, 10.0) - 90.0).abs() < EPS);
+    }
+
+    #[test]
+    fn lateral_sign_follows_heading_offset() {
+        // 改正后方位角 95° 偏向设计方位角 90° 的右侧
+        let row = designed(100.0, 0.0, 105.0, (0.0, 90.0));
+        let expected = 100.0 * 5f64.to_radians().sin();
+        assert!((lateral_displacement(&row, 10.0) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lateral_matches_frontend_formula() {
+        // calculations.ts：100 · cos(10°) · sin(95° − (90° + (−4.44°)))，手算为 16.152292816…
+        let row = designed(100.0, 10.0, 95.0, (0.0, 90.0));
+        assert!((lateral_displacement(&row, -4.44) - 16.152292816).abs() < 1e-8);
+        let point = &trajectory(std::slice::from_ref(&row), -4.44)[0];
+        assert!((point.lateral - 16.152292816).abs() < 1e-8);
+        assert!((point.heading - 99.44).abs() < EPS);
+        assert!((summarize(&[row], -4.44).max_heading.value - 9.44).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lateral_uses_carried_forward_angles() {
+        let mut gap = designed(20.0, 0.0, 0.0, (0.0, 0.0));
+        gap.pitch = None;
+        gap.heading = None;
+        let points = trajectory(&[designed(10.0, 0.0, 30.0, (0.0, 0.0)), gap], 0.0);
+        assert!((points[1].lateral - 20.0 * 30f64.to_radians().sin()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn summary_tracks_extremes_and_final_station() {
+        let stations = [
+            designed(10.0, 2.0, 358.0, (0.0, 0.0)),
+            designed(20.0, -1.0, 3.0, (0.0, 0.0)),
+            designed(30.0, 0.5, 1.0, (0.0, 0.0)),
+        ];
+        let summary = summarize(&stations, 0.0);
+
+        assert_eq!(summary.station_count, 3);
+        assert!((summary.surveyed_depth - 30.0).abs() < EPS);
+        assert!((summary.max_pitch.value - 2.0).abs() < EPS);
+        assert!((summary.max_pitch.depth - 10.0).abs() < EPS);
+        // 358° 与 0° 相差 2°，不是 358°
+        assert!((summary.max_heading.value - 3.0).abs() < EPS);
+        assert!((summary.max_heading.depth - 20.0).abs() < EPS);
+        let last = &trajectory(&stations, 0.0)[2];
+        assert!((summary.final_lateral - last.lateral).abs() < EPS);
+        assert!((summary.final_vertical - last.vertical).abs() < EPS);
+
+        let tolerance = ToleranceSettings {
+            lateral: 2.0,
+            vertical: 1.0,
+            pitch: 2.0,
+            heading: 3.0,
+        };
+        assert!(summary.passes(&tolerance));
+        let strict = ToleranceSettings {
+            heading: 2.5,
+            ..tolerance
+        };
+        assert!(!summary.passes(&strict));
+    }
+
+    #[test]
+    fn design_trajectory_needs_all_design_values() {
+        let stations = [
+            designed(10.0, 0.0, 0.0, (0.0, 0.0)),
+            station(20.0, 0.0, 0.0),
+        ];
+        assert!(design_trajectory(&stations).is_none());
+        assert!(design_trajectory(&[]).is_none());
+    }
+
+    #[test]
+    fn validate_stations_reports_each_problem() {
+        assert_eq!(validate_stations(&[]).len(), 1);
+        assert!(
+            validate_stations(&[station(10.0, 0.0, 0.0), station(20.0, -5.0, 359.0)]).is_empty()
+        );
+
+        let mut missing = station(30.0, 0.0, 0.0);
+        missing.pitch = None;
+        missing.heading = None;
+        let stations = [
+            station(10.0, 0.0, 0.0),
+            station(10.0, 95.0, 0.0),
+            station(-1.0, 0.0, 400.0),
+            missing,
+        ];
+        // 第 2 个：深度未递增、俯仰角超限；第 3 个：深度为负、深度未递增、方位角超限；
+        // 第 4 个：缺俯仰角、缺方位角
+        assert_eq!(validate_stations(&stations).len(), 7);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::i18n::{t, Msg};
+use crate::services::db::DbError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 错误码，前端据此区分错误类型并本地化提示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    // 数据库未初始化
+    DbNotInitialized,
+    // 违反唯一、外键等约束
+    DbConstraint,
+    // 数据库被占用或锁定
+    DbBusy,
+    // 其他数据库错误
+    Database,
+    // 记录不存在
+    NotFound,
+    // 参数无效
+    InvalidInput,
+    // 文件读写错误
+    Io,
+    // 许可证文件不存在
+    LicenseMissing,
+    // 许可证无效或与本机不匹配
+    LicenseInvalid,
+    // 网络服务错误
+    Network,
+    // 导出失败
+    Export,
+    // 未分类的内部错误
+    Internal,
+}
+
+/// 命令返回给前端的错误
+///
+/// 序列化为 `{ code, message, details }`：`code` 稳定不变，`message` 为按设置语言本地化的提示，
+/// `details` 为底层原因，仅用于排查问题。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl fmt::Display) -> Self {
+        self.details = Some(details.to_string());
+        self
+    }
+
+    /// 用操作说明替换提示，原提示转入 details
+    ///
+    /// 例如 `AppError::from(e).context(t(Msg::ListReposFailed))`。
+    pub fn context(mut self, message: impl Into<String>) -> Self {
+        let cause = match self.details.take() {
+            Some(details) => format!("{}: {}", self.message, details),
+            None => self.message,
+        };
+        self.message = message.into();
+        self.details = Some(cause);
+        self
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.details {
+            Some(details) => write!(f, "{}: {}", self.message, details),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl DbError {
+    /// 对应的错误码
+    pub fn code(&self) -> ErrorCode {
+        let sqlite = match self {
+            DbError::NotInitialized => return ErrorCode::DbNotInitialized,
+            DbError::NotFound(_) => return ErrorCode::NotFound,
+            DbError::Invalid(_) => return ErrorCode::InvalidInput,
+            DbError::Sqlite(e) => Some(e),
+            DbError::Other(e) => sqlite_cause(e),
+        };
+        match sqlite {
+            Some(rusqlite::Error::QueryReturnedNoRows) => ErrorCode::NotFound,
+            Some(e) => match e.sqlite_error_code() {
+                Some(rusqlite::ErrorCode::ConstraintViolation) => ErrorCode::DbConstraint,
+                Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked) => {
+                    ErrorCode::DbBusy
+                }
+                _ => ErrorCode::Database,
+            },
+            None => match self {
+                DbError::Other(e) if e.downcast_ref::<std::io::Error>().is_some() => ErrorCode::Io,
+                _ => ErrorCode::Database,
+            },
+        }
+    }
+}
+
+/// 取出 `conn.call` 等处包装在 anyhow 中的 SQLite 错误
+fn sqlite_cause(e: &anyhow::Error) -> Option<&rusqlite::Error> {
+    e.chain().find_map(|cause| {
+        cause.downcast_ref::<rusqlite::Error>().or_else(|| {
+            match cause.downcast_ref::<tokio_rusqlite::Error>() {
+                Some(tokio_rusqlite::Error::Rusqlite(e)) => Some(e),
+                _ => None,
+            }
+        })
+    })
+}
+
+impl From<DbError> for AppError {
+    fn from(e: DbError) -> Self {
+        let code = e.code();
+        match e {
+            DbError::NotInitialized => AppError::new(code, t(Msg::DbNotInitialized)),
+            DbError::NotFound(message) | DbError::Invalid(message) => AppError::new(code, message),
+            DbError::Sqlite(e) => AppError::new(code, t(Msg::DbOperationFailed)).with_details(e),
+            DbError::Other(e) if code == ErrorCode::Io => {
+                AppError::new(code, t(Msg::IoFailed)).with_details(e)
+            }
+            // 业务层的说明用 NotFound / Invalid 给出，其余错误原文只放在详情中
+            DbError::Other(e) => AppError::new(code, t(Msg::DbOperationFailed)).with_details(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::new(ErrorCode::Io, t(Msg::IoFailed)).with_details(e)
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<DbError>() {
+            Ok(e) => e.into(),
+            Err(e) => match e.downcast::<std::io::Error>() {
+                Ok(e) => e.into(),
+                Err(e) => AppError::new(ErrorCode::Internal, format!("{:#}", e)),
+            },
+        }
+    }
+}
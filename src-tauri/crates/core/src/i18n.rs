@@ -0,0 +1,1015 @@
+//! 后端消息目录（简体中文 / 英文）
+//!
+//! 命令错误、数据接收接口的提示和导出表头通过 [`Msg`] 取文本，语言取自应用设置；
+//! 命令行工具等不加载应用设置的程序用 [`set_locale`] 指定语言。
+
+use crate::services::settings;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 进程指定的语言，优先于应用设置
+static LOCALE_OVERRIDE: OnceCell<Locale> = OnceCell::new();
+
+/// 语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zh-CN" | "zh" => Ok(Locale::ZhCn),
+            "en-US" | "en" => Ok(Locale::EnUs),
+            _ => Err(tf(Msg::UnsupportedLocale, &[("locale", &s)])),
+        }
+    }
+}
+
+/// 指定当前进程的语言，只有第一次调用生效
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE_OVERRIDE.set(locale);
+}
+
+/// 当前语言：进程指定的语言，否则取设置中的语言
+pub fn current_locale() -> Locale {
+    LOCALE_OVERRIDE
+        .get()
+        .copied()
+        .unwrap_or_else(|| settings::current().locale)
+}
+
+/// 当前语言下的消息文本
+pub fn t(msg: Msg) -> &'static str {
+    msg.text(current_locale())
+}
+
+/// 当前语言下的消息文本，`{name}` 占位符替换为对应参数
+pub fn tf(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> String {
+    msg.format(current_locale(), args)
+}
+
+/// 消息键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // 通用错误
+    DbNotInitialized,
+    DbOperationFailed,
+    DbNeedsMigration,
+    IoFailed,
+
+    // 数据库命令
+    InitDatabaseFailed,
+    CloseDatabaseFailed,
+    ListUsersFailed,
+    GetUserFailed,
+    SearchUsersFailed,
+    SaveUserFailed,
+    DeleteUserFailed,
+    ListReposFailed,
+    GetDataListFailed,
+    GetRepoFailed,
+    SearchReposFailed,
+    UpdateNotesFailed,
+    ExportExcelFailed,
+    LoadTemplateFailed,
+    ListTemplatesFailed,
+    BatchExportFailed,
+    BatchExportRunning,
+    ExportDxfFailed,
+    ExportRepoFailed,
+    ExportPdfFailed,
+    ExportPlotFailed,
+    ExportWitsmlFailed,
+    ImportWitsmlFailed,
+    ExportKmlFailed,
+
+    // 数据管理命令
+    ExportArchiveFailed,
+    InspectArchiveFailed,
+    ImportArchiveFailed,
+    BackupDatabaseFailed,
+    ValidateBackupFailed,
+    RestoreDatabaseFailed,
+    ListSnapshotsFailed,
+    CreateSnapshotFailed,
+    PreviewMergeFailed,
+    MergeDatabaseFailed,
+    ListLocationsFailed,
+    CreateLocationFailed,
+    RenameLocationFailed,
+    DeleteLocationFailed,
+    MergeLocationsFailed,
+    GetLocationTreeFailed,
+    GetLocationReposFailed,
+    AssignRepoLocationFailed,
+    GetCurrentProjectFailed,
+    ListProjectsFailed,
+    CreateProjectFailed,
+    SwitchProjectFailed,
+    OpenProjectFailed,
+    RemoveRecentProjectFailed,
+    SaveSettingsFailed,
+    RotateSnapshotsFailed,
+    ReadLogsFailed,
+
+    // 初始化与服务
+    ServerBindFailed,
+    TlsFileMissing,
+    TlsBindFailed,
+    TlsUnsupported,
+    DiscoveryNoAddress,
+    DiscoverySocketFailed,
+    DiscoveryBroadcastFailed,
+    DiscoveryBindFailed,
+    SettingsNotInitialized,
+    SettingsLockPoisoned,
+    TemplatesNotInitialized,
+    LoggingInitFailed,
+    LoggingNotInitialized,
+    SnapshotsNotInitialized,
+    WorkspaceNotInitialized,
+    CurrentDirFailed,
+    UnsupportedLocale,
+
+    // PDF 标志图片
+    LogoReadFailed,
+    LogoInvalid,
+    LogoUnsupportedFormat,
+    JpegUnsupportedComponents,
+    JpegNoSize,
+    PngUnsupportedColor,
+
+    // 许可证命令
+    ExportMachineIdFailed,
+    InvalidLicenseKey,
+    LicensePathUnknown,
+    LicenseMissing,
+    LicenseInvalid,
+    LicenseMismatch,
+    LicenseReadFailed,
+    LicenseVerifyFailed,
+
+    // 网络命令
+    StartDiscoveryFailed,
+    StartServerFailed,
+    StopSignalFailed,
+
+    // 数据接收接口
+    ServerRunning,
+    FetchDataFailed,
+    DbQueryFailed,
+    DbWriteFailed,
+    DataListInsertFailed,
+    RepoReceived,
+    RepoDuplicate,
+    RepoNotFound,
+    WitsmlImported,
+    WitsmlReplaceForbidden,
+
+    // 位置管理
+    LocationNotFound,
+    LocationParentRequired,
+    LocationNameEmpty,
+    LocationNameClash,
+    LocationInUse,
+    LocationHasChildren,
+    LocationMergeParent,
+
+    // 导入
+    ImportedNameSuffix,
+
+    // 归档
+    NoHolesSelected,
+    HoleIdNotFound,
+    ArchiveInvalid,
+    ArchiveMissingFile,
+    ArchiveManifestInvalid,
+    ArchiveUnknownFormat,
+    ArchiveTooNew,
+    ArchiveChecksum,
+    ArchiveDataInvalid,
+
+    // 备份与合并
+    BackupIsCurrent,
+    BackupNotFound,
+    BackupNotDatabase,
+    BackupCorrupt,
+    BackupMissingTable,
+    BackupTooNew,
+    RestoreFromCurrent,
+    MergeSelf,
+
+    // 项目
+    ProjectNameEmpty,
+    ProjectNameInvalid,
+    ProjectExists,
+    ProjectNotFound,
+
+    // 设置校验
+    SettingDiscoveryPort,
+    SettingDataPort,
+    SettingClientPort,
+    SettingPortZero,
+    SettingPortClash,
+    SettingServerNameEmpty,
+    SettingDeclinationRange,
+    SettingToleranceNegative,
+    SettingDecimalPlaces,
+    SettingExportDirRelative,
+    SettingSnapshotRetention,
+    SettingsTooNew,
+
+    // 测点校验
+    StationsEmpty,
+    StationDepthInvalid,
+    StationDepthOrder,
+    StationPitchMissing,
+    StationPitchRange,
+    StationHeadingMissing,
+    StationHeadingRange,
+
+    // 导出参数
+    CsvDecimalSeparator,
+    CsvDelimiterClash,
+    CsvDelimiterInvalid,
+    PlotWidth,
+    PlotHeight,
+    PlotSizeRange,
+    PlotAreaTooLarge,
+    PlotExaggerationInvalid,
+    ProjectionMeridianRange,
+    ProjectionScaleRange,
+    ProjectionOffsetInvalid,
+    ProjectionOutOfZone,
+    ExportNoFormat,
+    ExportNoHoles,
+    CreateDirFailed,
+    HoleContext,
+    KmlNeedsCollar,
+    KmlNeedsProjection,
+    KmlNoCollar,
+    KmlNoCollars,
+    ExcelWriteFailed,
+    ExcelSaveFailed,
+
+    // 报表模板
+    TemplateReadFailed,
+    TemplateParseFailed,
+    TemplateInvalid,
+    TemplateNoSheets,
+    TemplateSheetKindDuplicate,
+    TemplateSheetNameLength,
+    TemplateSheetNameChars,
+    TemplateSheetNameClash,
+    TemplateNoColumns,
+    TemplateChartsNeedStations,
+    TemplateChartsNeedDepth,
+    TemplateColumnWidth,
+    TemplateNotFound,
+
+    // WITSML 校验
+    WitsmlParseFailed,
+    WitsmlUnbalanced,
+    WitsmlNoRoot,
+    WitsmlRootName,
+    WitsmlNamespace,
+    WitsmlVersion,
+    WitsmlNoTrajectories,
+    WitsmlInvalid,
+    WitsmlTrajectory,
+    WitsmlStation,
+    WitsmlMissing,
+    WitsmlRepeated,
+    WitsmlTooLong,
+    WitsmlMissingAttr,
+    WitsmlAttrTooLong,
+    WitsmlNotNumber,
+    WitsmlMissingUom,
+    WitsmlUnsupportedUom,
+    WitsmlBadTimestamp,
+    WitsmlBadValue,
+    WitsmlDuplicateUid,
+    WitsmlNegative,
+    WitsmlOutOfRange,
+    WitsmlNoStations,
+
+    // 命令行工具
+    CliDelimiterInvalid,
+    CliDateFormat,
+    CliDateOrder,
+    CliHolesNotFound,
+    CliHoleInvalid,
+    CliHoleCount,
+    CliNoMatchingHoles,
+    CliHolesInvalid,
+    CliCollarsReadFailed,
+    CliCollarsInvalid,
+    CliLabelIntervalNegative,
+    CliLogoNotFound,
+    CliFilesExported,
+    CliHolesNotExported,
+    CliPlotExtension,
+    CliNoHolesToPlot,
+    CliWriteFailed,
+    CliHolesPlotted,
+    CliHolesNotPlotted,
+    CliKmlNeedsProjection,
+    CliHoleNoCollar,
+    CliHolesExported,
+    CliHolesSkipped,
+    CliError,
+    CliCloseWarning,
+
+    // 导出表头
+    ColIndex,
+    ColDepth,
+    ColPitch,
+    ColHeading,
+    ColLateral,
+    ColVertical,
+    ColDesignLateral,
+    ColDesignVertical,
+    ColCadPlan,
+    ColCadProfile,
+    ColNorth,
+    ColEast,
+    ColElevation,
+    ColDogleg,
+
+    // 报表
+    SheetCover,
+    SheetStations,
+    SheetTrajectory,
+    SheetSummary,
+    ReportTitle,
+    SectionHole,
+    SectionParameters,
+    FieldHoleName,
+    FieldSurveyTime,
+    FieldMine,
+    FieldWorkingFace,
+    FieldDrillingSite,
+    FieldRig,
+    FieldHoleLength,
+    FieldNotes,
+    FieldStationCount,
+    FieldSurveyedDepth,
+    FieldDeclination,
+    FieldDecimalPlaces,
+    FieldMethod,
+    MethodMinimumCurvature,
+    FieldGeneratedAt,
+    FieldToleranceLateral,
+    FieldToleranceVertical,
+    FieldTolerancePitch,
+    FieldToleranceHeading,
+    ColTime,
+    ColRoll,
+    ColDesignPitch,
+    ColDesignHeading,
+    ColCorrectedHeading,
+    ColItem,
+    ColMaxDeviation,
+    ColUnit,
+    ColAtDepth,
+    ColTolerance,
+    ColVerdict,
+    QaLateral,
+    QaVertical,
+    QaPitch,
+    QaHeading,
+    MaxDogleg,
+    FinalLateral,
+    FinalVertical,
+    QaVerdict,
+    VerdictPass,
+    VerdictFail,
+    SheetCharts,
+    SheetIndex,
+    ColSheet,
+    ChartPlanTitle,
+    ChartProfileTitle,
+    SeriesActual,
+    SeriesDesign,
+    FieldCollar,
+    SectionSignatures,
+    SignSurveyor,
+    SignReviewer,
+    SignApprover,
+    FieldSignature,
+    FieldDate,
+    PageOf,
+    PlotPlanTitle,
+    PlotProfileTitle,
+    PlotIsometricTitle,
+    PlotSectionDistance,
+    PlotVerticalExaggeration,
+    FieldLongitudeLatitude,
+    KmlTrajectory,
+    KmlUnspecified,
+}
+
+impl Msg {
+    /// 指定语言下的文本
+    pub fn text(self, locale: Locale) -> &'static str {
+        let (zh, en) = match self {
+            Msg::DbNotInitialized => ("数据库未初始化", "Database is not initialized"),
+            Msg::DbOperationFailed => ("数据库操作失败", "Database operation failed"),
+            Msg::DbNeedsMigration => (
+                "数据库结构版本 {version} 低于当前程序（{latest}），请先用桌面程序或数据服务器打开一次以完成升级",
+                "Database schema version {version} is older than this program ({latest}); open it once with the desktop app or data server to upgrade it",
+            ),
+            Msg::IoFailed => ("文件读写失败", "File read or write failed"),
+
+            Msg::InitDatabaseFailed => ("数据库初始化失败", "Failed to initialize database"),
+            Msg::CloseDatabaseFailed => ("关闭数据库失败", "Failed to close database"),
+            Msg::ListUsersFailed => ("获取用户列表失败", "Failed to list users"),
+            Msg::GetUserFailed => ("获取用户失败", "Failed to get user"),
+            Msg::SearchUsersFailed => ("搜索用户失败", "Failed to search users"),
+            Msg::SaveUserFailed => ("保存用户失败", "Failed to save user"),
+            Msg::DeleteUserFailed => ("删除用户失败", "Failed to delete user"),
+            Msg::ListReposFailed => ("获取repo列表失败", "Failed to list holes"),
+            Msg::GetDataListFailed => ("获取 data_list 数据失败", "Failed to load survey stations"),
+            Msg::GetRepoFailed => ("获取repo失败", "Failed to get hole"),
+            Msg::SearchReposFailed => ("检索repo失败", "Failed to search holes"),
+            Msg::UpdateNotesFailed => ("更新备注失败", "Failed to update notes"),
+            Msg::ExportExcelFailed => ("导出Excel失败", "Failed to export Excel file"),
+            Msg::LoadTemplateFailed => ("加载报表模板失败", "Failed to load report template"),
+            Msg::ListTemplatesFailed => ("获取报表模板列表失败", "Failed to list report templates"),
+            Msg::BatchExportFailed => ("批量导出失败", "Batch export failed"),
+            Msg::ExportDxfFailed => ("导出DXF失败", "Failed to export DXF file"),
+            Msg::ExportRepoFailed => ("导出钻孔失败", "Failed to export hole"),
+            Msg::ExportPdfFailed => ("导出PDF报告失败", "Failed to export PDF report"),
+            Msg::ExportPlotFailed => ("导出轨迹图失败", "Failed to export trajectory plot"),
+            Msg::ExportWitsmlFailed => ("导出WITSML失败", "Failed to export WITSML"),
+            Msg::ImportWitsmlFailed => ("导入WITSML失败", "Failed to import WITSML"),
+            Msg::ExportKmlFailed => ("导出KML失败", "Failed to export KML"),
+
+            Msg::ExportArchiveFailed => ("导出归档失败", "Failed to export archive"),
+            Msg::InspectArchiveFailed => ("读取归档失败", "Failed to read archive"),
+            Msg::ImportArchiveFailed => ("导入归档失败", "Failed to import archive"),
+            Msg::BackupDatabaseFailed => ("备份数据库失败", "Failed to back up the database"),
+            Msg::ValidateBackupFailed => ("校验备份失败", "Failed to validate the backup"),
+            Msg::RestoreDatabaseFailed => ("恢复数据库失败", "Failed to restore the database"),
+            Msg::ListSnapshotsFailed => ("获取快照列表失败", "Failed to list snapshots"),
+            Msg::CreateSnapshotFailed => ("生成快照失败", "Failed to create a snapshot"),
+            Msg::PreviewMergeFailed => ("比对数据库失败", "Failed to compare databases"),
+            Msg::MergeDatabaseFailed => ("合并数据库失败", "Failed to merge the database"),
+            Msg::ListLocationsFailed => ("获取位置列表失败", "Failed to list locations"),
+            Msg::CreateLocationFailed => ("新建位置失败", "Failed to create location"),
+            Msg::RenameLocationFailed => ("重命名位置失败", "Failed to rename location"),
+            Msg::DeleteLocationFailed => ("删除位置失败", "Failed to delete location"),
+            Msg::MergeLocationsFailed => ("合并位置失败", "Failed to merge locations"),
+            Msg::GetLocationTreeFailed => ("获取位置树失败", "Failed to load the location tree"),
+            Msg::GetLocationReposFailed => ("获取repo列表失败", "Failed to list holes"),
+            Msg::AssignRepoLocationFailed => ("更新repo位置失败", "Failed to update the hole location"),
+            Msg::GetCurrentProjectFailed => ("获取当前项目失败", "Failed to get the current project"),
+            Msg::ListProjectsFailed => ("获取项目列表失败", "Failed to list projects"),
+            Msg::CreateProjectFailed => ("新建项目失败", "Failed to create project"),
+            Msg::SwitchProjectFailed => ("切换项目失败", "Failed to switch project"),
+            Msg::OpenProjectFailed => ("打开项目失败", "Failed to open project"),
+            Msg::RemoveRecentProjectFailed => ("移除最近项目失败", "Failed to remove the recent project"),
+            Msg::SaveSettingsFailed => ("保存设置失败", "Failed to save settings"),
+            Msg::RotateSnapshotsFailed => ("清理旧快照失败", "Failed to remove old snapshots"),
+            Msg::ReadLogsFailed => ("读取日志失败", "Failed to read logs"),
+
+            Msg::ServerBindFailed => (
+                "无法监听端口 {port}: {error}",
+                "Cannot listen on port {port}: {error}",
+            ),
+            Msg::TlsFileMissing => ("TLS 文件不存在: {path}", "TLS file not found: {path}"),
+            Msg::TlsBindFailed => (
+                "无法在端口 {port} 启动 TLS 服务: {error}",
+                "Cannot start the TLS service on port {port}: {error}",
+            ),
+            Msg::TlsUnsupported => ("当前构建未启用 TLS 支持", "This build does not include TLS support"),
+            Msg::DiscoveryNoAddress => ("无法获取本机IP地址", "Could not determine a local IP address"),
+            Msg::DiscoverySocketFailed => (
+                "无法创建UDP套接字: {error}",
+                "Cannot create a UDP socket: {error}",
+            ),
+            Msg::DiscoveryBroadcastFailed => (
+                "无法设置广播模式: {error}",
+                "Cannot enable UDP broadcast: {error}",
+            ),
+            Msg::DiscoveryBindFailed => (
+                "无法绑定UDP监听端口 {port}: {error}",
+                "Cannot bind UDP port {port}: {error}",
+            ),
+            Msg::SettingsNotInitialized => ("设置未初始化", "Settings are not initialized"),
+            Msg::SettingsLockPoisoned => ("设置锁已损坏", "The settings lock is poisoned"),
+            Msg::TemplatesNotInitialized => (
+                "模板目录未初始化",
+                "The template directory is not initialized",
+            ),
+            Msg::LoggingInitFailed => ("日志初始化失败: {error}", "Failed to initialize logging: {error}"),
+            Msg::LoggingNotInitialized => ("日志未初始化", "Logging is not initialized"),
+            Msg::SnapshotsNotInitialized => (
+                "快照目录未初始化",
+                "The snapshot directory is not initialized",
+            ),
+            Msg::WorkspaceNotInitialized => ("工作区未初始化", "The workspace is not initialized"),
+            Msg::CurrentDirFailed => (
+                "无法获取当前目录: {error}",
+                "Cannot get the current directory: {error}",
+            ),
+            Msg::UnsupportedLocale => (
+                "不支持的语言: {locale}（可选 zh-CN、en-US）",
+                "Unsupported language: {locale} (choose zh-CN or en-US)",
+            ),
+
+            Msg::LogoReadFailed => ("无法读取标志图片 {path}", "Cannot read the logo image {path}"),
+            Msg::LogoInvalid => ("标志图片无效 {path}", "Invalid logo image {path}"),
+            Msg::LogoUnsupportedFormat => (
+                "标志图片只支持 JPEG 和 PNG: {path}",
+                "The logo image must be JPEG or PNG: {path}",
+            ),
+            Msg::JpegUnsupportedComponents => (
+                "不支持 {count} 个颜色分量的 JPEG",
+                "JPEG images with {count} color components are not supported",
+            ),
+            Msg::JpegNoSize => ("找不到 JPEG 图像尺寸", "The JPEG image has no size information"),
+            Msg::PngUnsupportedColor => (
+                "不支持的 PNG 颜色类型 {color}",
+                "Unsupported PNG color type {color}",
+            ),
+            Msg::BatchExportRunning => {
+                ("已有批量导出正在进行", "A batch export is already running")
+            }
+
+            Msg::ExportMachineIdFailed => ("导出机器ID失败", "Failed to export machine ID"),
+            Msg::InvalidLicenseKey => ("无效的许可证密钥格式", "Invalid license key format"),
+            Msg::LicensePathUnknown => (
+                "无法确定许可证文件路径",
+                "Cannot determine license file location",
+            ),
+            Msg::LicenseMissing => ("许可证文件不存在", "License file not found"),
+            Msg::LicenseInvalid => ("许可证无效", "License is invalid"),
+            Msg::LicenseMismatch => (
+                "许可证无效或与此机器不匹配",
+                "License is invalid or does not match this machine",
+            ),
+            Msg::LicenseReadFailed => ("无法读取许可证文件", "Cannot read license file"),
+            Msg::LicenseVerifyFailed => ("许可证校验失败", "License verification failed"),
+
+            Msg::StartDiscoveryFailed => (
+                "启动局域网发现服务失败",
+                "Failed to start LAN discovery service",
+            ),
+            Msg::StartServerFailed => ("启动数据服务器失败", "Failed to start data server"),
+            Msg::StopSignalFailed => ("无法发送停止信号", "Failed to send stop signal"),
+
+            Msg::ServerRunning => ("数据服务器正在运行", "Data server is running"),
+            Msg::FetchDataFailed => ("获取数据失败", "Failed to fetch data"),
+            Msg::DbQueryFailed => ("数据库查询失败", "Database query failed"),
+            Msg::DbWriteFailed => ("数据库写入失败", "Database write failed"),
+            Msg::DataListInsertFailed => ("data_list 插入失败", "Failed to store survey stations"),
+            Msg::RepoReceived => (
+                "repo 和 data_list 已接收并存储",
+                "Hole and survey stations received and stored",
+            ),
+            Msg::RepoDuplicate => (
+                "repo 已存在，忽略重复上传",
+                "Hole already exists, duplicate upload ignored",
+            ),
+            Msg::RepoNotFound => ("repo 不存在", "Hole not found"),
+            Msg::WitsmlImported => ("WITSML 轨迹已导入", "WITSML trajectories imported"),
+            Msg::WitsmlReplaceForbidden => (
+                "网络上传不能覆盖已有钻孔，请使用 skip 或 keep_both，或在桌面程序中导入",
+                "Network uploads cannot overwrite existing holes; use skip or keep_both, or import in the desktop app",
+            ),
+
+            Msg::LocationNotFound => ("{level}不存在: {id}", "{level} not found: {id}"),
+            Msg::LocationParentRequired => (
+                "新建{level}需要指定所属{parent}",
+                "{level} requires a parent {parent}",
+            ),
+            Msg::LocationNameEmpty => ("{level}名称不能为空", "{level} name cannot be empty"),
+            Msg::LocationNameClash => (
+                "已存在同名{level}（ID {id}），请使用合并",
+                "{level} with the same name already exists (ID {id}), merge them instead",
+            ),
+            Msg::LocationInUse => (
+                "{level}仍被 {count} 个钻孔引用，无法删除",
+                "{level} is still used by {count} holes and cannot be deleted",
+            ),
+            Msg::LocationHasChildren => (
+                "{level}下仍有 {count} 个{child}，无法删除",
+                "{level} still contains {count} entries of {child} and cannot be deleted",
+            ),
+            Msg::LocationMergeParent => (
+                "只能合并同一上级下的{level}",
+                "Only entries of {level} under the same parent can be merged",
+            ),
+
+            Msg::ImportedNameSuffix => (" (导入)", " (imported)"),
+
+            Msg::NoHolesSelected => ("未选择要导出的钻孔", "No holes selected for export"),
+            Msg::HoleIdNotFound => ("钻孔不存在: {id}", "Hole not found: {id}"),
+            Msg::ArchiveInvalid => ("不是有效的归档文件: {error}", "Not a valid archive: {error}"),
+            Msg::ArchiveMissingFile => ("归档缺少文件: {name}", "Archive is missing {name}"),
+            Msg::ArchiveManifestInvalid => (
+                "归档清单格式错误: {error}",
+                "Archive manifest is malformed: {error}",
+            ),
+            Msg::ArchiveUnknownFormat => ("未知的归档格式: {format}", "Unknown archive format: {format}"),
+            Msg::ArchiveTooNew => (
+                "归档来自更新版本的程序（格式版本 {version}），请先升级",
+                "The archive was written by a newer version (format {version}); please upgrade first",
+            ),
+            Msg::ArchiveChecksum => ("归档文件校验失败: {path}", "Archive checksum mismatch: {path}"),
+            Msg::ArchiveDataInvalid => ("归档数据格式错误: {error}", "Archive data is malformed: {error}"),
+
+            Msg::BackupIsCurrent => (
+                "备份路径不能是当前数据库文件",
+                "The backup path cannot be the current database file",
+            ),
+            Msg::BackupNotFound => ("备份文件不存在: {path}", "Backup file not found: {path}"),
+            Msg::BackupNotDatabase => ("不是有效的数据库文件: {error}", "Not a valid database file: {error}"),
+            Msg::BackupCorrupt => ("备份文件已损坏: {detail}", "The backup file is corrupt: {detail}"),
+            Msg::BackupMissingTable => (
+                "备份文件缺少数据表: {table}",
+                "The backup file is missing table {table}",
+            ),
+            Msg::BackupTooNew => (
+                "备份文件来自更新版本的程序（结构版本 {version}），请先升级",
+                "The backup was written by a newer version (schema {version}); please upgrade first",
+            ),
+            Msg::RestoreFromCurrent => (
+                "不能从当前数据库文件恢复",
+                "Cannot restore from the current database file",
+            ),
+            Msg::MergeSelf => ("不能与当前数据库自身合并", "Cannot merge the current database into itself"),
+
+            Msg::ProjectNameEmpty => ("项目名称不能为空", "Project name cannot be empty"),
+            Msg::ProjectNameInvalid => (
+                "项目名称包含非法字符: {name}",
+                "Project name contains invalid characters: {name}",
+            ),
+            Msg::ProjectExists => ("项目已存在: {name}", "Project already exists: {name}"),
+            Msg::ProjectNotFound => ("项目不存在: {name}", "Project not found: {name}"),
+
+            Msg::SettingDiscoveryPort => ("发现服务端口", "Discovery port"),
+            Msg::SettingDataPort => ("数据服务端口", "Data server port"),
+            Msg::SettingClientPort => ("客户端端口", "Client port"),
+            Msg::SettingPortZero => ("{field}不能为 0", "{field} cannot be 0"),
+            Msg::SettingPortClash => (
+                "发现服务端口与数据服务端口不能相同",
+                "The discovery port and the data server port must differ",
+            ),
+            Msg::SettingServerNameEmpty => ("服务器名称不能为空", "Server name cannot be empty"),
+            Msg::SettingDeclinationRange => (
+                "磁偏角应在 -180 到 180 度之间",
+                "Magnetic declination must be between -180 and 180 degrees",
+            ),
+            Msg::SettingToleranceNegative => ("{field}不能为负数", "{field} cannot be negative"),
+            Msg::SettingDecimalPlaces => ("小数位数不能超过 10", "Decimal places cannot exceed 10"),
+            Msg::SettingExportDirRelative => (
+                "默认导出目录必须是绝对路径",
+                "The default export directory must be an absolute path",
+            ),
+            Msg::SettingSnapshotRetention => ("快照保留份数至少为 1", "At least 1 snapshot must be kept"),
+            Msg::SettingsTooNew => (
+                "设置文件来自更新版本的程序（版本 {version}），请先升级",
+                "The settings file was written by a newer version ({version}); please upgrade first",
+            ),
+
+            Msg::StationsEmpty => ("没有测点数据", "No survey stations"),
+            Msg::StationDepthInvalid => (
+                "第 {no} 个测点深度无效: {depth}",
+                "Station {no}: invalid depth {depth}",
+            ),
+            Msg::StationDepthOrder => (
+                "第 {no} 个测点深度 {depth} 未大于上一测点 {previous}",
+                "Station {no}: depth {depth} is not greater than the previous station ({previous})",
+            ),
+            Msg::StationPitchMissing => ("第 {no} 个测点缺少俯仰角", "Station {no}: pitch is missing"),
+            Msg::StationPitchRange => (
+                "第 {no} 个测点俯仰角超出范围: {value}",
+                "Station {no}: pitch out of range: {value}",
+            ),
+            Msg::StationHeadingMissing => ("第 {no} 个测点缺少方位角", "Station {no}: azimuth is missing"),
+            Msg::StationHeadingRange => (
+                "第 {no} 个测点方位角超出范围: {value}",
+                "Station {no}: azimuth out of range: {value}",
+            ),
+
+            Msg::CsvDecimalSeparator => ("小数点只能是 . 或 ,", "The decimal separator must be . or ,"),
+            Msg::CsvDelimiterClash => (
+                "CSV 分隔符不能与小数点相同",
+                "The CSV delimiter cannot be the decimal separator",
+            ),
+            Msg::CsvDelimiterInvalid => (
+                "无效的 CSV 分隔符: {delimiter}",
+                "Invalid CSV delimiter: {delimiter}",
+            ),
+            Msg::PlotWidth => ("宽度", "width"),
+            Msg::PlotHeight => ("高度", "height"),
+            Msg::PlotSizeRange => (
+                "图片{field}应在 200 到 8000 像素之间",
+                "Image {field} must be between 200 and 8000 pixels",
+            ),
+            Msg::PlotAreaTooLarge => (
+                "图片宽度与高度的乘积不能超过 4096×4096 像素",
+                "Image width × height must not exceed 4096 × 4096 pixels",
+            ),
+            Msg::PlotExaggerationInvalid => (
+                "高程放大倍数必须大于 0",
+                "Vertical exaggeration must be greater than 0",
+            ),
+            Msg::ProjectionMeridianRange => (
+                "中央子午线应在 -180° 到 180° 之间",
+                "The central meridian must be between -180° and 180°",
+            ),
+            Msg::ProjectionScaleRange => (
+                "投影比例因子应在 0.9 到 1.1 之间",
+                "The scale factor must be between 0.9 and 1.1",
+            ),
+            Msg::ProjectionOffsetInvalid => (
+                "假东距与假北距必须是有效数字",
+                "False easting and false northing must be valid numbers",
+            ),
+            Msg::ProjectionOutOfZone => (
+                "坐标 E {east} N {north} 超出投影带范围，请检查中央子午线与假东距（东坐标是否含带号）",
+                "Coordinates E {east} N {north} are outside the projection zone; check the central meridian and false easting (does the easting include the zone number?)",
+            ),
+            Msg::ExportNoFormat => ("未指定导出格式", "No export format selected"),
+            Msg::ExportNoHoles => ("没有可导出的钻孔", "No holes to export"),
+            Msg::CreateDirFailed => (
+                "无法创建输出目录 {path}: {error}",
+                "Cannot create output directory {path}: {error}",
+            ),
+            Msg::HoleContext => ("钻孔 {name}", "Hole {name}"),
+            Msg::KmlNeedsCollar => (
+                "KML 导出需要钻孔 {name} 的孔口坐标",
+                "KML export needs collar coordinates for hole {name}",
+            ),
+            Msg::KmlNeedsProjection => (
+                "KML 导出需要指定孔口坐标的投影（中央子午线等）",
+                "KML export needs the collar projection (central meridian etc.)",
+            ),
+            Msg::KmlNoCollar => ("没有孔口坐标", "No collar coordinates"),
+            Msg::KmlNoCollars => (
+                "没有指定孔口坐标的钻孔，无法导出 KML",
+                "No hole has collar coordinates; cannot export KML",
+            ),
+            Msg::ExcelWriteFailed => ("写入Excel失败", "Failed to write the workbook"),
+            Msg::ExcelSaveFailed => ("保存Excel文件失败", "Failed to save the Excel file"),
+
+            Msg::TemplateReadFailed => ("无法读取模板文件 {path}", "Cannot read template file {path}"),
+            Msg::TemplateParseFailed => ("模板文件格式错误 {path}", "Template file is malformed: {path}"),
+            Msg::TemplateInvalid => ("模板文件无效 {path}", "Template file is invalid: {path}"),
+            Msg::TemplateNoSheets => ("模板至少需要一个工作表", "A template needs at least one sheet"),
+            Msg::TemplateSheetKindDuplicate => (
+                "工作表类型 {kind} 重复",
+                "Sheet type {kind} appears more than once",
+            ),
+            Msg::TemplateSheetNameLength => (
+                "工作表名称应为 1 到 {max} 个字符",
+                "Sheet names must be 1 to {max} characters",
+            ),
+            Msg::TemplateSheetNameChars => (
+                "工作表名称不能包含 [ ] : * ? / \\ : {name}",
+                "Sheet names cannot contain [ ] : * ? / \\ : {name}",
+            ),
+            Msg::TemplateSheetNameClash => ("工作表名称重复: {name}", "Duplicate sheet name: {name}"),
+            Msg::TemplateNoColumns => (
+                "测点数据页至少需要一列",
+                "The stations sheet needs at least one column",
+            ),
+            Msg::TemplateChartsNeedStations => (
+                "轨迹图引用测点数据页，模板中缺少测点数据页",
+                "The charts sheet refers to the stations sheet, which the template lacks",
+            ),
+            Msg::TemplateChartsNeedDepth => (
+                "轨迹图以深度为横轴，测点数据页缺少深度列",
+                "The charts plot against depth, but the stations sheet has no depth column",
+            ),
+            Msg::TemplateColumnWidth => (
+                "列宽应在 0 到 255 之间",
+                "Column width must be between 0 and 255",
+            ),
+            Msg::TemplateNotFound => ("报表模板不存在: {name}", "Report template not found: {name}"),
+
+            Msg::WitsmlParseFailed => (
+                "WITSML 文档无法解析: {error}",
+                "Cannot parse the WITSML document: {error}",
+            ),
+            Msg::WitsmlUnbalanced => ("XML 结构错误", "Malformed XML structure"),
+            Msg::WitsmlNoRoot => ("文档中没有根元素", "The document has no root element"),
+            Msg::WitsmlRootName => (
+                "根元素应为 trajectorys，实际为 {name}（只支持 trajectory 对象）",
+                "The root element must be trajectorys, found {name} (only trajectory objects are supported)",
+            ),
+            Msg::WitsmlNamespace => (
+                "根元素缺少命名空间 {namespace}",
+                "The root element lacks the namespace {namespace}",
+            ),
+            Msg::WitsmlVersion => (
+                "只支持 1.4.1 版本，文档版本为 {version}",
+                "Only version 1.4.1 is supported, the document is version {version}",
+            ),
+            Msg::WitsmlNoTrajectories => (
+                "文档中没有 trajectory",
+                "The document contains no trajectory",
+            ),
+            Msg::WitsmlInvalid => (
+                "WITSML 校验未通过: {problems}",
+                "WITSML validation failed: {problems}",
+            ),
+            Msg::WitsmlTrajectory => ("第 {no} 个 trajectory: ", "trajectory {no}: "),
+            Msg::WitsmlStation => ("第 {no} 个测点: ", "station {no}: "),
+            Msg::WitsmlMissing => ("缺少 {name}", "{name} is missing"),
+            Msg::WitsmlRepeated => ("{name} 只能出现一次", "{name} must occur at most once"),
+            Msg::WitsmlTooLong => ("{name} 超过 {max} 个字符", "{name} exceeds {max} characters"),
+            Msg::WitsmlMissingAttr => ("缺少属性 {name}", "attribute {name} is missing"),
+            Msg::WitsmlAttrTooLong => (
+                "属性 {name} 超过 {max} 个字符",
+                "attribute {name} exceeds {max} characters",
+            ),
+            Msg::WitsmlNotNumber => (
+                "{name} 不是有效数值: {value}",
+                "{name} is not a valid number: {value}",
+            ),
+            Msg::WitsmlMissingUom => ("{name} 缺少计量单位 uom", "{name} has no uom"),
+            Msg::WitsmlUnsupportedUom => (
+                "{name} 的计量单位不受支持: {uom}",
+                "unsupported uom for {name}: {uom}",
+            ),
+            Msg::WitsmlBadTimestamp => (
+                "{name} 不是有效时间: {value}",
+                "{name} is not a valid timestamp: {value}",
+            ),
+            Msg::WitsmlBadValue => ("{name} 取值无效: {value}", "invalid {name}: {value}"),
+            Msg::WitsmlDuplicateUid => ("uid 重复: {uid}", "duplicate uid: {uid}"),
+            Msg::WitsmlNegative => ("{name} 不能为负数: {value}", "{name} cannot be negative: {value}"),
+            Msg::WitsmlOutOfRange => (
+                "{name} 超出 {min} ~ {max} 度: {value}",
+                "{name} is outside {min} to {max} degrees: {value}",
+            ),
+            Msg::WitsmlNoStations => ("没有 trajectoryStation", "no trajectoryStation"),
+
+            Msg::CliDelimiterInvalid => ("分隔符必须是单个字符", "The delimiter must be a single character"),
+            Msg::CliDateFormat => ("日期格式应为 YYYY-MM-DD: {date}", "Dates must be YYYY-MM-DD: {date}"),
+            Msg::CliDateOrder => (
+                "起始日期 {from} 晚于截止日期 {to}",
+                "Start date {from} is after end date {to}",
+            ),
+            Msg::CliHolesNotFound => ("钻孔不存在: {ids}", "Holes not found: {ids}"),
+            Msg::CliHoleInvalid => (
+                "钻孔 {id} ({name}) 校验未通过: {problem}",
+                "Hole {id} ({name}) failed validation: {problem}",
+            ),
+            Msg::CliHoleCount => ("共 {count} 个钻孔", "{count} holes"),
+            Msg::CliNoMatchingHoles => ("没有符合条件的钻孔", "No holes match the filter"),
+            Msg::CliHolesInvalid => ("{count} 个钻孔数据校验未通过", "{count} holes failed validation"),
+            Msg::CliCollarsReadFailed => (
+                "无法读取孔口坐标文件 {path}: {error}",
+                "Cannot read collar file {path}: {error}",
+            ),
+            Msg::CliCollarsInvalid => (
+                "孔口坐标文件格式错误 {path}: {error}",
+                "Collar file is malformed {path}: {error}",
+            ),
+            Msg::CliLabelIntervalNegative => ("标注间隔不能为负数", "The label interval cannot be negative"),
+            Msg::CliLogoNotFound => ("找不到标志图片 {path}", "Logo image not found: {path}"),
+            Msg::CliFilesExported => ("已导出 {count} 个文件", "{count} files exported"),
+            Msg::CliHolesNotExported => (
+                "{count} 个钻孔数据校验未通过，未导出",
+                "{count} holes failed validation and were not exported",
+            ),
+            Msg::CliPlotExtension => (
+                "输出文件的扩展名应为 .svg 或 .png",
+                "The output file extension must be .svg or .png",
+            ),
+            Msg::CliNoHolesToPlot => ("没有可绘制的钻孔", "No holes to plot"),
+            Msg::CliWriteFailed => ("无法写入 {path}: {error}", "Cannot write {path}: {error}"),
+            Msg::CliHolesPlotted => ("已绘制 {count} 个钻孔", "{count} holes plotted"),
+            Msg::CliHolesNotPlotted => (
+                "{count} 个钻孔数据校验未通过，未绘制",
+                "{count} holes failed validation and were not plotted",
+            ),
+            Msg::CliKmlNeedsProjection => (
+                "导出 KML 需要用 --central-meridian 指定孔口坐标的投影",
+                "KML export needs the collar projection; set it with --central-meridian",
+            ),
+            Msg::CliHoleNoCollar => (
+                "钻孔 {id} ({name}) 没有孔口坐标，未导出",
+                "Hole {id} ({name}) has no collar coordinates and was not exported",
+            ),
+            Msg::CliHolesExported => ("已导出 {count} 个钻孔", "{count} holes exported"),
+            Msg::CliHolesSkipped => ("{count} 个钻孔未导出", "{count} holes were not exported"),
+            Msg::CliError => ("错误: {error}", "Error: {error}"),
+            Msg::CliCloseWarning => (
+                "警告: 关闭数据库失败: {error}",
+                "Warning: failed to close the database: {error}",
+            ),
+
+            Msg::ColIndex => ("序号", "No."),
+            Msg::ColDepth => ("深度", "Depth"),
+            Msg::ColPitch => ("俯仰角", "Pitch"),
+            Msg::ColHeading => ("方位角", "Azimuth"),
+            Msg::ColLateral => ("左右位移", "Lateral offset"),
+            Msg::ColVertical => ("上下位移", "Vertical offset"),
+            Msg::ColDesignLateral => ("左右位移(设计)", "Lateral offset (design)"),
+            Msg::ColDesignVertical => ("上下位移(设计)", "Vertical offset (design)"),
+            Msg::ColCadPlan => ("CAD平面坐标", "CAD plan coordinates"),
+            Msg::ColCadProfile => ("CAD剖面坐标", "CAD profile coordinates"),
+            Msg::ColNorth => ("北坐标", "Northing"),
+            Msg::ColEast => ("东坐标", "Easting"),
+            Msg::ColElevation => ("高程", "Elevation"),
+            Msg::ColDogleg => ("狗腿度", "Dogleg"),
+
+            Msg::SheetCover => ("封面", "Cover"),
+            Msg::SheetStations => ("测点数据", "Stations"),
+            Msg::SheetTrajectory => ("轨迹计算", "Trajectory"),
+            Msg::SheetSummary => ("偏差汇总", "Summary"),
+            Msg::ReportTitle => ("钻孔轨迹测量报告", "Borehole Trajectory Survey Report"),
+            Msg::SectionHole => ("钻孔信息", "Hole information"),
+            Msg::SectionParameters => ("计算参数", "Calculation parameters"),
+            Msg::FieldHoleName => ("钻孔名称", "Hole name"),
+            Msg::FieldSurveyTime => ("测量时间", "Survey time"),
+            Msg::FieldMine => ("矿井", "Mine"),
+            Msg::FieldWorkingFace => ("工作面", "Working face"),
+            Msg::FieldDrillingSite => ("钻场", "Drilling site"),
+            Msg::FieldRig => ("钻机", "Rig"),
+            Msg::FieldHoleLength => ("孔深", "Hole length"),
+            Msg::FieldNotes => ("备注", "Notes"),
+            Msg::FieldStationCount => ("测点数", "Stations"),
+            Msg::FieldSurveyedDepth => ("测量深度", "Surveyed depth"),
+            Msg::FieldDeclination => ("磁偏角", "Magnetic declination"),
+            Msg::FieldDecimalPlaces => ("坐标小数位数", "Coordinate decimal places"),
+            Msg::FieldMethod => ("计算方法", "Calculation method"),
+            Msg::MethodMinimumCurvature => ("最小曲率法", "Minimum curvature"),
+            Msg::FieldGeneratedAt => ("生成时间", "Generated at"),
+            Msg::FieldToleranceLateral => ("左右位移容许值", "Lateral offset tolerance"),
+            Msg::FieldToleranceVertical => ("上下位移容许值", "Vertical offset tolerance"),
+            Msg::FieldTolerancePitch => ("俯仰角容许偏差", "Pitch tolerance"),
+            Msg::FieldToleranceHeading => ("方位角容许偏差", "Azimuth tolerance"),
+            Msg::ColTime => ("测量时间", "Time"),
+            Msg::ColRoll => ("翻滚角", "Roll"),
+            Msg::ColDesignPitch => ("设计俯仰角", "Design pitch"),
+            Msg::ColDesignHeading => ("设计方位角", "Design azimuth"),
+            Msg::ColCorrectedHeading => ("方位角(已修正)", "Azimuth (corrected)"),
+            Msg::ColItem => ("项目", "Item"),
+            Msg::ColMaxDeviation => ("最大偏差", "Max deviation"),
+            Msg::ColUnit => ("单位", "Unit"),
+            Msg::ColAtDepth => ("所在深度", "At depth"),
+            Msg::ColTolerance => ("容许值", "Tolerance"),
+            Msg::ColVerdict => ("结论", "Verdict"),
+            Msg::QaLateral => ("左右位移偏差", "Lateral deviation"),
+            Msg::QaVertical => ("上下位移偏差", "Vertical deviation"),
+            Msg::QaPitch => ("俯仰角偏差", "Pitch deviation"),
+            Msg::QaHeading => ("方位角偏差", "Azimuth deviation"),
+            Msg::MaxDogleg => ("最大狗腿度", "Max dogleg"),
+            Msg::FinalLateral => ("终孔左右位移", "Final lateral offset"),
+            Msg::FinalVertical => ("终孔上下位移", "Final vertical offset"),
+            Msg::QaVerdict => ("质量结论", "QA verdict"),
+            Msg::VerdictPass => ("合格", "Pass"),
+            Msg::VerdictFail => ("超限", "Out of tolerance"),
+            Msg::SheetCharts => ("轨迹图", "Charts"),
+            Msg::SheetIndex => ("索引", "Index"),
+            Msg::ColSheet => ("工作表", "Sheet"),
+            Msg::ChartPlanTitle => ("平面图（左右位移）", "Plan view (lateral offset)"),
+            Msg::ChartProfileTitle => ("剖面图（上下位移）", "Profile view (vertical offset)"),
+            Msg::SeriesActual => ("实测", "Actual"),
+            Msg::SeriesDesign => ("设计", "Design"),
+            Msg::FieldCollar => ("孔口坐标", "Collar coordinates"),
+            Msg::SectionSignatures => ("签字", "Sign-off"),
+            Msg::SignSurveyor => ("测量人", "Surveyed by"),
+            Msg::SignReviewer => ("审核人", "Reviewed by"),
+            Msg::SignApprover => ("批准人", "Approved by"),
+            Msg::FieldSignature => ("签名", "Signature"),
+            Msg::FieldDate => ("日期", "Date"),
+            Msg::PageOf => ("第 {page} 页 / 共 {pages} 页", "Page {page} of {pages}"),
+            Msg::PlotPlanTitle => ("平面图", "Plan view"),
+            Msg::PlotProfileTitle => ("剖面图", "Profile view"),
+            Msg::PlotIsometricTitle => ("等轴测图", "Isometric view"),
+            Msg::PlotSectionDistance => ("沿剖面水平距离", "Distance along section"),
+            Msg::PlotVerticalExaggeration => ("高程放大", "vertical exaggeration"),
+            Msg::FieldLongitudeLatitude => ("经纬度", "Longitude, latitude"),
+            Msg::KmlTrajectory => ("轨迹", "trajectory"),
+            Msg::KmlUnspecified => ("未指定", "Unspecified"),
+        };
+        match locale {
+            Locale::ZhCn => zh,
+            Locale::EnUs => en,
+        }
+    }
+
+    /// 指定语言下的文本，`{name}` 占位符替换为对应参数
+    pub fn format(self, locale: Locale, args: &[(&str, &dyn fmt::Display)]) -> String {
+        args.iter()
+            .fold(self.text(locale).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), &value.to_string())
+            })
+    }
+}
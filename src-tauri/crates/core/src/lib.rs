@@ -0,0 +1,9 @@
+//! DrillTrack 核心库：数据库、数据接收路由、局域网发现及轨迹计算
+//!
+//! 桌面程序（Tauri）与无界面服务器 `drilltrack-server` 共用。
+
+pub mod calc;
+pub mod error;
+pub mod i18n;
+pub mod models;
+pub mod services;
@@ -0,0 +1,131 @@
+use crate::models::repo::new_uuid;
+use crate::services::db::{check_db_connection, get_conn_with_retry, DbError};
+use rusqlite::{params, Connection, Result, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataList {
+    pub id: Option<i32>,
+
+    // 全局唯一标识，缺失时由服务端生成
+    #[serde(default)]
+    pub uuid: Option<String>,
+
+    pub time: Option<String>,
+    // 深度
+    pub depth: f64,
+
+    // （俯仰角）：描述物体绕横轴的旋转。可以理解为物体的"前后倾斜"。
+    pub pitch: Option<f64>,
+
+    // （翻滚角）：描述物体绕纵轴的旋转。可以理解为物体的"左右倾斜"。
+    pub roll: Option<f64>,
+
+    // （方位角）：描述物体绕垂直轴的旋转。可以理解为物体的"朝向"。
+    pub heading: Option<f64>,
+
+    #[serde(alias = "repoId")]
+    pub repo_id: Option<i32>,
+
+    // 设计俯仰角
+    #[serde(alias = "designPitch")]
+    pub design_pitch: Option<f64>,
+
+    // 设计方位角
+    #[serde(alias = "designHeading")]
+    pub design_heading: Option<f64>,
+}
+
+impl DataList {
+    pub async fn insert_data(repo: DataList) -> Result<i64, DbError> {
+        // 检查数据库连接
+        check_db_connection().await?;
+
+        let conn_guard = get_conn_with_retry(3).await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        let repo = repo.clone();
+        debug!(id = ?repo.id, "插入记录");
+        conn.call(move |c| DataList::insert_with(c, &repo))
+            .await
+            .map_err(|e| DbError::Other(e.into()))
+    }
+
+    /// 按 `list_with` 的列顺序构造 DataList
+    pub fn from_row(row: &Row) -> Result<DataList> {
+        Ok(DataList {
+            id: Some(row.get(0)?),
+            time: row.get(1)?,
+            depth: row.get(2)?,
+            pitch: row.get(3)?,
+            roll: row.get(4)?,
+            heading: row.get(5)?,
+            repo_id: Some(row.get(6)?),
+            design_pitch: row.get(7)?,
+            design_heading: row.get(8)?,
+            uuid: row.get(9)?,
+        })
+    }
+
+    /// 在给定连接（或事务）上按深度顺序查询某个 repo 的测点数据
+    pub fn list_with(c: &Connection, repo_id: i64) -> Result<Vec<DataList>> {
+        let mut stmt = c.prepare(
+            "SELECT id, time, depth, pitch, roll, heading, repo_id, design_pitch, design_heading, uuid
+             FROM data_list
+             WHERE repo_id = ?
+             ORDER BY depth",
+        )?;
+        let rows = stmt.query_map(params![repo_id], DataList::from_row)?;
+        let mut result = Vec::new();
+        for data in rows {
+            result.push(data?);
+        }
+        Ok(result)
+    }
+
+    /// 在给定连接（或事务）上插入一条测点数据，整数 ID 由数据库分配
+    pub fn insert_with(c: &Connection, data: &DataList) -> Result<i64> {
+        let uuid = data.uuid.clone().unwrap_or_else(new_uuid);
+        c.execute(
+            "
+            INSERT INTO data_list (uuid, time, depth, pitch, roll, heading, repo_id, design_pitch, design_heading)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+            params![
+                uuid,
+                data.time,
+                data.depth,
+                data.pitch,
+                data.roll,
+                data.heading,
+                data.repo_id,
+                data.design_pitch,
+                data.design_heading
+            ],
+        )?;
+        Ok(c.last_insert_rowid())
+    }
+
+    /// 测点数据指纹，用于判断两份数据是否一致（忽略 id 与 repo_id）
+    pub fn fingerprint(stations: &[DataList]) -> String {
+        let mut sorted: Vec<&DataList> = stations.iter().collect();
+        sorted.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+
+        let fmt = |v: Option<f64>| v.map(|v| format!("{:.6}", v)).unwrap_or_default();
+        let mut hasher = Sha256::new();
+        for s in sorted {
+            hasher.update(format!(
+                "{:.6};{};{};{};{};{}\n",
+                s.depth,
+                fmt(s.pitch),
+                fmt(s.roll),
+                fmt(s.heading),
+                fmt(s.design_pitch),
+                fmt(s.design_heading)
+            ));
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
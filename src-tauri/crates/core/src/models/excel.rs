@@ -0,0 +1,715 @@
+use crate::calc::{self, DeviationSummary, QaCheck, QaItem, TrajectoryPoint};
+use crate::i18n::{t, Locale, Msg};
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use crate::services::records::{records_from_points, FieldValue};
+use crate::services::settings::ToleranceSettings;
+use crate::services::templates::{ColumnField, ReportTemplate, SheetKind, ValueKind};
+use anyhow::{Context, Result};
+use rust_xlsxwriter::{
+    Chart, ChartFormat, ChartLegendPosition, ChartLine, ChartLineDashType, ChartType, Color,
+    Format, FormatAlign, FormatBorder, Workbook, Worksheet, XlsxError,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExcelData {
+    pub file_path: String,
+    pub data: Vec<DataList>,
+    pub magnetic_declination: String,
+    // CAD 坐标保留的小数位数
+    #[serde(default = "default_decimal_places")]
+    pub decimal_places: usize,
+    // 表头语言
+    #[serde(default)]
+    pub locale: Locale,
+    // 钻孔信息，写入封面
+    #[serde(default)]
+    pub repo: Option<Repo>,
+    // 偏差容许值，用于汇总页的质量结论
+    #[serde(default)]
+    pub tolerance: ToleranceSettings,
+    // 报表模板，决定工作表、测点数据页的列及标题行
+    #[serde(default)]
+    pub template: ReportTemplate,
+}
+
+fn default_decimal_places() -> usize {
+    5
+}
+
+// 长度、角度、狗腿度单位
+const UNIT_M: &str = "m";
+const UNIT_DEG: &str = "°";
+const UNIT_DOGLEG: &str = "°/30m";
+
+/// 报表中用到的单元格格式
+struct Formats {
+    title: Format,
+    subtitle: Format,
+    section: Format,
+    label: Format,
+    header: Format,
+    text: Format,
+    angle: Format,
+    length: Format,
+    coordinate: Format,
+    pass: Format,
+    fail: Format,
+}
+
+impl Formats {
+    fn new(decimal_places: usize) -> Self {
+        let coordinate_format = if decimal_places == 0 {
+            "0".to_string()
+        } else {
+            format!("0.{}", "0".repeat(decimal_places))
+        };
+        let cell = || Format::new().set_border(FormatBorder::Thin);
+        Self {
+            title: Format::new()
+                .set_bold()
+                .set_font_size(16.0)
+                .set_align(FormatAlign::Center),
+            subtitle: Format::new().set_align(FormatAlign::Center),
+            section: Format::new()
+                .set_bold()
+                .set_background_color(Color::RGB(0xD9E1F2)),
+            label: cell().set_bold(),
+            header: cell()
+                .set_bold()
+                .set_text_wrap()
+                .set_align(FormatAlign::Center)
+                .set_background_color(Color::RGB(0xD9E1F2)),
+            text: cell(),
+            angle: cell().set_num_format("0.00"),
+            length: cell().set_num_format("0.000"),
+            coordinate: cell().set_num_format(&coordinate_format),
+            pass: cell().set_bold().set_font_color(Color::RGB(0x006100)),
+            fail: cell().set_bold().set_font_color(Color::RGB(0x9C0006)),
+        }
+    }
+}
+
+/// 写表头行、设置列宽并冻结表头
+fn write_headers(
+    sheet: &mut Worksheet,
+    formats: &Formats,
+    row: u32,
+    headers: &[(String, f64)],
+) -> Result<(), XlsxError> {
+    for (col, (header, width)) in headers.iter().enumerate() {
+        let col = col as u16;
+        sheet.write_string_with_format(row, col, header, &formats.header)?;
+        sheet.set_column_width(col, *width)?;
+    }
+    sheet.set_freeze_panes(row + 1, 0)?;
+    Ok(())
+}
+
+impl Formats {
+    /// 模板列的单元格格式，模板未指定数字格式时按数值类别选择
+    fn column(&self, kind: ValueKind, num_format: Option<&str>) -> Format {
+        match (num_format, kind) {
+            (Some(num_format), _) => self.text.clone().set_num_format(num_format),
+            (None, ValueKind::Text) => self.text.clone(),
+            (None, ValueKind::Length) => self.length.clone(),
+            (None, ValueKind::Angle) => self.angle.clone(),
+            (None, ValueKind::Coordinate) => self.coordinate.clone(),
+        }
+    }
+}
+
+impl ExcelData {
+    /// 按模板生成报表工作簿，默认模板为封面、测点数据、轨迹计算、偏差汇总及轨迹图
+    pub async fn save_to_excel(&self) -> Result<()> {
+        let mut workbook = Workbook::new();
+        self.write_report(&mut workbook)
+            .context(t(Msg::ExcelWriteFailed))?;
+        workbook
+            .save(&self.file_path)
+            .context(t(Msg::ExcelSaveFailed))?;
+        Ok(())
+    }
+
+    fn write_report(&self, workbook: &mut Workbook) -> Result<(), XlsxError> {
+        let formats = Formats::new(self.decimal_places);
+        let declination = self.declination();
+        let points = calc::trajectory(&self.data, declination);
+        let summary = calc::summarize(&self.data, declination);
+
+        for sheet in &self.template.sheets {
+            match sheet.kind {
+                SheetKind::Cover => {
+                    self.write_cover(workbook.add_worksheet(), &formats, &summary)?
+                }
+                SheetKind::Stations => self.write_stations(
+                    workbook.add_worksheet(),
+                    &formats,
+                    &self.sheet_name(SheetKind::Stations),
+                    &points,
+                )?,
+                SheetKind::Trajectory => {
+                    self.write_trajectory(workbook.add_worksheet(), &formats, &points)?
+                }
+                SheetKind::Summary => {
+                    self.write_summary(workbook.add_worksheet(), &formats, &summary)?
+                }
+                SheetKind::Charts if !self.data.is_empty() => {
+                    self.write_charts(workbook.add_worksheet())?
+                }
+                SheetKind::Charts => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn sheet_name(&self, kind: SheetKind) -> String {
+        self.template.sheet_name(kind, self.locale)
+    }
+
+    /// 测点数据页标题行数，表头位于其下
+    fn title_row_count(&self) -> u32 {
+        self.template.title_rows.len() as u32
+    }
+
+    /// 替换标题行中的占位符
+    fn title_text(&self, row: &str) -> String {
+        let field =
+            |f: fn(&Repo) -> &String| self.repo.as_ref().map(f).cloned().unwrap_or_default();
+        [
+            ("{name}", field(|r| &r.name)),
+            ("{time}", field(|r| &r.mn_time)),
+            ("{mine}", field(|r| &r.mine)),
+            ("{face}", field(|r| &r.work)),
+            ("{site}", field(|r| &r.factory)),
+            ("{rig}", field(|r| &r.drilling)),
+            ("{declination}", self.declination().to_string()),
+        ]
+        .iter()
+        .fold(row.to_string(), |text, (placeholder, value)| {
+            text.replace(placeholder, value)
+        })
+    }
+
+    fn text(&self, msg: Msg) -> &'static str {
+        msg.text(self.locale)
+    }
+
+    /// 带单位的表头，如 `深度 (m)`
+    fn header(&self, msg: Msg, unit: &str) -> String {
+        format!("{} ({})", self.text(msg), unit)
+    }
+
+    // 封面：钻孔信息与计算参数
+    fn write_cover(
+        &self,
+        sheet: &mut Worksheet,
+        formats: &Formats,
+        summary: &DeviationSummary,
+    ) -> Result<(), XlsxError> {
+        sheet.set_name(self.sheet_name(SheetKind::Cover))?;
+        sheet.set_column_width(0, 24.0)?;
+        sheet.set_column_width(1, 40.0)?;
+        sheet.merge_range(0, 0, 0, 1, self.text(Msg::ReportTitle), &formats.title)?;
+
+        let repo = self.repo.as_ref();
+        let field = |f: fn(&Repo) -> String| repo.map(f).unwrap_or_default();
+        let hole = [
+            (Msg::FieldHoleName, field(|r| r.name.clone())),
+            (Msg::FieldSurveyTime, field(|r| r.mn_time.clone())),
+            (Msg::FieldMine, field(|r| r.mine.clone())),
+            (Msg::FieldWorkingFace, field(|r| r.work.clone())),
+            (Msg::FieldDrillingSite, field(|r| r.factory.clone())),
+            (Msg::FieldRig, field(|r| r.drilling.clone())),
+            (
+                Msg::FieldHoleLength,
+                field(|r| format!("{} {}", r.len, UNIT_M)),
+            ),
+            (Msg::FieldStationCount, summary.station_count.to_string()),
+            (
+                Msg::FieldSurveyedDepth,
+                format!("{:.2} {}", summary.surveyed_depth, UNIT_M),
+            ),
+            (
+                Msg::FieldNotes,
+                field(|r| r.notes.clone().unwrap_or_default()),
+            ),
+        ];
+
+        let tolerance = &self.tolerance;
+        let parameters = [
+            (
+                Msg::FieldMethod,
+                self.text(Msg::MethodMinimumCurvature).to_string(),
+            ),
+            (
+                Msg::FieldDeclination,
+                format!("{} {}", self.declination(), UNIT_DEG),
+            ),
+            (Msg::FieldDecimalPlaces, self.decimal_places.to_string()),
+            (
+                Msg::FieldToleranceLateral,
+                format!("{} {}", tolerance.lateral, UNIT_M),
+            ),
+            (
+                Msg::FieldToleranceVertical,
+                format!("{} {}", tolerance.vertical, UNIT_M),
+            ),
+            (
+                Msg::FieldTolerancePitch,
+                format!("{} {}", tolerance.pitch, UNIT_DEG),
+            ),
+            (
+                Msg::FieldToleranceHeading,
+                format!("{} {}", tolerance.heading, UNIT_DEG),
+            ),
+            (
+                Msg::FieldGeneratedAt,
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+        ];
+
+        let mut row = 2;
+        for (section, fields) in [
+            (Msg::SectionHole, &hole[..]),
+            (Msg::SectionParameters, &parameters[..]),
+        ] {
+            sheet.merge_range(row, 0, row, 1, self.text(section), &formats.section)?;
+            row += 1;
+            for (label, value) in fields {
+                sheet.write_string_with_format(row, 0, self.text(*label), &formats.label)?;
+                sheet.write_string_with_format(row, 1, value, &formats.text)?;
+                row += 1;
+            }
+            row += 1;
+        }
+        Ok(())
+    }
+
+    // 测点数据：按模板的列输出测量值及计算结果，表头上方为标题行
+    fn write_stations(
+        &self,
+        sheet: &mut Worksheet,
+        formats: &Formats,
+        name: &str,
+        points: &[TrajectoryPoint],
+    ) -> Result<(), XlsxError> {
+        sheet.set_name(name)?;
+        let columns = &self.template.columns;
+        let last_col = columns.len().saturating_sub(1) as u16;
+        for (row, title) in self.template.title_rows.iter().enumerate() {
+            let row = row as u32;
+            let format = if row == 0 {
+                &formats.title
+            } else {
+                &formats.subtitle
+            };
+            let title = self.title_text(title);
+            if last_col > 0 {
+                sheet.merge_range(row, 0, row, last_col, &title, format)?;
+            } else {
+                sheet.write_string_with_format(row, 0, &title, format)?;
+            }
+        }
+
+        let header_row = self.title_row_count();
+        let headers: Vec<(String, f64)> = columns
+            .iter()
+            .map(|c| (c.header_text(self.locale), c.width()))
+            .collect();
+        write_headers(sheet, formats, header_row, &headers)?;
+
+        let cell_formats: Vec<Format> = columns
+            .iter()
+            .map(|c| formats.column(c.field.kind(), c.num_format.as_deref()))
+            .collect();
+        for (index, record) in records_from_points(&self.data, points).iter().enumerate() {
+            let row_num = header_row + 1 + index as u32;
+            for (col, (column, format)) in columns.iter().zip(&cell_formats).enumerate() {
+                let col = col as u16;
+                match record.value(column.field) {
+                    FieldValue::Number(value) => {
+                        sheet.write_number_with_format(row_num, col, value, format)?
+                    }
+                    FieldValue::Text(value) => {
+                        sheet.write_string_with_format(row_num, col, value, format)?
+                    }
+                    FieldValue::CadPoint(x, y) => sheet.write_string_with_format(
+                        row_num,
+                        col,
+                        FieldValue::cad_point(x, y, self.decimal_places),
+                        format,
+                    )?,
+                    FieldValue::Blank => sheet.write_blank(row_num, col, format)?,
+                };
+            }
+        }
+        Ok(())
+    }
+
+    // 轨迹计算：最小曲率法得到的坐标和狗腿度
+    fn write_trajectory(
+        &self,
+        sheet: &mut Worksheet,
+        formats: &Formats,
+        points: &[TrajectoryPoint],
+    ) -> Result<(), XlsxError> {
+        sheet.set_name(self.sheet_name(SheetKind::Trajectory))?;
+        let headers = [
+            (self.header(Msg::ColDepth, UNIT_M), 10.0),
+            (self.header(Msg::ColPitch, UNIT_DEG), 10.0),
+            (self.header(Msg::ColCorrectedHeading, UNIT_DEG), 14.0),
+            (self.header(Msg::ColNorth, UNIT_M), 12.0),
+            (self.header(Msg::ColEast, UNIT_M), 12.0),
+            (self.header(Msg::ColElevation, UNIT_M), 12.0),
+            (self.header(Msg::ColLateral, UNIT_M), 12.0),
+            (self.header(Msg::ColVertical, UNIT_M), 12.0),
+            (self.header(Msg::ColDesignVertical, UNIT_M), 14.0),
+            (self.header(Msg::ColDogleg, UNIT_DOGLEG), 12.0),
+        ];
+        write_headers(sheet, formats, 0, &headers)?;
+
+        for (index, p) in points.iter().enumerate() {
+            let row = (index + 1) as u32;
+            let values = [
+                (p.depth, &formats.length),
+                (p.pitch, &formats.angle),
+                (p.heading, &formats.angle),
+                (p.north, &formats.coordinate),
+                (p.east, &formats.coordinate),
+                (p.elevation, &formats.coordinate),
+                (p.lateral, &formats.coordinate),
+                (p.vertical, &formats.coordinate),
+                (p.design_vertical, &formats.coordinate),
+                (p.dogleg, &formats.angle),
+            ];
+            for (col, (value, format)) in values.into_iter().enumerate() {
+                sheet.write_number_with_format(row, col as u16, value, format)?;
+            }
+        }
+        Ok(())
+    }
+
+    // 偏差汇总：各项最大偏差与容许值比较，给出质量结论
+    fn write_summary(
+        &self,
+        sheet: &mut Worksheet,
+        formats: &Formats,
+        summary: &DeviationSummary,
+    ) -> Result<(), XlsxError> {
+        sheet.set_name(self.sheet_name(SheetKind::Summary))?;
+        let headers = [
+            (self.text(Msg::ColItem).to_string(), 20.0),
+            (self.text(Msg::ColMaxDeviation).to_string(), 12.0),
+            (self.text(Msg::ColUnit).to_string(), 8.0),
+            (self.header(Msg::ColAtDepth, UNIT_M), 12.0),
+            (self.text(Msg::ColTolerance).to_string(), 10.0),
+            (self.text(Msg::ColVerdict).to_string(), 16.0),
+        ];
+        write_headers(sheet, formats, 0, &headers)?;
+
+        let verdict = |passed: bool| {
+            if passed {
+                (self.text(Msg::VerdictPass), &formats.pass)
+            } else {
+                (self.text(Msg::VerdictFail), &formats.fail)
+            }
+        };
+
+        let mut row = 1;
+        for check in summary.qa_checks(&self.tolerance) {
+            let (label, unit, format) = match check.item {
+                QaItem::Lateral => (Msg::QaLateral, UNIT_M, &formats.coordinate),
+                QaItem::Vertical => (Msg::QaVertical, UNIT_M, &formats.coordinate),
+                QaItem::Pitch => (Msg::QaPitch, UNIT_DEG, &formats.angle),
+                QaItem::Heading => (Msg::QaHeading, UNIT_DEG, &formats.angle),
+            };
+            let (verdict_text, verdict_format) = verdict(check.passed);
+            sheet.write_string_with_format(row, 0, self.text(label), &formats.label)?;
+            sheet.write_number_with_format(row, 1, check.max.value, format)?;
+            sheet.write_string_with_format(row, 2, unit, &formats.text)?;
+            sheet.write_number_with_format(row, 3, check.max.depth, &formats.length)?;
+            sheet.write_number_with_format(row, 4, check.tolerance, format)?;
+            sheet.write_string_with_format(row, 5, verdict_text, verdict_format)?;
+            row += 1;
+        }
+
+        // 狗腿度没有容许值，仅列出
+        sheet.write_string_with_format(row, 0, self.text(Msg::MaxDogleg), &formats.label)?;
+        sheet.write_number_with_format(row, 1, summary.max_dogleg.value, &formats.angle)?;
+        sheet.write_string_with_format(row, 2, UNIT_DOGLEG, &formats.text)?;
+        sheet.write_number_with_format(row, 3, summary.max_dogleg.depth, &formats.length)?;
+        sheet.write_blank(row, 4, &formats.text)?;
+        sheet.write_blank(row, 5, &formats.text)?;
+        row += 2;
+
+        let totals = [
+            (
+                Msg::FieldStationCount,
+                summary.station_count as f64,
+                "",
+                &formats.text,
+            ),
+            (
+                Msg::FieldSurveyedDepth,
+                summary.surveyed_depth,
+                UNIT_M,
+                &formats.length,
+            ),
+            (
+                Msg::FinalLateral,
+                summary.final_lateral,
+                UNIT_M,
+                &formats.coordinate,
+            ),
+            (
+                Msg::FinalVertical,
+                summary.final_vertical,
+                UNIT_M,
+                &formats.coordinate,
+            ),
+        ];
+        for (label, value, unit, format) in totals {
+            sheet.write_string_with_format(row, 0, self.text(label), &formats.label)?;
+            sheet.write_number_with_format(row, 1, value, format)?;
+            sheet.write_string_with_format(row, 2, unit, &formats.text)?;
+            row += 1;
+        }
+        row += 1;
+
+        let (verdict_text, verdict_format) = verdict(summary.passes(&self.tolerance));
+        sheet.write_string_with_format(row, 0, self.text(Msg::QaVerdict), &formats.label)?;
+        sheet.write_string_with_format(row, 1, verdict_text, verdict_format)?;
+        Ok(())
+    }
+
+    // 轨迹图：平面图（左右位移）与剖面图（上下位移），实测与设计对比
+    //
+    // 数据引用测点数据页中的列，模板未包含某列时省略对应的曲线或图表。
+    fn write_charts(&self, sheet: &mut Worksheet) -> Result<(), XlsxError> {
+        sheet.set_name(self.sheet_name(SheetKind::Charts))?;
+        sheet.set_landscape();
+
+        let template = &self.template;
+        let depth_col = match template.column_of(ColumnField::Depth) {
+            Some(col) => col,
+            None => return Ok(()),
+        };
+        let stations = self.sheet_name(SheetKind::Stations);
+        let first_row = self.title_row_count() + 1;
+        let last_row = self.title_row_count() + self.data.len() as u32;
+        let charts = [
+            (
+                Msg::ChartPlanTitle,
+                Msg::ColLateral,
+                ColumnField::Lateral,
+                ColumnField::DesignLateral,
+            ),
+            (
+                Msg::ChartProfileTitle,
+                Msg::ColVertical,
+                ColumnField::Vertical,
+                ColumnField::DesignVertical,
+            ),
+        ];
+
+        let mut position = 0;
+        for (title, axis, actual, design) in charts {
+            let series = [
+                (Msg::SeriesActual, template.column_of(actual), false),
+                (Msg::SeriesDesign, template.column_of(design), true),
+            ];
+            if series.iter().all(|(_, col, _)| col.is_none()) {
+                continue;
+            }
+
+            let mut chart = Chart::new(ChartType::ScatterStraightWithMarkers);
+            chart.title().set_name(self.text(title));
+            chart.x_axis().set_name(&self.header(Msg::ColDepth, UNIT_M));
+            chart.y_axis().set_name(&self.header(axis, UNIT_M));
+            chart.legend().set_position(ChartLegendPosition::Bottom);
+            chart.set_width(900).set_height(400);
+
+            let depth = (stations.as_str(), first_row, depth_col, last_row, depth_col);
+            for (name, col, dashed) in series {
+                let col = match col {
+                    Some(col) => col,
+                    None => continue,
+                };
+                let series = chart
+                    .add_series()
+                    .set_name(self.text(name))
+                    .set_categories(depth)
+                    .set_values((stations.as_str(), first_row, col, last_row, col));
+                if dashed {
+                    series.set_format(
+                        ChartFormat::new()
+                            .set_line(ChartLine::new().set_dash_type(ChartLineDashType::Dash)),
+                    );
+                }
+            }
+
+            sheet.insert_chart(1 + position * 21, 1, &chart)?;
+            position += 1;
+        }
+        Ok(())
+    }
+
+    fn declination(&self) -> f64 {
+        self.magnetic_declination.parse::<f64>().unwrap_or(0.0)
+    }
+}
+
+/// 多钻孔汇总工作簿：首页为钻孔索引，其后每个钻孔一页测点数据
+pub struct BatchWorkbook {
+    workbook: Workbook,
+    file_path: String,
+    locale: Locale,
+    // 已写入的钻孔：工作表名称、钻孔信息、偏差汇总及逐项检查结果
+    entries: Vec<(String, Option<Repo>, DeviationSummary, Vec<QaCheck>)>,
+}
+
+impl BatchWorkbook {
+    pub fn new(file_path: impl Into<String>, locale: Locale) -> Result<Self> {
+        let mut workbook = Workbook::new();
+        workbook
+            .add_worksheet()
+            .set_name(Msg::SheetIndex.text(locale))
+            .context(t(Msg::ExcelWriteFailed))?;
+        Ok(Self {
+            workbook,
+            file_path: file_path.into(),
+            locale,
+            entries: Vec::new(),
+        })
+    }
+
+    /// 添加一个钻孔的测点数据页，列与标题行取自该钻孔的报表模板
+    pub fn add_hole(&mut self, hole: &ExcelData) -> Result<()> {
+        let name = self.sheet_name(hole);
+        let formats = Formats::new(hole.decimal_places);
+        let declination = hole.declination();
+        let points = calc::trajectory(&hole.data, declination);
+        let summary = calc::summarize(&hole.data, declination);
+        hole.write_stations(self.workbook.add_worksheet(), &formats, &name, &points)
+            .context(t(Msg::ExcelWriteFailed))?;
+
+        let checks = summary.qa_checks(&hole.tolerance);
+        self.entries
+            .push((name, hole.repo.clone(), summary, checks));
+        Ok(())
+    }
+
+    /// 写入索引页并保存
+    pub fn save(mut self) -> Result<()> {
+        self.write_index().context(t(Msg::ExcelWriteFailed))?;
+        self.workbook
+            .save(&self.file_path)
+            .context(t(Msg::ExcelSaveFailed))?;
+        Ok(())
+    }
+
+    /// 工作表名称 `{id}-{名称}`，去掉 Excel 不允许的字符并截断到 31 个字符，重名时追加序号
+    fn sheet_name(&self, hole: &ExcelData) -> String {
+        let base: String = match &hole.repo {
+            Some(repo) => format!("{}-{}", repo.id.unwrap_or_default(), repo.name.trim()),
+            None => (self.entries.len() + 1).to_string(),
+        }
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .take(31)
+        .collect();
+
+        let taken = |name: &str| {
+            self.entries
+                .iter()
+                .any(|(used, ..)| used.to_lowercase() == name.to_lowercase())
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while taken(&name) || name == Msg::SheetIndex.text(self.locale) {
+            let tag = format!("({})", suffix);
+            let keep = 31 - tag.chars().count();
+            name = base.chars().take(keep).collect::<String>() + &tag;
+            suffix += 1;
+        }
+        name
+    }
+
+    // 索引页：每个钻孔一行，列出基本信息、最大偏差和质量结论
+    fn write_index(&mut self) -> Result<(), XlsxError> {
+        let locale = self.locale;
+        let text = |msg: Msg| msg.text(locale);
+        let formats = Formats::new(3);
+        let sheet = self.workbook.worksheet_from_index(0)?;
+
+        let headers = [
+            (text(Msg::ColIndex).to_string(), 6.0),
+            (text(Msg::ColSheet).to_string(), 20.0),
+            (text(Msg::FieldHoleName).to_string(), 16.0),
+            (text(Msg::FieldSurveyTime).to_string(), 20.0),
+            (text(Msg::FieldMine).to_string(), 12.0),
+            (text(Msg::FieldWorkingFace).to_string(), 12.0),
+            (text(Msg::FieldDrillingSite).to_string(), 12.0),
+            (text(Msg::FieldRig).to_string(), 12.0),
+            (text(Msg::FieldStationCount).to_string(), 8.0),
+            (
+                format!("{} ({})", text(Msg::FieldSurveyedDepth), UNIT_M),
+                12.0,
+            ),
+            (format!("{} ({})", text(Msg::QaLateral), UNIT_M), 14.0),
+            (format!("{} ({})", text(Msg::QaVertical), UNIT_M), 14.0),
+            (format!("{} ({})", text(Msg::FinalLateral), UNIT_M), 14.0),
+            (format!("{} ({})", text(Msg::FinalVertical), UNIT_M), 14.0),
+            (text(Msg::QaVerdict).to_string(), 12.0),
+        ];
+        write_headers(sheet, &formats, 0, &headers)?;
+
+        for (index, (name, repo, summary, checks)) in self.entries.iter().enumerate() {
+            let row = (index + 1) as u32;
+            let field = |f: fn(&Repo) -> &String| repo.as_ref().map(f).cloned().unwrap_or_default();
+            sheet.write_number_with_format(row, 0, (index + 1) as f64, &formats.text)?;
+            let texts = [
+                name.clone(),
+                field(|r| &r.name),
+                field(|r| &r.mn_time),
+                field(|r| &r.mine),
+                field(|r| &r.work),
+                field(|r| &r.factory),
+                field(|r| &r.drilling),
+            ];
+            for (col, value) in texts.iter().enumerate() {
+                sheet.write_string_with_format(row, 1 + col as u16, value, &formats.text)?;
+            }
+            sheet.write_number_with_format(row, 8, summary.station_count as f64, &formats.text)?;
+            sheet.write_number_with_format(row, 9, summary.surveyed_depth, &formats.length)?;
+            // 最大偏差取自偏差检查，与各钻孔偏差汇总页的检查项一致
+            let max_of = |item: QaItem| {
+                checks
+                    .iter()
+                    .find(|check| check.item == item)
+                    .map_or(0.0, |check| check.max.value)
+            };
+            let deviations = [
+                max_of(QaItem::Lateral),
+                max_of(QaItem::Vertical),
+                summary.final_lateral,
+                summary.final_vertical,
+            ];
+            for (col, value) in deviations.into_iter().enumerate() {
+                sheet.write_number_with_format(row, 10 + col as u16, value, &formats.coordinate)?;
+            }
+            let (verdict, format) = if checks.iter().all(|check| check.passed) {
+                (Msg::VerdictPass, &formats.pass)
+            } else {
+                (Msg::VerdictFail, &formats.fail)
+            };
+            sheet.write_string_with_format(row, 14, text(verdict), format)?;
+        }
+        Ok(())
+    }
+}
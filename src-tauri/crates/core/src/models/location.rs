@@ -0,0 +1,701 @@
+use crate::i18n::{t, tf, Msg};
+use crate::models::repo::{prefixed_repo_columns, Repo};
+use crate::services::db::{get_conn, DbError};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 位置层级：矿井 / 工作面 / 钻场 / 钻机
+///
+/// 矿井、工作面、钻场构成树形结构；钻机会在不同钻场之间调动，单独成表。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationLevel {
+    Mine,
+    WorkingFace,
+    DrillingSite,
+    Rig,
+}
+
+impl LocationLevel {
+    /// 对应的表名
+    pub fn table(self) -> &'static str {
+        match self {
+            LocationLevel::Mine => "mine",
+            LocationLevel::WorkingFace => "working_face",
+            LocationLevel::DrillingSite => "drilling_site",
+            LocationLevel::Rig => "rig",
+        }
+    }
+
+    /// 指向上级的外键列
+    pub fn parent_column(self) -> Option<&'static str> {
+        match self {
+            LocationLevel::WorkingFace => Some("mine_id"),
+            LocationLevel::DrillingSite => Some("working_face_id"),
+            _ => None,
+        }
+    }
+
+    /// 下级层级
+    pub fn child(self) -> Option<LocationLevel> {
+        match self {
+            LocationLevel::Mine => Some(LocationLevel::WorkingFace),
+            LocationLevel::WorkingFace => Some(LocationLevel::DrillingSite),
+            _ => None,
+        }
+    }
+
+    /// repo 表中引用该层级的外键列
+    pub fn repo_id_column(self) -> &'static str {
+        match self {
+            LocationLevel::Mine => "mine_id",
+            LocationLevel::WorkingFace => "working_face_id",
+            LocationLevel::DrillingSite => "drilling_site_id",
+            LocationLevel::Rig => "rig_id",
+        }
+    }
+
+    /// repo 表中保留的原始文本列
+    pub fn repo_text_column(self) -> &'static str {
+        match self {
+            LocationLevel::Mine => "mine",
+            LocationLevel::WorkingFace => "work",
+            LocationLevel::DrillingSite => "factory",
+            LocationLevel::Rig => "drilling",
+        }
+    }
+
+    /// 当前语言下的层级名称
+    fn label(self) -> &'static str {
+        t(match self {
+            LocationLevel::Mine => Msg::FieldMine,
+            LocationLevel::WorkingFace => Msg::FieldWorkingFace,
+            LocationLevel::DrillingSite => Msg::FieldDrillingSite,
+            LocationLevel::Rig => Msg::FieldRig,
+        })
+    }
+}
+
+/// 位置节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub id: i64,
+    pub level: LocationLevel,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub repo_count: i64,
+}
+
+/// 钻场节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrillingSiteNode {
+    pub id: i64,
+    pub name: String,
+    pub repo_count: i64,
+}
+
+/// 工作面节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkingFaceNode {
+    pub id: i64,
+    pub name: String,
+    pub repo_count: i64,
+    pub drilling_sites: Vec<DrillingSiteNode>,
+}
+
+/// 矿井节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MineNode {
+    pub id: i64,
+    pub name: String,
+    pub repo_count: i64,
+    pub working_faces: Vec<WorkingFaceNode>,
+}
+
+/// repo 文本字段解析得到的层级 ID
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocationIds {
+    pub mine_id: Option<i64>,
+    pub working_face_id: Option<i64>,
+    pub drilling_site_id: Option<i64>,
+    pub rig_id: Option<i64>,
+}
+
+/// 规范化显示名称：全角字母数字转半角、去掉首尾空白、合并连续空白
+pub fn normalize_name(name: &str) -> String {
+    let halfwidth: String = name
+        .chars()
+        .map(|ch| match ch {
+            '\u{3000}' => ' ',
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(ch as u32 - 0xFEE0).unwrap_or(ch),
+            _ => ch,
+        })
+        .collect();
+    halfwidth.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 去重用的比较键：规范化后忽略大小写与空白
+pub fn name_key(name: &str) -> String {
+    normalize_name(name)
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(|ch| ch.to_lowercase())
+        .collect()
+}
+
+/// 名称为空
+fn name_empty(level: LocationLevel) -> rusqlite::Error {
+    DbError::Invalid(tf(Msg::LocationNameEmpty, &[("level", &level.label())])).into()
+}
+
+/// 节点不存在
+fn not_found(level: LocationLevel, id: i64) -> rusqlite::Error {
+    DbError::NotFound(tf(
+        Msg::LocationNotFound,
+        &[("level", &level.label()), ("id", &id)],
+    ))
+    .into()
+}
+
+/// 按名称查找或创建节点，返回节点 ID；空名称返回 None
+pub fn get_or_create(
+    c: &Connection,
+    level: LocationLevel,
+    name: &str,
+    parent_id: Option<i64>,
+) -> Result<Option<i64>> {
+    let display = normalize_name(name);
+    if display.is_empty() {
+        return Ok(None);
+    }
+    let key = name_key(&display);
+
+    let existing = match level.parent_column() {
+        Some(parent_col) => c
+            .query_row(
+                &format!(
+                    "SELECT id FROM {} WHERE {} = ? AND name_key = ?",
+                    level.table(),
+                    parent_col
+                ),
+                params![parent_id, key],
+                |row| row.get(0),
+            )
+            .optional()?,
+        None => c
+            .query_row(
+                &format!("SELECT id FROM {} WHERE name_key = ?", level.table()),
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?,
+    };
+    if existing.is_some() {
+        return Ok(existing);
+    }
+
+    match level.parent_column() {
+        Some(parent_col) => c.execute(
+            &format!(
+                "INSERT INTO {} (name, name_key, {}) VALUES (?, ?, ?)",
+                level.table(),
+                parent_col
+            ),
+            params![display, key, parent_id],
+        )?,
+        None => c.execute(
+            &format!("INSERT INTO {} (name, name_key) VALUES (?, ?)", level.table()),
+            params![display, key],
+        )?,
+    };
+    Ok(Some(c.last_insert_rowid()))
+}
+
+/// 把 repo 的四个文本字段解析为层级 ID，缺失的节点会自动创建
+pub fn resolve_ids(
+    c: &Connection,
+    mine: &str,
+    work: &str,
+    factory: &str,
+    drilling: &str,
+) -> Result<LocationIds> {
+    let mine_id = get_or_create(c, LocationLevel::Mine, mine, None)?;
+    // 没有矿井时工作面、钻场无法挂靠，只保留文本
+    let working_face_id = match mine_id {
+        Some(id) => get_or_create(c, LocationLevel::WorkingFace, work, Some(id))?,
+        None => None,
+    };
+    let drilling_site_id = match working_face_id {
+        Some(id) => get_or_create(c, LocationLevel::DrillingSite, factory, Some(id))?,
+        None => None,
+    };
+    let rig_id = get_or_create(c, LocationLevel::Rig, drilling, None)?;
+
+    Ok(LocationIds {
+        mine_id,
+        working_face_id,
+        drilling_site_id,
+        rig_id,
+    })
+}
+
+/// 把 repo 的文本列改写为所关联节点的规范名称，repo_id 为空时处理全部 repo
+pub fn sync_repo_names(c: &Connection, repo_id: Option<i64>) -> Result<()> {
+    for level in [
+        LocationLevel::Mine,
+        LocationLevel::WorkingFace,
+        LocationLevel::DrillingSite,
+        LocationLevel::Rig,
+    ] {
+        let sql = format!(
+            "UPDATE repo SET {text} = (SELECT name FROM {table} WHERE id = repo.{id_col})
+             WHERE {id_col} IS NOT NULL AND (?1 IS NULL OR id = ?1)",
+            text = level.repo_text_column(),
+            table = level.table(),
+            id_col = level.repo_id_column(),
+        );
+        c.execute(&sql, params![repo_id])?;
+    }
+    Ok(())
+}
+
+/// 查询节点名称
+fn location_name(c: &Connection, level: LocationLevel, id: i64) -> Result<String> {
+    c.query_row(
+        &format!("SELECT name FROM {} WHERE id = ?", level.table()),
+        params![id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| not_found(level, id))
+}
+
+/// 查询节点的上级 ID
+fn location_parent(c: &Connection, level: LocationLevel, id: i64) -> Result<Option<i64>> {
+    match level.parent_column() {
+        Some(parent_col) => c
+            .query_row(
+                &format!("SELECT {} FROM {} WHERE id = ?", parent_col, level.table()),
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| not_found(level, id)),
+        None => Ok(None),
+    }
+}
+
+/// 把 repo 中的文本列同步为节点当前名称
+fn sync_repo_text(c: &Connection, level: LocationLevel, id: i64) -> Result<()> {
+    let name = location_name(c, level, id)?;
+    c.execute(
+        &format!(
+            "UPDATE repo SET {} = ? WHERE {} = ?",
+            level.repo_text_column(),
+            level.repo_id_column()
+        ),
+        params![name, id],
+    )?;
+    Ok(())
+}
+
+/// 将 source 节点合并到 target：下级同名节点递归合并，其余下级和 repo 改挂到 target
+fn merge_into(c: &Connection, level: LocationLevel, source_id: i64, target_id: i64) -> Result<()> {
+    if source_id == target_id {
+        return Ok(());
+    }
+
+    if let Some(child) = level.child() {
+        let parent_col = child.parent_column().unwrap();
+        let children: Vec<(i64, String)> = {
+            let mut stmt = c.prepare(&format!(
+                "SELECT id, name_key FROM {} WHERE {} = ?",
+                child.table(),
+                parent_col
+            ))?;
+            let rows = stmt.query_map(params![source_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_>>()?
+        };
+        for (child_id, key) in children {
+            let twin: Option<i64> = c
+                .query_row(
+                    &format!(
+                        "SELECT id FROM {} WHERE {} = ? AND name_key = ?",
+                        child.table(),
+                        parent_col
+                    ),
+                    params![target_id, key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match twin {
+                Some(twin_id) => merge_into(c, child, child_id, twin_id)?,
+                None => {
+                    c.execute(
+                        &format!("UPDATE {} SET {} = ? WHERE id = ?", child.table(), parent_col),
+                        params![target_id, child_id],
+                    )?;
+                }
+            }
+        }
+    }
+
+    c.execute(
+        &format!(
+            "UPDATE repo SET {} = ? WHERE {} = ?",
+            level.repo_id_column(),
+            level.repo_id_column()
+        ),
+        params![target_id, source_id],
+    )?;
+    c.execute(
+        &format!("DELETE FROM {} WHERE id = ?", level.table()),
+        params![source_id],
+    )?;
+    sync_repo_text(c, level, target_id)
+}
+
+impl Location {
+    /// 列出某层级的节点，parent_id 为空时列出全部
+    pub async fn list(level: LocationLevel, parent_id: Option<i64>) -> Result<Vec<Location>, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let parent_select = match level.parent_column() {
+                Some(parent_col) => format!("l.{}", parent_col),
+                None => "NULL".to_string(),
+            };
+            let mut sql = format!(
+                "SELECT l.id, l.name, {parent}, (SELECT COUNT(*) FROM repo r WHERE r.{repo_col} = l.id)
+                 FROM {table} l",
+                parent = parent_select,
+                repo_col = level.repo_id_column(),
+                table = level.table(),
+            );
+            if parent_id.is_some() && level.parent_column().is_some() {
+                sql.push_str(&format!(" WHERE {} = ?", parent_select));
+            }
+            sql.push_str(" ORDER BY l.name");
+
+            let mut stmt = c.prepare(&sql)?;
+            let map_row = |row: &rusqlite::Row| {
+                Ok(Location {
+                    id: row.get(0)?,
+                    level,
+                    name: row.get(1)?,
+                    parent_id: row.get(2)?,
+                    repo_count: row.get(3)?,
+                })
+            };
+            let rows = if parent_id.is_some() && level.parent_column().is_some() {
+                stmt.query_map(params![parent_id], map_row)?
+            } else {
+                stmt.query_map([], map_row)?
+            };
+
+            let mut result = Vec::new();
+            for location in rows {
+                result.push(location?);
+            }
+            Ok::<_, rusqlite::Error>(result)
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 新建节点，同级下已有同名节点时直接返回该节点
+    pub async fn create(level: LocationLevel, name: String, parent_id: Option<i64>) -> Result<i64, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            if level.parent_column().is_some() {
+                let parent_level = match level {
+                    LocationLevel::WorkingFace => LocationLevel::Mine,
+                    _ => LocationLevel::WorkingFace,
+                };
+                let parent_id = parent_id.ok_or_else(|| {
+                    DbError::Invalid(tf(
+                        Msg::LocationParentRequired,
+                        &[("level", &level.label()), ("parent", &parent_level.label())],
+                    ))
+                })?;
+                location_name(c, parent_level, parent_id)?;
+            }
+            get_or_create(c, level, &name, parent_id)?
+                .ok_or_else(|| name_empty(level))
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 重命名节点，并同步引用它的 repo 文本字段
+    pub async fn rename(level: LocationLevel, id: i64, name: String) -> Result<bool, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let display = normalize_name(&name);
+            if display.is_empty() {
+                return Err(name_empty(level));
+            }
+            let key = name_key(&display);
+            let parent_id = location_parent(c, level, id)?;
+
+            // 同级重名时提示使用合并
+            let clash: Option<i64> = match level.parent_column() {
+                Some(parent_col) => c
+                    .query_row(
+                        &format!(
+                            "SELECT id FROM {} WHERE {} = ? AND name_key = ? AND id <> ?",
+                            level.table(),
+                            parent_col
+                        ),
+                        params![parent_id, key, id],
+                        |row| row.get(0),
+                    )
+                    .optional()?,
+                None => c
+                    .query_row(
+                        &format!("SELECT id FROM {} WHERE name_key = ? AND id <> ?", level.table()),
+                        params![key, id],
+                        |row| row.get(0),
+                    )
+                    .optional()?,
+            };
+            if let Some(clash_id) = clash {
+                return Err(DbError::Invalid(tf(
+                    Msg::LocationNameClash,
+                    &[("level", &level.label()), ("id", &clash_id)],
+                ))
+                .into());
+            }
+
+            let tx = c.transaction()?;
+            let rows = tx.execute(
+                &format!("UPDATE {} SET name = ?, name_key = ? WHERE id = ?", level.table()),
+                params![display, key, id],
+            )?;
+            if rows > 0 {
+                sync_repo_text(&tx, level, id)?;
+            }
+            tx.commit()?;
+            Ok(rows > 0)
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 删除节点，仍有下级节点或 repo 引用时拒绝删除
+    pub async fn delete(level: LocationLevel, id: i64) -> Result<bool, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let repo_count: i64 = c.query_row(
+                &format!("SELECT COUNT(*) FROM repo WHERE {} = ?", level.repo_id_column()),
+                params![id],
+                |row| row.get(0),
+            )?;
+            if repo_count > 0 {
+                return Err(DbError::Invalid(tf(
+                    Msg::LocationInUse,
+                    &[("level", &level.label()), ("count", &repo_count)],
+                ))
+                .into());
+            }
+            if let Some(child) = level.child() {
+                let child_count: i64 = c.query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM {} WHERE {} = ?",
+                        child.table(),
+                        child.parent_column().unwrap()
+                    ),
+                    params![id],
+                    |row| row.get(0),
+                )?;
+                if child_count > 0 {
+                    return Err(DbError::Invalid(tf(
+                        Msg::LocationHasChildren,
+                        &[
+                            ("level", &level.label()),
+                            ("count", &child_count),
+                            ("child", &child.label()),
+                        ],
+                    ))
+                    .into());
+                }
+            }
+            let rows = c.execute(
+                &format!("DELETE FROM {} WHERE id = ?", level.table()),
+                params![id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 合并两个同级节点（用于修正拼写不一致），source 合并后被删除
+    pub async fn merge(level: LocationLevel, source_id: i64, target_id: i64) -> Result<(), DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            location_name(c, level, source_id)?;
+            location_name(c, level, target_id)?;
+            if location_parent(c, level, source_id)? != location_parent(c, level, target_id)? {
+                return Err(DbError::Invalid(tf(
+                    Msg::LocationMergeParent,
+                    &[("level", &level.label())],
+                ))
+                .into());
+            }
+
+            let tx = c.transaction()?;
+            merge_into(&tx, level, source_id, target_id)?;
+            tx.commit()
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 查询矿井 / 工作面 / 钻场树
+    pub async fn tree() -> Result<Vec<MineNode>, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(|c| {
+            let mut sites: HashMap<i64, Vec<DrillingSiteNode>> = HashMap::new();
+            {
+                let mut stmt = c.prepare(
+                    "SELECT s.id, s.name, s.working_face_id,
+                            (SELECT COUNT(*) FROM repo r WHERE r.drilling_site_id = s.id)
+                     FROM drilling_site s ORDER BY s.name",
+                )?;
+                let mut rows = stmt.query([])?;
+                while let Some(row) = rows.next()? {
+                    sites.entry(row.get(2)?).or_default().push(DrillingSiteNode {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        repo_count: row.get(3)?,
+                    });
+                }
+            }
+
+            let mut faces: HashMap<i64, Vec<WorkingFaceNode>> = HashMap::new();
+            {
+                let mut stmt = c.prepare(
+                    "SELECT w.id, w.name, w.mine_id,
+                            (SELECT COUNT(*) FROM repo r WHERE r.working_face_id = w.id)
+                     FROM working_face w ORDER BY w.name",
+                )?;
+                let mut rows = stmt.query([])?;
+                while let Some(row) = rows.next()? {
+                    let id: i64 = row.get(0)?;
+                    faces.entry(row.get(2)?).or_default().push(WorkingFaceNode {
+                        id,
+                        name: row.get(1)?,
+                        repo_count: row.get(3)?,
+                        drilling_sites: sites.remove(&id).unwrap_or_default(),
+                    });
+                }
+            }
+
+            let mut stmt = c.prepare(
+                "SELECT m.id, m.name, (SELECT COUNT(*) FROM repo r WHERE r.mine_id = m.id)
+                 FROM mine m ORDER BY m.name",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut result = Vec::new();
+            while let Some(row) = rows.next()? {
+                let id: i64 = row.get(0)?;
+                result.push(MineNode {
+                    id,
+                    name: row.get(1)?,
+                    repo_count: row.get(2)?,
+                    working_faces: faces.remove(&id).unwrap_or_default(),
+                });
+            }
+            Ok::<_, rusqlite::Error>(result)
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 查询挂在某节点下的 repo
+    pub async fn repos(level: LocationLevel, id: i64) -> Result<Vec<Repo>, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let mut stmt = c.prepare(&format!(
+                "SELECT {} FROM repo r WHERE r.{} = ? ORDER BY r.mn_time DESC, r.id DESC",
+                prefixed_repo_columns("r"),
+                level.repo_id_column()
+            ))?;
+            let rows = stmt.query_map(params![id], Repo::from_row)?;
+            let mut result = Vec::new();
+            for repo in rows {
+                result.push(repo?);
+            }
+            Ok::<_, rusqlite::Error>(result)
+        })
+        .await
+        .map_err(DbError::from)
+    }
+
+    /// 指定 repo 所在钻场和钻机，矿井、工作面随钻场确定
+    pub async fn assign_repo(
+        repo_id: i32,
+        drilling_site_id: Option<i64>,
+        rig_id: Option<i64>,
+    ) -> Result<bool, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let tx = c.transaction()?;
+            let mut rows = 0;
+
+            if let Some(site_id) = drilling_site_id {
+                let face_id = location_parent(&tx, LocationLevel::DrillingSite, site_id)?;
+                let mine_id = match face_id {
+                    Some(id) => location_parent(&tx, LocationLevel::WorkingFace, id)?,
+                    None => None,
+                };
+                let site = location_name(&tx, LocationLevel::DrillingSite, site_id)?;
+                let face = match face_id {
+                    Some(id) => location_name(&tx, LocationLevel::WorkingFace, id)?,
+                    None => String::new(),
+                };
+                let mine = match mine_id {
+                    Some(id) => location_name(&tx, LocationLevel::Mine, id)?,
+                    None => String::new(),
+                };
+                rows = tx.execute(
+                    "UPDATE repo
+                     SET mine_id = ?, working_face_id = ?, drilling_site_id = ?,
+                         mine = ?, work = ?, factory = ?
+                     WHERE id = ?",
+                    params![mine_id, face_id, site_id, mine, face, site, repo_id],
+                )?;
+            }
+
+            if let Some(rig_id) = rig_id {
+                let rig = location_name(&tx, LocationLevel::Rig, rig_id)?;
+                rows = tx.execute(
+                    "UPDATE repo SET rig_id = ?, drilling = ? WHERE id = ?",
+                    params![rig_id, rig, repo_id],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(rows > 0)
+        })
+        .await
+        .map_err(DbError::from)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod data;
 pub mod excel;
+pub mod location;
 pub mod repo;
 pub mod user;
@@ -0,0 +1,380 @@
+use crate::models::data::DataList;
+use crate::models::location;
+use crate::services::db::{check_db_connection, get_conn, get_conn_with_retry, DbError};
+use rusqlite::{ffi, params, Connection, OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// repo 表查询列，顺序与 `Repo::from_row` 保持一致
+pub const REPO_COLUMNS: &str = "id, name, mn_time, len, mine, work, factory, drilling, notes, \
+    mine_id, working_face_id, drilling_site_id, rig_id, uuid";
+
+/// 生成新的 UUID（v4，小写带连字符）
+pub fn new_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// 带表别名前缀的 repo 查询列，用于联表查询
+pub fn prefixed_repo_columns(alias: &str) -> String {
+    REPO_COLUMNS
+        .split(", ")
+        .map(|col| format!("{}.{}", alias, col))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// repo 查询条件，未设置的条件不参与过滤
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepoFilter {
+    pub ids: Vec<i64>,
+    pub mine: Option<String>,
+    pub working_face: Option<String>,
+    // 测量日期范围（含两端），格式 YYYY-MM-DD
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repo {
+    // 本机内部自增 ID，仅在当前数据库内有效
+    pub id: Option<i32>,
+
+    // 全局唯一标识，跨设备同步、导出和合并时使用；缺失时由服务端生成
+    #[serde(default)]
+    pub uuid: Option<String>,
+
+    pub name: String,
+
+    #[serde(alias = "mnTime")]
+    pub mn_time: String,
+
+    pub len: i32,
+    pub mine: String,
+    pub work: String,
+    pub factory: String,
+    pub drilling: String,
+
+    // 备注（自由文本，参与全文检索）
+    #[serde(default)]
+    pub notes: Option<String>,
+
+    // 规范化后的矿井 / 工作面 / 钻场 / 钻机 ID，由文本字段解析得到
+    #[serde(default, alias = "mineId")]
+    pub mine_id: Option<i64>,
+
+    #[serde(default, alias = "workingFaceId")]
+    pub working_face_id: Option<i64>,
+
+    #[serde(default, alias = "drillingSiteId")]
+    pub drilling_site_id: Option<i64>,
+
+    #[serde(default, alias = "rigId")]
+    pub rig_id: Option<i64>,
+}
+
+/// 上传 repo 的写入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upload {
+    // 新写入的 repo ID
+    Inserted(i64),
+    // UUID 已存在，未写入；值为已有 repo 的 ID
+    Duplicate(i64),
+}
+
+/// 是否违反唯一约束
+fn is_unique_violation(e: &rusqlite::Error) -> bool {
+    matches!(
+        e,
+        rusqlite::Error::SqliteFailure(err, _) if err.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE
+    )
+}
+
+impl Repo {
+    /// 按 `REPO_COLUMNS` 的列顺序构造 Repo
+    pub fn from_row(row: &Row) -> Result<Repo> {
+        Ok(Repo {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            mn_time: row.get(2)?,
+            len: row.get(3)?,
+            mine: row.get(4)?,
+            work: row.get(5)?,
+            factory: row.get(6)?,
+            drilling: row.get(7)?,
+            notes: row.get(8)?,
+            mine_id: row.get(9)?,
+            working_face_id: row.get(10)?,
+            drilling_site_id: row.get(11)?,
+            rig_id: row.get(12)?,
+            uuid: row.get(13)?,
+        })
+    }
+
+    pub async fn insert_repo(repo: Repo) -> Result<i64, DbError> {
+        // 检查数据库连接
+        check_db_connection().await?;
+
+        let conn_guard = get_conn_with_retry(3).await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        let repo = repo.clone();
+        debug!(id = ?repo.id, "插入记录");
+        conn.call(move |c| {
+            let tx = c.transaction()?;
+            let id = Repo::insert_with(&tx, &repo)?;
+            tx.commit()?;
+            Ok(id)
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+
+    /// 跨数据库识别同一钻孔的键：名称 + 矿井 + 工作面 + 测量时间，名称部分按规范化后比较
+    pub fn identity_key(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            location::name_key(&self.name),
+            location::name_key(&self.mine),
+            location::name_key(&self.work),
+            self.mn_time.trim()
+        )
+    }
+
+    /// 在给定连接（或事务）上插入 repo，并关联矿井 / 工作面 / 钻场 / 钻机
+    ///
+    /// 客户端传入的整数 ID 会被忽略，由数据库分配；没有 UUID 时生成新的 UUID。
+    pub fn insert_with(c: &Connection, repo: &Repo) -> Result<i64> {
+        let ids = location::resolve_ids(c, &repo.mine, &repo.work, &repo.factory, &repo.drilling)?;
+        let uuid = repo.uuid.clone().unwrap_or_else(new_uuid);
+        c.execute(
+            "
+            INSERT INTO repo (uuid, name, mn_time, len, mine, work, factory, drilling, notes,
+                              mine_id, working_face_id, drilling_site_id, rig_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+            params![
+                uuid,
+                repo.name,
+                repo.mn_time,
+                repo.len,
+                repo.mine,
+                repo.work,
+                repo.factory,
+                repo.drilling,
+                repo.notes,
+                ids.mine_id,
+                ids.working_face_id,
+                ids.drilling_site_id,
+                ids.rig_id
+            ],
+        )?;
+        let id = c.last_insert_rowid();
+        location::sync_repo_names(c, Some(id))?;
+        Ok(id)
+    }
+
+    /// 按 UUID 查找本地 repo ID
+    pub fn find_id_by_uuid(c: &Connection, uuid: &str) -> Result<Option<i64>> {
+        c.query_row("SELECT id FROM repo WHERE uuid = ?", params![uuid], |row| {
+            row.get(0)
+        })
+        .optional()
+    }
+
+    /// 按 UUID 查询 repo
+    pub async fn query_repo_by_uuid(uuid: String) -> Result<Option<Repo>, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            c.query_row(
+                &format!("SELECT {} FROM repo WHERE uuid = ?", REPO_COLUMNS),
+                params![uuid],
+                Repo::from_row,
+            )
+            .optional()
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+
+    /// 在同一事务内写入上传的 repo 及其测点
+    ///
+    /// 由 uuid 唯一索引判断重复：UUID 已存在时不写入任何内容，返回已有 repo 的 ID。
+    pub async fn insert_upload(repo: Repo, stations: Vec<DataList>) -> Result<Upload, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let tx = c.transaction()?;
+            let repo_id = match Repo::insert_with(&tx, &repo) {
+                Ok(id) => id,
+                Err(e) if is_unique_violation(&e) => {
+                    return match repo.uuid.as_deref() {
+                        Some(uuid) => match Repo::find_id_by_uuid(&tx, uuid)? {
+                            Some(id) => Ok(Upload::Duplicate(id)),
+                            None => Err(e),
+                        },
+                        None => Err(e),
+                    };
+                }
+                Err(e) => return Err(e),
+            };
+            for station in &stations {
+                let mut station = station.clone();
+                station.repo_id = Some(repo_id as i32);
+                DataList::insert_with(&tx, &station)?;
+            }
+            tx.commit()?;
+            Ok(Upload::Inserted(repo_id))
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+
+    #[allow(dead_code)]
+    pub async fn delete_repo_by_id(id: i32) -> Result<bool, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let rows_affected = c.execute("DELETE FROM repo WHERE id = ?", params![id])?;
+            Ok(rows_affected > 0)
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_repo(repo: Repo) -> Result<bool, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        let repo = repo.clone();
+        if let Some(id) = repo.id {
+            conn.call(move |c| {
+                let tx = c.transaction()?;
+                let ids =
+                    location::resolve_ids(&tx, &repo.mine, &repo.work, &repo.factory, &repo.drilling)?;
+                let rows_affected = tx.execute(
+                    "
+                    UPDATE repo
+                    SET name = ?, mn_time = ?, len = ?, mine = ?, work = ?, factory = ?, drilling = ?, notes = ?,
+                        mine_id = ?, working_face_id = ?, drilling_site_id = ?, rig_id = ?
+                    WHERE id = ?
+                    ",
+                    params![
+                        repo.name,
+                        repo.mn_time,
+                        repo.len,
+                        repo.mine,
+                        repo.work,
+                        repo.factory,
+                        repo.drilling,
+                        repo.notes,
+                        ids.mine_id,
+                        ids.working_face_id,
+                        ids.drilling_site_id,
+                        ids.rig_id,
+                        id
+                    ],
+                )?;
+                location::sync_repo_names(&tx, Some(id as i64))?;
+                tx.commit()?;
+                Ok(rows_affected > 0)
+            })
+            .await
+            .map_err(|e| DbError::Other(e.into()))
+        } else {
+            Err(DbError::Other(anyhow::anyhow!("更新失败：ID 为 None")))
+        }
+    }
+
+    pub async fn query_repo_by_id(id: i32) -> Result<Option<Repo>, DbError> {
+        // 检查数据库连接
+        check_db_connection().await?;
+
+        let conn_guard = get_conn_with_retry(3).await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let mut stmt =
+                c.prepare(&format!("SELECT {} FROM repo WHERE id = ?", REPO_COLUMNS))?;
+            let mut rows = stmt.query(params![id])?;
+            if let Some(row) = rows.next()? {
+                Ok(Some(Repo::from_row(row)?))
+            } else {
+                Ok(None)
+            }
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+
+    /// 按条件查询 repo，矿井、工作面按规范化名称匹配
+    pub async fn query_filtered(filter: RepoFilter) -> Result<Vec<Repo>, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let mut clauses = Vec::new();
+            let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+            if !filter.ids.is_empty() {
+                clauses.push(format!(
+                    "id IN ({})",
+                    vec!["?"; filter.ids.len()].join(", ")
+                ));
+                values.extend(filter.ids.iter().map(|id| (*id).into()));
+            }
+            if let Some(mine) = &filter.mine {
+                clauses.push("mine_id IN (SELECT id FROM mine WHERE name_key = ?)".to_string());
+                values.push(location::name_key(mine).into());
+            }
+            if let Some(face) = &filter.working_face {
+                clauses.push(
+                    "working_face_id IN (SELECT id FROM working_face WHERE name_key = ?)"
+                        .to_string(),
+                );
+                values.push(location::name_key(face).into());
+            }
+            if let Some(from) = &filter.from {
+                clauses.push("substr(mn_time, 1, 10) >= ?".to_string());
+                values.push(from.clone().into());
+            }
+            if let Some(to) = &filter.to {
+                clauses.push("substr(mn_time, 1, 10) <= ?".to_string());
+                values.push(to.clone().into());
+            }
+
+            let where_clause = if clauses.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", clauses.join(" AND "))
+            };
+            let mut stmt = c.prepare(&format!(
+                "SELECT {} FROM repo {} ORDER BY id",
+                REPO_COLUMNS, where_clause
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(values), Repo::from_row)?;
+            rows.collect()
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+
+    /// 更新备注
+    pub async fn update_notes(id: i32, notes: Option<String>) -> Result<bool, DbError> {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+
+        conn.call(move |c| {
+            let rows_affected =
+                c.execute("UPDATE repo SET notes = ? WHERE id = ?", params![notes, id])?;
+            Ok(rows_affected > 0)
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+    }
+}
@@ -0,0 +1,616 @@
+use crate::i18n::{t, tf, Msg};
+use crate::models::data::DataList;
+use crate::models::repo::{Repo, REPO_COLUMNS};
+use crate::services::db::{self, get_conn, DbError};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 归档格式标识与版本
+pub const ARCHIVE_FORMAT: &str = "drilltrack-archive";
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const HOLES_FILE: &str = "holes.json";
+
+/// 归档清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub repo_count: usize,
+    pub station_count: usize,
+    pub files: Vec<ArchiveFileEntry>,
+}
+
+/// 归档内文件及校验和
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveFileEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// 一个钻孔及其测点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveHole {
+    pub repo: Repo,
+    pub stations: Vec<DataList>,
+}
+
+/// 导入时遇到同一钻孔但数据不同时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    // 保留本地数据，跳过归档中的钻孔
+    Skip,
+    // 用归档中的数据覆盖本地钻孔
+    Replace,
+    // 两份都保留，导入的钻孔名称加后缀
+    KeepBoth,
+}
+
+/// 钻孔在目标数据库中的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoleStatus {
+    // 目标库中没有
+    New,
+    // 目标库中已有完全相同的数据
+    Duplicate,
+    // 目标库中有同一钻孔但测点数据不同
+    Conflict,
+}
+
+/// 单个钻孔的比对结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolePreview {
+    pub uuid: Option<String>,
+    pub name: String,
+    pub mine: String,
+    pub work: String,
+    pub mn_time: String,
+    pub station_count: usize,
+    pub status: HoleStatus,
+    pub existing_id: Option<i64>,
+}
+
+/// 导入前预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivePreview {
+    pub manifest: ArchiveManifest,
+    pub holes: Vec<HolePreview>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub replaced: usize,
+    pub duplicates: Vec<HolePreview>,
+    pub conflicts: Vec<HolePreview>,
+    pub new_repo_ids: Vec<i64>,
+}
+
+/// 归档内容或参数有误
+fn invalid(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> DbError {
+    DbError::Invalid(tf(msg, args))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// 导出所选 repo 及测点到归档文件
+pub async fn export_archive(repo_ids: &[i32], file_path: &str) -> Result<ArchiveManifest, DbError> {
+    if repo_ids.is_empty() {
+        return Err(invalid(Msg::NoHolesSelected, &[]));
+    }
+
+    let mut holes = Vec::with_capacity(repo_ids.len());
+    for &id in repo_ids {
+        let mut repo = Repo::query_repo_by_id(id)
+            .await?
+            .ok_or_else(|| DbError::NotFound(tf(Msg::HoleIdNotFound, &[("id", &id)])))?;
+        let stations = db::query_data_list_by_repo_id(id)
+            .await?
+            .into_iter()
+            .map(|mut s| {
+                s.id = None;
+                s.repo_id = None;
+                s
+            })
+            .collect();
+        // 本地整数 ID 在其他数据库中没有意义，靠 UUID 识别
+        repo.id = None;
+        repo.mine_id = None;
+        repo.working_face_id = None;
+        repo.drilling_site_id = None;
+        repo.rig_id = None;
+        holes.push(ArchiveHole { repo, stations });
+    }
+
+    write_archive(&holes, file_path)
+}
+
+/// 写归档：holes.json 加带校验和的 manifest.json
+pub fn write_archive(holes: &[ArchiveHole], file_path: &str) -> Result<ArchiveManifest, DbError> {
+    let holes_json = serde_json::to_vec_pretty(holes).map_err(|e| DbError::Other(e.into()))?;
+
+    let manifest = ArchiveManifest {
+        format: ARCHIVE_FORMAT.to_string(),
+        format_version: ARCHIVE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        repo_count: holes.len(),
+        station_count: holes.iter().map(|h| h.stations.len()).sum(),
+        files: vec![ArchiveFileEntry {
+            path: HOLES_FILE.to_string(),
+            size: holes_json.len() as u64,
+            sha256: sha256_hex(&holes_json),
+        }],
+    };
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| DbError::Other(e.into()))?;
+
+    let file = File::create(file_path).map_err(|e| DbError::Other(e.into()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, bytes) in [(MANIFEST_FILE, &manifest_json), (HOLES_FILE, &holes_json)] {
+        zip.start_file(name, options)
+            .map_err(|e| DbError::Other(e.into()))?;
+        zip.write_all(bytes).map_err(|e| DbError::Other(e.into()))?;
+    }
+    zip.finish().map_err(|e| DbError::Other(e.into()))?;
+
+    Ok(manifest)
+}
+
+/// 读取并校验归档
+pub fn read_archive(file_path: &str) -> Result<(ArchiveManifest, Vec<ArchiveHole>), DbError> {
+    let file = File::open(file_path).map_err(|e| DbError::Other(e.into()))?;
+    let mut zip =
+        ZipArchive::new(file).map_err(|e| invalid(Msg::ArchiveInvalid, &[("error", &e)]))?;
+
+    let read_entry = |zip: &mut ZipArchive<File>, name: &str| -> Result<Vec<u8>, DbError> {
+        let mut entry = zip
+            .by_name(name)
+            .map_err(|_| invalid(Msg::ArchiveMissingFile, &[("name", &name)]))?;
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| DbError::Other(e.into()))?;
+        Ok(bytes)
+    };
+
+    let manifest: ArchiveManifest = serde_json::from_slice(&read_entry(&mut zip, MANIFEST_FILE)?)
+        .map_err(|e| invalid(Msg::ArchiveManifestInvalid, &[("error", &e)]))?;
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(invalid(
+            Msg::ArchiveUnknownFormat,
+            &[("format", &manifest.format)],
+        ));
+    }
+    if manifest.format_version > ARCHIVE_VERSION {
+        return Err(invalid(
+            Msg::ArchiveTooNew,
+            &[("version", &manifest.format_version)],
+        ));
+    }
+
+    let mut holes_json = None;
+    for entry in &manifest.files {
+        let bytes = read_entry(&mut zip, &entry.path)?;
+        if bytes.len() as u64 != entry.size || sha256_hex(&bytes) != entry.sha256 {
+            return Err(invalid(Msg::ArchiveChecksum, &[("path", &entry.path)]));
+        }
+        if entry.path == HOLES_FILE {
+            holes_json = Some(bytes);
+        }
+    }
+    let holes_json =
+        holes_json.ok_or_else(|| invalid(Msg::ArchiveMissingFile, &[("name", &HOLES_FILE)]))?;
+    let holes: Vec<ArchiveHole> = serde_json::from_slice(&holes_json)
+        .map_err(|e| invalid(Msg::ArchiveDataInvalid, &[("error", &e)]))?;
+
+    Ok((manifest, holes))
+}
+
+/// 目标库中已有的 repo，按 UUID 和身份键索引
+pub struct ExistingRepos {
+    by_uuid: HashMap<String, i64>,
+    by_identity: HashMap<String, i64>,
+}
+
+impl ExistingRepos {
+    pub fn load(c: &Connection) -> rusqlite::Result<ExistingRepos> {
+        let mut existing = ExistingRepos {
+            by_uuid: HashMap::new(),
+            by_identity: HashMap::new(),
+        };
+        let mut stmt = c.prepare(&format!("SELECT {} FROM repo", REPO_COLUMNS))?;
+        let rows = stmt.query_map([], Repo::from_row)?;
+        for repo in rows {
+            let repo = repo?;
+            if let Some(id) = repo.id {
+                existing.insert(&repo, id as i64);
+            }
+        }
+        Ok(existing)
+    }
+
+    /// 优先按 UUID 匹配，旧数据没有 UUID 时退回到身份键
+    pub fn find(&self, repo: &Repo) -> Option<i64> {
+        repo.uuid
+            .as_ref()
+            .and_then(|uuid| self.by_uuid.get(uuid))
+            .or_else(|| self.by_identity.get(&repo.identity_key()))
+            .copied()
+    }
+
+    pub fn insert(&mut self, repo: &Repo, id: i64) {
+        if let Some(uuid) = &repo.uuid {
+            self.by_uuid.insert(uuid.clone(), id);
+        }
+        self.by_identity.insert(repo.identity_key(), id);
+    }
+}
+
+/// 与目标库比对单个钻孔
+pub fn classify(
+    c: &Connection,
+    existing: &ExistingRepos,
+    hole: &ArchiveHole,
+) -> rusqlite::Result<HolePreview> {
+    let (status, existing_id) = match existing.find(&hole.repo) {
+        None => (HoleStatus::New, None),
+        Some(id) => {
+            let local = DataList::list_with(c, id)?;
+            if DataList::fingerprint(&local) == DataList::fingerprint(&hole.stations) {
+                (HoleStatus::Duplicate, Some(id))
+            } else {
+                (HoleStatus::Conflict, Some(id))
+            }
+        }
+    };
+
+    Ok(HolePreview {
+        uuid: hole.repo.uuid.clone(),
+        name: hole.repo.name.clone(),
+        mine: hole.repo.mine.clone(),
+        work: hole.repo.work.clone(),
+        mn_time: hole.repo.mn_time.clone(),
+        station_count: hole.stations.len(),
+        status,
+        existing_id,
+    })
+}
+
+/// 插入钻孔及测点，返回新 repo ID
+///
+/// 指定名称后缀时视为另存一份副本，重新生成 UUID。
+pub fn insert_hole(
+    c: &Connection,
+    hole: &ArchiveHole,
+    name_suffix: Option<&str>,
+) -> rusqlite::Result<i64> {
+    let mut repo = hole.repo.clone();
+    repo.id = None;
+    if let Some(suffix) = name_suffix {
+        repo.name = format!("{}{}", repo.name, suffix);
+        repo.uuid = None;
+    }
+    let repo_id = Repo::insert_with(c, &repo)?;
+    for station in &hole.stations {
+        let mut station = station.clone();
+        station.id = None;
+        station.repo_id = Some(repo_id as i32);
+        if name_suffix.is_some() {
+            station.uuid = None;
+        }
+        DataList::insert_with(c, &station)?;
+    }
+    Ok(repo_id)
+}
+
+/// 删除钻孔及测点
+pub fn delete_hole(c: &Connection, repo_id: i64) -> rusqlite::Result<()> {
+    c.execute("DELETE FROM data_list WHERE repo_id = ?", params![repo_id])?;
+    c.execute("DELETE FROM repo WHERE id = ?", params![repo_id])?;
+    Ok(())
+}
+
+/// 导入前预览：列出每个钻孔在当前数据库中的状态
+pub async fn inspect_archive(file_path: &str) -> Result<ArchivePreview, DbError> {
+    let (manifest, holes) = read_archive(file_path)?;
+    Ok(ArchivePreview {
+        manifest,
+        holes: preview_holes(holes).await?,
+    })
+}
+
+/// 逐个比对钻孔与当前数据库，供归档及其他格式的导入预览使用
+pub async fn preview_holes(holes: Vec<ArchiveHole>) -> Result<Vec<HolePreview>, DbError> {
+    let conn_guard = get_conn().await?;
+    let conn = conn_guard.as_ref().unwrap();
+    let previews = conn
+        .call(move |c| {
+            let existing = ExistingRepos::load(c)?;
+            holes
+                .iter()
+                .map(|hole| classify(c, &existing, hole))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))?;
+    Ok(previews)
+}
+
+/// 导入归档，所有写入在同一事务内完成
+pub async fn import_archive(
+    file_path: &str,
+    policy: ConflictPolicy,
+) -> Result<ImportReport, DbError> {
+    let (_, holes) = read_archive(file_path)?;
+    import_holes(holes, policy).await
+}
+
+/// 在一个事务内导入钻孔，按 `policy` 处理冲突；KeepBoth 时副本名称加 `suffix`
+pub fn import_into(
+    c: &mut Connection,
+    holes: &[ArchiveHole],
+    policy: ConflictPolicy,
+    suffix: &str,
+) -> rusqlite::Result<ImportReport> {
+    let tx = c.transaction()?;
+    let mut existing = ExistingRepos::load(&tx)?;
+    let mut report = ImportReport::default();
+
+    for hole in holes {
+        let preview = classify(&tx, &existing, hole)?;
+        match (preview.status, policy) {
+            (HoleStatus::New, _) => {
+                let repo_id = insert_hole(&tx, hole, None)?;
+                // 归档内重复的钻孔按已导入处理
+                existing.insert(&hole.repo, repo_id);
+                report.new_repo_ids.push(repo_id);
+                report.imported += 1;
+            }
+            (HoleStatus::Duplicate, _) => report.duplicates.push(preview),
+            (HoleStatus::Conflict, ConflictPolicy::Skip) => report.conflicts.push(preview),
+            (HoleStatus::Conflict, ConflictPolicy::Replace) => {
+                delete_hole(&tx, preview.existing_id.unwrap())?;
+                let repo_id = insert_hole(&tx, hole, None)?;
+                existing.insert(&hole.repo, repo_id);
+                report.new_repo_ids.push(repo_id);
+                report.replaced += 1;
+                report.conflicts.push(preview);
+            }
+            (HoleStatus::Conflict, ConflictPolicy::KeepBoth) => {
+                report
+                    .new_repo_ids
+                    .push(insert_hole(&tx, hole, Some(suffix))?);
+                report.imported += 1;
+                report.conflicts.push(preview);
+            }
+        }
+    }
+
+    tx.commit()?;
+    Ok(report)
+}
+
+/// 导入钻孔及测点，按 `policy` 处理冲突；所有写入在同一事务内完成
+pub async fn import_holes(
+    holes: Vec<ArchiveHole>,
+    policy: ConflictPolicy,
+) -> Result<ImportReport, DbError> {
+    let conn_guard = get_conn().await?;
+    let conn = conn_guard.as_ref().unwrap();
+    let suffix = t(Msg::ImportedNameSuffix);
+    conn.call(move |c| import_into(c, &holes, policy, suffix))
+        .await
+        .map_err(|e| DbError::Other(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::db::create_schema;
+
+    const UUID_A: &str = "6f1c2a3b-4d5e-4f60-8a7b-9c0d1e2f3a4b";
+
+    fn connection() -> Connection {
+        let mut c = Connection::open_in_memory().unwrap();
+        create_schema(&mut c).unwrap();
+        c
+    }
+
+    fn hole(uuid: Option<&str>, name: &str, headings: &[f64]) -> ArchiveHole {
+        ArchiveHole {
+            repo: Repo {
+                id: Some(99),
+                uuid: uuid.map(str::to_string),
+                name: name.to_string(),
+                mn_time: "2024-05-06 08:30:00".to_string(),
+                len: 30,
+                mine: "一矿".to_string(),
+                work: "1201".to_string(),
+                factory: "3#".to_string(),
+                drilling: "ZDY-6000".to_string(),
+                notes: None,
+                mine_id: None,
+                working_face_id: None,
+                drilling_site_id: None,
+                rig_id: None,
+            },
+            stations: headings
+                .iter()
+                .enumerate()
+                .map(|(i, heading)| DataList {
+                    id: Some(i as i32 + 1),
+                    uuid: None,
+                    time: None,
+                    depth: (i + 1) as f64 * 10.0,
+                    pitch: Some(-2.0),
+                    roll: None,
+                    heading: Some(*heading),
+                    repo_id: Some(99),
+                    design_pitch: Some(-2.0),
+                    design_heading: Some(90.0),
+                })
+                .collect(),
+        }
+    }
+
+    fn classify_in(c: &Connection, hole: &ArchiveHole) -> HolePreview {
+        let existing = ExistingRepos::load(c).unwrap();
+        classify(c, &existing, hole).unwrap()
+    }
+
+    fn repo_names(c: &Connection) -> Vec<String> {
+        let mut stmt = c.prepare("SELECT name FROM repo ORDER BY id").unwrap();
+        let names = stmt.query_map([], |row| row.get(0)).unwrap();
+        names.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    fn headings(c: &Connection, repo_id: i64) -> Vec<f64> {
+        DataList::list_with(c, repo_id)
+            .unwrap()
+            .iter()
+            .map(|s| s.heading.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn classify_new_duplicate_and_conflict() {
+        let c = connection();
+        let local = hole(Some(UUID_A), "ZK-1", &[90.0, 91.0]);
+        assert_eq!(classify_in(&c, &local).status, HoleStatus::New);
+
+        let id = insert_hole(&c, &local, None).unwrap();
+        let duplicate = classify_in(&c, &local);
+        assert_eq!(duplicate.status, HoleStatus::Duplicate);
+        assert_eq!(duplicate.existing_id, Some(id));
+
+        let changed = hole(Some(UUID_A), "ZK-1 改名", &[90.0, 92.0]);
+        let conflict = classify_in(&c, &changed);
+        assert_eq!(conflict.status, HoleStatus::Conflict);
+        assert_eq!(conflict.existing_id, Some(id));
+
+        // 没有 UUID 时按名称、矿井、工作面和测量时间匹配
+        let legacy = hole(None, " zk-1 ", &[90.0, 91.0]);
+        assert_eq!(classify_in(&c, &legacy).status, HoleStatus::Duplicate);
+        let other = hole(None, "ZK-2", &[90.0, 91.0]);
+        assert_eq!(classify_in(&c, &other).status, HoleStatus::New);
+    }
+
+    #[test]
+    fn skip_keeps_local_data() {
+        let mut c = connection();
+        let id = insert_hole(&c, &hole(Some(UUID_A), "ZK-1", &[90.0]), None).unwrap();
+        let incoming = [hole(Some(UUID_A), "ZK-1", &[95.0])];
+
+        let report = import_into(&mut c, &incoming, ConflictPolicy::Skip, "-副本").unwrap();
+        assert_eq!((report.imported, report.replaced), (0, 0));
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(repo_names(&c), ["ZK-1"]);
+        assert_eq!(headings(&c, id), [90.0]);
+    }
+
+    #[test]
+    fn replace_overwrites_local_hole() {
+        let mut c = connection();
+        insert_hole(&c, &hole(Some(UUID_A), "ZK-1", &[90.0]), None).unwrap();
+        let incoming = [hole(Some(UUID_A), "ZK-1", &[95.0, 96.0])];
+
+        let report = import_into(&mut c, &incoming, ConflictPolicy::Replace, "-副本").unwrap();
+        assert_eq!((report.imported, report.replaced), (0, 1));
+        assert_eq!(repo_names(&c), ["ZK-1"]);
+        assert_eq!(headings(&c, report.new_repo_ids[0]), [95.0, 96.0]);
+        let stations: i64 = c
+            .query_row("SELECT COUNT(*) FROM data_list", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stations, 2);
+    }
+
+    #[test]
+    fn keep_both_adds_renamed_copy_with_new_uuid() {
+        let mut c = connection();
+        let id = insert_hole(&c, &hole(Some(UUID_A), "ZK-1", &[90.0]), None).unwrap();
+        let incoming = [hole(Some(UUID_A), "ZK-1", &[95.0])];
+
+        let report = import_into(&mut c, &incoming, ConflictPolicy::KeepBoth, "-副本").unwrap();
+        assert_eq!((report.imported, report.replaced), (1, 0));
+        assert_eq!(repo_names(&c), ["ZK-1", "ZK-1-副本"]);
+        assert_eq!(headings(&c, id), [90.0]);
+        let copy_uuid: String = c
+            .query_row(
+                "SELECT uuid FROM repo WHERE id = ?",
+                [report.new_repo_ids[0]],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_ne!(copy_uuid, UUID_A);
+    }
+
+    #[test]
+    fn repeated_hole_in_one_archive_is_imported_once() {
+        let mut c = connection();
+        let incoming = [
+            hole(Some(UUID_A), "ZK-1", &[90.0]),
+            hole(Some(UUID_A), "ZK-1", &[90.0]),
+        ];
+
+        let report = import_into(&mut c, &incoming, ConflictPolicy::Skip, "-副本").unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(repo_names(&c), ["ZK-1"]);
+    }
+
+    #[test]
+    fn archive_round_trip_and_checksum_mismatch() {
+        let dir = std::env::temp_dir().join(format!("drilltrack-archive-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("holes.zip");
+        let path = path.to_str().unwrap();
+
+        let holes = [hole(Some(UUID_A), "ZK-1", &[90.0, 91.0])];
+        let manifest = write_archive(&holes, path).unwrap();
+        assert_eq!((manifest.repo_count, manifest.station_count), (1, 2));
+        let (read, holes_read) = read_archive(path).unwrap();
+        assert_eq!(read.files[0].sha256, manifest.files[0].sha256);
+        assert_eq!(holes_read.len(), 1);
+        assert_eq!(holes_read[0].repo.uuid.as_deref(), Some(UUID_A));
+        assert_eq!(
+            DataList::fingerprint(&holes_read[0].stations),
+            DataList::fingerprint(&holes[0].stations)
+        );
+
+        // 清单不变、数据被改动的归档
+        let tampered = dir.join("tampered.zip");
+        let tampered = tampered.to_str().unwrap();
+        let changed =
+            serde_json::to_vec_pretty(&[hole(Some(UUID_A), "ZK-1", &[90.0, 99.0])]).unwrap();
+        let mut zip = ZipWriter::new(File::create(tampered).unwrap());
+        let options = FileOptions::default();
+        zip.start_file(MANIFEST_FILE, options).unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+            .unwrap();
+        zip.start_file(HOLES_FILE, options).unwrap();
+        zip.write_all(&changed).unwrap();
+        zip.finish().unwrap();
+
+        let error = read_archive(tampered).unwrap_err();
+        assert!(matches!(error, DbError::Invalid(ref message) if message.contains(HOLES_FILE)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
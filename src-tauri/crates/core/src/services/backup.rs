@@ -0,0 +1,372 @@
+use crate::i18n::{t, tf, Msg};
+use crate::services::db::{get_conn, get_db_status, DbError};
+use crate::services::migrations;
+use crate::services::settings;
+use chrono::{Local, NaiveDate};
+use once_cell::sync::OnceCell;
+use rusqlite::{backup::Progress, Connection, DatabaseName, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::time::Duration;
+use tracing::{error, info};
+
+/// 快照文件名前缀，后接 YYYYMMDD
+const SNAPSHOT_PREFIX: &str = "database-";
+const SNAPSHOT_EXT: &str = "db";
+
+/// 恢复前快照文件名前缀，后接 YYYYMMDD-HHMMSS
+const PRE_RESTORE_PREFIX: &str = "pre-restore-";
+
+/// 保留的恢复前快照份数，与每日快照分开轮换
+const PRE_RESTORE_RETENTION: usize = 5;
+
+/// 快照检查间隔
+const SNAPSHOT_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 备份文件必须包含的表
+const REQUIRED_TABLES: [&str; 2] = ["repo", "data_list"];
+
+/// 旧版快照所属的项目：多项目之前只有默认项目 database.db
+const LEGACY_PROJECT: &str = "database";
+
+/// 快照目录（应用数据目录下的 snapshots），每个项目一个子目录
+static SNAPSHOT_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub schema_version: i32,
+    pub repo_count: i64,
+    pub data_count: i64,
+}
+
+/// 每日快照配置（保存在应用设置中）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    pub enabled: bool,
+    // 保留的快照份数，超出后删除最旧的
+    pub retention: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention: 7,
+        }
+    }
+}
+
+/// 快照条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub name: String,
+    pub path: String,
+    pub date: String,
+    pub size_bytes: u64,
+}
+
+fn other(message: String) -> DbError {
+    DbError::Other(anyhow::anyhow!(message))
+}
+
+/// 备份文件或路径有误
+fn invalid(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> DbError {
+    DbError::Invalid(tf(msg, args))
+}
+
+/// 在线备份当前数据库到指定路径
+///
+/// 先写入同目录下的临时文件，完成后再改名，避免中途失败留下不完整的备份。
+pub async fn backup_to(target_path: &str) -> Result<BackupInfo, DbError> {
+    let target = PathBuf::from(target_path);
+    if let Some(parent) = target.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            std::fs::create_dir_all(parent).map_err(|e| DbError::Other(e.into()))?;
+        }
+    }
+    if let Some(current) = get_db_status().await.path {
+        if same_file(Path::new(&current), &target) {
+            return Err(invalid(Msg::BackupIsCurrent, &[]));
+        }
+    }
+
+    let tmp_path = target.with_extension("db.tmp");
+    {
+        let conn_guard = get_conn().await?;
+        let conn = conn_guard.as_ref().unwrap();
+        let tmp = tmp_path.clone();
+        conn.call(move |c| c.backup(DatabaseName::Main, &tmp, None))
+            .await
+            .map_err(|e| DbError::Other(e.into()))?;
+    }
+
+    std::fs::rename(&tmp_path, &target).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        DbError::Other(e.into())
+    })?;
+
+    validate_backup(target_path)
+}
+
+/// 校验备份文件：完整性检查、必需表、结构版本
+pub fn validate_backup(path: &str) -> Result<BackupInfo, DbError> {
+    let file_path = Path::new(path);
+    if !file_path.is_file() {
+        return Err(DbError::NotFound(tf(Msg::BackupNotFound, &[("path", &path)])));
+    }
+
+    let conn = Connection::open_with_flags(
+        file_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| invalid(Msg::BackupNotDatabase, &[("error", &e)]))?;
+    if integrity != "ok" {
+        return Err(invalid(Msg::BackupCorrupt, &[("detail", &integrity)]));
+    }
+
+    for table in REQUIRED_TABLES {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+            [table],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(invalid(Msg::BackupMissingTable, &[("table", &table)]));
+        }
+    }
+
+    let schema_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if schema_version > migrations::LATEST_VERSION {
+        return Err(invalid(
+            Msg::BackupTooNew,
+            &[("version", &schema_version)],
+        ));
+    }
+
+    let repo_count: i64 = conn.query_row("SELECT COUNT(*) FROM repo", [], |row| row.get(0))?;
+    let data_count: i64 = conn.query_row("SELECT COUNT(*) FROM data_list", [], |row| row.get(0))?;
+    let size_bytes = std::fs::metadata(file_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    Ok(BackupInfo {
+        path: path.to_string(),
+        size_bytes,
+        schema_version,
+        repo_count,
+        data_count,
+    })
+}
+
+/// 从备份恢复当前数据库
+///
+/// 恢复前会先校验备份，并在当前项目的快照目录留一份恢复前的快照；恢复后补齐结构迁移。
+pub async fn restore_from(source_path: &str) -> Result<BackupInfo, DbError> {
+    let info = validate_backup(source_path)?;
+
+    if let Some(current) = get_db_status().await.path {
+        if same_file(Path::new(&current), Path::new(source_path)) {
+            return Err(invalid(Msg::RestoreFromCurrent, &[]));
+        }
+    }
+
+    if SNAPSHOT_DIR.get().is_some() {
+        let dir = project_snapshot_dir().await?;
+        let name = format!(
+            "{}{}.{}",
+            PRE_RESTORE_PREFIX,
+            Local::now().format("%Y%m%d-%H%M%S"),
+            SNAPSHOT_EXT
+        );
+        backup_to(&dir.join(name).to_string_lossy()).await?;
+        rotate_pre_restore(&dir)?;
+    }
+
+    let conn_guard = get_conn().await?;
+    let conn = conn_guard.as_ref().unwrap();
+    let source = source_path.to_string();
+    conn.call(move |c| {
+        c.restore(DatabaseName::Main, &source, None::<fn(Progress)>)?;
+        migrations::run(c)
+    })
+    .await
+    .map_err(|e| DbError::Other(e.into()))?;
+
+    Ok(info)
+}
+
+/// 判断两个路径是否指向同一文件
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// 初始化快照目录
+///
+/// 旧版直接放在 snapshots 下的快照移到默认项目的子目录中。
+pub fn init_snapshots(app_data_dir: &Path) -> Result<(), DbError> {
+    let dir = app_data_dir.join("snapshots");
+    let legacy_dir = dir.join(LEGACY_PROJECT);
+    std::fs::create_dir_all(&legacy_dir).map_err(|e| DbError::Other(e.into()))?;
+
+    let suffix = format!(".{}", SNAPSHOT_EXT);
+    for entry in std::fs::read_dir(&dir).map_err(|e| DbError::Other(e.into()))? {
+        let entry = entry.map_err(|e| DbError::Other(e.into()))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_snapshot = name.starts_with(SNAPSHOT_PREFIX) || name.starts_with(PRE_RESTORE_PREFIX);
+        if is_snapshot && name.ends_with(&suffix) && entry.path().is_file() {
+            std::fs::rename(entry.path(), legacy_dir.join(&name))
+                .map_err(|e| DbError::Other(e.into()))?;
+        }
+    }
+
+    let _ = SNAPSHOT_DIR.set(dir);
+    Ok(())
+}
+
+fn snapshot_dir() -> Result<&'static PathBuf, DbError> {
+    SNAPSHOT_DIR
+        .get()
+        .ok_or_else(|| other(t(Msg::SnapshotsNotInitialized).to_string()))
+}
+
+/// 当前项目的快照目录：snapshots/<项目名称>，项目名称为数据库文件名
+async fn project_snapshot_dir() -> Result<PathBuf, DbError> {
+    let root = snapshot_dir()?;
+    let path = get_db_status().await.path.ok_or(DbError::NotInitialized)?;
+    let project = Path::new(&path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| LEGACY_PROJECT.to_string());
+    let dir = root.join(project);
+    std::fs::create_dir_all(&dir).map_err(|e| DbError::Other(e.into()))?;
+    Ok(dir)
+}
+
+/// 解析快照文件名中的日期
+fn snapshot_date(file_name: &str) -> Option<NaiveDate> {
+    let stem = file_name
+        .strip_prefix(SNAPSHOT_PREFIX)?
+        .strip_suffix(&format!(".{}", SNAPSHOT_EXT))?;
+    NaiveDate::parse_from_str(stem, "%Y%m%d").ok()
+}
+
+/// 列出目录中的每日快照，最新的在前
+fn snapshots_in(dir: &Path) -> Result<Vec<SnapshotEntry>, DbError> {
+    let mut entries = Vec::new();
+
+    for entry in std::fs::read_dir(dir).map_err(|e| DbError::Other(e.into()))? {
+        let entry = entry.map_err(|e| DbError::Other(e.into()))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(date) = snapshot_date(&name) {
+            entries.push(SnapshotEntry {
+                path: entry.path().to_string_lossy().to_string(),
+                date: date.format("%Y-%m-%d").to_string(),
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                name,
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(entries)
+}
+
+/// 列出当前项目的每日快照，最新的在前
+pub async fn list_snapshots() -> Result<Vec<SnapshotEntry>, DbError> {
+    snapshots_in(&project_snapshot_dir().await?)
+}
+
+/// 删除目录中超出保留份数的旧快照
+fn rotate_in(dir: &Path, retention: usize) -> Result<(), DbError> {
+    for entry in snapshots_in(dir)?.into_iter().skip(retention) {
+        std::fs::remove_file(&entry.path).map_err(|e| DbError::Other(e.into()))?;
+    }
+    Ok(())
+}
+
+/// 按保留份数轮换每个项目的快照，各项目分别计数
+pub fn rotate_snapshots(retention: usize) -> Result<(), DbError> {
+    for entry in std::fs::read_dir(snapshot_dir()?).map_err(|e| DbError::Other(e.into()))? {
+        let entry = entry.map_err(|e| DbError::Other(e.into()))?;
+        if entry.path().is_dir() {
+            rotate_in(&entry.path(), retention)?;
+        }
+    }
+    Ok(())
+}
+
+/// 删除超出保留份数的旧恢复前快照
+///
+/// 文件名中的时间戳按字典序即按时间排序。
+fn rotate_pre_restore(dir: &Path) -> Result<(), DbError> {
+    let suffix = format!(".{}", SNAPSHOT_EXT);
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| DbError::Other(e.into()))? {
+        let entry = entry.map_err(|e| DbError::Other(e.into()))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(PRE_RESTORE_PREFIX) && name.ends_with(&suffix) {
+            paths.push((name, entry.path()));
+        }
+    }
+
+    paths.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, path) in paths.into_iter().skip(PRE_RESTORE_RETENTION) {
+        std::fs::remove_file(&path).map_err(|e| DbError::Other(e.into()))?;
+    }
+    Ok(())
+}
+
+/// 生成当前项目的当天快照（已存在时跳过），返回快照信息
+pub async fn take_daily_snapshot() -> Result<Option<SnapshotEntry>, DbError> {
+    let dir = project_snapshot_dir().await?;
+    let today = Local::now().date_naive();
+    let name = format!(
+        "{}{}.{}",
+        SNAPSHOT_PREFIX,
+        today.format("%Y%m%d"),
+        SNAPSHOT_EXT
+    );
+    let path = dir.join(&name);
+    if path.exists() {
+        return Ok(None);
+    }
+
+    let info = backup_to(&path.to_string_lossy()).await?;
+    rotate_in(&dir, settings::current().snapshot.retention)?;
+
+    Ok(Some(SnapshotEntry {
+        name,
+        path: info.path,
+        date: today.format("%Y-%m-%d").to_string(),
+        size_bytes: info.size_bytes,
+    }))
+}
+
+/// 后台定时快照任务：启动时及之后每小时检查一次当天快照是否已生成
+pub async fn run_snapshot_scheduler() {
+    let mut interval = tokio::time::interval(SNAPSHOT_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        if !settings::current().snapshot.enabled {
+            continue;
+        }
+        match take_daily_snapshot().await {
+            Ok(Some(entry)) => info!("已生成数据库快照: {}", entry.path),
+            Ok(None) => {}
+            // 数据库尚未初始化时等待下一轮
+            Err(DbError::NotInitialized) => {}
+            Err(e) => error!("生成数据库快照失败: {}", e),
+        }
+    }
+}
@@ -1,8 +1,10 @@
+use crate::i18n::{tf, Msg};
 use crate::models::data::DataList;
-use crate::models::repo::Repo;
+use crate::models::repo::{Repo, REPO_COLUMNS};
+use crate::services::migrations;
 use anyhow::Result;
 use once_cell::sync::OnceCell;
-use rusqlite::params;
+use rusqlite::{params, Connection, OpenFlags};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tokio::sync::Mutex;
@@ -20,6 +22,38 @@ pub enum DbError {
     Sqlite(#[from] rusqlite::Error),
     #[error("其他错误: {0}")]
     Other(#[from] anyhow::Error),
+    // 业务层给出的说明（已按当前语言本地化）
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// 在 `conn.call` 闭包中返回业务错误（借用可携带任意错误的 `ToSqlConversionFailure`）
+impl From<DbError> for rusqlite::Error {
+    fn from(e: DbError) -> Self {
+        rusqlite::Error::ToSqlConversionFailure(Box::new(e))
+    }
+}
+
+/// 取回闭包中返回的业务错误，其余错误按原样包装
+impl From<tokio_rusqlite::Error> for DbError {
+    fn from(e: tokio_rusqlite::Error) -> Self {
+        match e {
+            tokio_rusqlite::Error::Rusqlite(rusqlite::Error::ToSqlConversionFailure(inner)) => {
+                match inner.downcast::<DbError>() {
+                    Ok(e) => *e,
+                    Err(inner) => DbError::Other(
+                        tokio_rusqlite::Error::Rusqlite(rusqlite::Error::ToSqlConversionFailure(
+                            inner,
+                        ))
+                        .into(),
+                    ),
+                }
+            }
+            e => DbError::Other(e.into()),
+        }
+    }
 }
 
 /// 数据库状态
@@ -48,61 +82,93 @@ pub async fn init_db(db_path: &str) -> Result<(), DbError> {
         .await
         .map_err(|e| DbError::Other(e.into()))?;
 
-    // 初始化表
-    conn.call(|conn| {
-        conn.execute_batch(
-            "
-            BEGIN;
-            
-            CREATE TABLE IF NOT EXISTS users (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                email TEXT UNIQUE,
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-            
-            CREATE TABLE IF NOT EXISTS repo (
-                id INTEGER PRIMARY KEY,
-                name TEXT NOT NULL,
-                mn_time TEXT NOT NULL,
-                len INTEGER NOT NULL,
-                mine TEXT NOT NULL,
-                work TEXT NOT NULL,
-                factory TEXT NOT NULL,
-                drilling TEXT NOT NULL
-            );
-            
-            CREATE TABLE IF NOT EXISTS data_list (
-                id INTEGER PRIMARY KEY,
-                time TEXT,
-                depth REAL NOT NULL,
-                pitch REAL,
-                roll REAL,
-                heading REAL,
-                repo_id INTEGER,
-                design_pitch REAL,
-                design_heading REAL,
-                FOREIGN KEY (repo_id) REFERENCES repo(id)
-            );
-
-            COMMIT;
-        ",
-        )
-    })
+    // 初始化表并执行结构迁移
+    conn.call(create_schema)
+        .await
+        .map_err(|e| DbError::Other(e.into()))?;
+
+    set_conn(conn, db_path).await;
+    Ok(())
+}
+
+/// 建表并执行结构迁移
+pub fn create_schema(conn: &mut Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "
+        BEGIN;
+        
+        CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            email TEXT UNIQUE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        
+        CREATE TABLE IF NOT EXISTS repo (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            mn_time TEXT NOT NULL,
+            len INTEGER NOT NULL,
+            mine TEXT NOT NULL,
+            work TEXT NOT NULL,
+            factory TEXT NOT NULL,
+            drilling TEXT NOT NULL
+        );
+        
+        CREATE TABLE IF NOT EXISTS data_list (
+            id INTEGER PRIMARY KEY,
+            time TEXT,
+            depth REAL NOT NULL,
+            pitch REAL,
+            roll REAL,
+            heading REAL,
+            repo_id INTEGER,
+            design_pitch REAL,
+            design_heading REAL,
+            FOREIGN KEY (repo_id) REFERENCES repo(id)
+        );
+
+        COMMIT;
+    ",
+    )?;
+    migrations::run(conn)
+}
+
+/// 以只读方式打开数据库，不建表也不执行迁移，供命令行工具查询和导出使用
+///
+/// 结构版本低于当前程序时返回错误，需先用桌面程序或数据服务器打开一次以完成升级。
+pub async fn open_read_only(db_path: &str) -> Result<(), DbError> {
+    let conn = TokioConnection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
     .await
-    .map_err(|e| DbError::Other(e.into()))?;
+    .map_err(DbError::from)?;
 
-    // 初始化全局连接
+    let version: i32 = conn
+        .call(|c| c.query_row("PRAGMA user_version", [], |row| row.get(0)))
+        .await
+        .map_err(DbError::from)?;
+    if version < migrations::LATEST_VERSION {
+        return Err(DbError::Invalid(tf(
+            Msg::DbNeedsMigration,
+            &[("version", &version), ("latest", &migrations::LATEST_VERSION)],
+        )));
+    }
+
+    set_conn(conn, db_path).await;
+    Ok(())
+}
+
+/// 设置全局连接并记录数据库路径
+async fn set_conn(conn: TokioConnection, db_path: &str) {
     let conn_mutex = DB_CONNECTION.get_or_init(|| Mutex::new(None));
     let mut conn_guard = conn_mutex.lock().await;
     *conn_guard = Some(conn);
 
-    // 保存数据库路径
     let path_mutex = DB_PATH.get_or_init(|| Mutex::new(None));
     let mut path_guard = path_mutex.lock().await;
     *path_guard = Some(db_path.to_string());
-
-    Ok(())
 }
 
 /// 获取数据库状态
@@ -395,26 +461,18 @@ pub async fn query_all_repos() -> Result<Vec<Repo>, DbError> {
         Some(c) => c,
         None => return Err(DbError::NotInitialized),
     };
-    let repos = conn.call(|c| {
-        let mut stmt = c.prepare("SELECT id, name, mn_time, len, mine, work, factory, drilling FROM repo ORDER BY id")?;
-        let rows = stmt.query_map([], |row| {
-            Ok(Repo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                mn_time: row.get(2)?,
-                len: row.get(3)?,
-                mine: row.get(4)?,
-                work: row.get(5)?,
-                factory: row.get(6)?,
-                drilling: row.get(7)?,
-            })
-        })?;
-        let mut result = Vec::new();
-        for repo in rows {
-            result.push(repo?);
-        }
-        Ok::<_, rusqlite::Error>(result)
-    }).await.map_err(|e| DbError::Other(e.into()))?;
+    let repos = conn
+        .call(|c| {
+            let mut stmt = c.prepare(&format!("SELECT {} FROM repo ORDER BY id", REPO_COLUMNS))?;
+            let rows = stmt.query_map([], Repo::from_row)?;
+            let mut result = Vec::new();
+            for repo in rows {
+                result.push(repo?);
+            }
+            Ok::<_, rusqlite::Error>(result)
+        })
+        .await
+        .map_err(|e| DbError::Other(e.into()))?;
     Ok(repos)
 }
 
@@ -443,32 +501,7 @@ pub async fn query_data_list_by_repo_id(repo_id: i32) -> Result<Vec<DataList>, D
         None => return Err(DbError::NotInitialized),
     };
     let data_list = conn
-        .call(move |c| {
-            let mut stmt = c.prepare(
-            "SELECT id, time, depth, pitch, roll, heading, repo_id, design_pitch, design_heading 
-             FROM data_list 
-             WHERE repo_id = ? 
-             ORDER BY depth"
-        )?;
-            let rows = stmt.query_map(params![repo_id], |row| {
-                Ok(DataList {
-                    id: Some(row.get(0)?),
-                    time: row.get(1)?,
-                    depth: row.get(2)?,
-                    pitch: row.get(3)?,
-                    roll: row.get(4)?,
-                    heading: row.get(5)?,
-                    repo_id: Some(row.get(6)?),
-                    design_pitch: row.get(7)?,
-                    design_heading: row.get(8)?,
-                })
-            })?;
-            let mut result = Vec::new();
-            for data in rows {
-                result.push(data?);
-            }
-            Ok::<_, rusqlite::Error>(result)
-        })
+        .call(move |c| DataList::list_with(c, repo_id as i64))
         .await
         .map_err(|e| DbError::Other(e.into()))?;
     Ok(data_list)
@@ -0,0 +1,203 @@
+use crate::i18n::{t, tf, Msg};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::{watch, Mutex};
+use tokio::time::Duration;
+use tracing::{debug, error, info, trace, warn};
+
+/// 广播间隔
+const BROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 局域网发现服务参数
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    // 监听客户端发现请求的端口
+    pub port: u16,
+    // 客户端监听广播的端口
+    pub client_port: u16,
+    // 数据接收服务器端口，随广播告知客户端
+    pub http_port: u16,
+    pub server_name: String,
+}
+
+/// 运行中的发现服务，stop 后广播和监听任务都会退出
+pub struct DiscoveryHandle {
+    pub ip_addresses: Vec<String>,
+    clients: Arc<Mutex<Vec<String>>>,
+    stop_tx: watch::Sender<bool>,
+}
+
+impl DiscoveryHandle {
+    /// 已发现的客户端地址
+    pub async fn clients(&self) -> Vec<String> {
+        self.clients.lock().await.clone()
+    }
+
+    /// 停止发现服务
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(true);
+    }
+}
+
+/// 获取本机所有IPv4地址
+pub fn local_ipv4_addresses() -> Vec<String> {
+    match local_ip_address::list_afinet_netifas() {
+        Ok(netifas) => {
+            let addresses: Vec<String> = netifas
+                .iter()
+                .filter_map(|(_, addr)| {
+                    if let std::net::IpAddr::V4(ipv4) = addr {
+                        let octets = ipv4.octets();
+                        // 只保留 192.168.x.x，排除 loopback/169.254/10.x/172.16-31
+                        if octets[0] == 192 && octets[1] == 168 {
+                            Some(ipv4.to_string())
+                        } else {
+                            None
+                        }
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            // 没有找到非回环地址时，添加环回地址
+            if addresses.is_empty() {
+                return vec!["127.0.0.1".to_string()];
+            }
+
+            debug!("本机IP地址: {:?}", addresses);
+            addresses
+        }
+        Err(e) => {
+            warn!("获取IP地址错误: {:?}", e);
+            vec!["127.0.0.1".to_string()]
+        }
+    }
+}
+
+/// 启动局域网发现服务：定时广播服务器信息，并回复客户端的发现请求
+pub async fn start_discovery(config: DiscoveryConfig) -> Result<DiscoveryHandle> {
+    let ip_addresses = local_ipv4_addresses();
+    if ip_addresses.is_empty() {
+        return Err(anyhow!(t(Msg::DiscoveryNoAddress)));
+    }
+
+    // 创建UDP套接字用于广播
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| anyhow!(tf(Msg::DiscoverySocketFailed, &[("error", &e)])))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| anyhow!(tf(Msg::DiscoveryBroadcastFailed, &[("error", &e)])))?;
+
+    // 绑定UDP监听端口
+    let listen_socket = UdpSocket::bind(("0.0.0.0", config.port))
+        .await
+        .map_err(|e| {
+            anyhow!(tf(
+                Msg::DiscoveryBindFailed,
+                &[("port", &config.port), ("error", &e)]
+            ))
+        })?;
+
+    let server_info = serde_json::json!({
+        "type": "server_announce",
+        "name": config.server_name,
+        "http_port": config.http_port,
+        "discovery_port": config.port,
+        "ip": ip_addresses[0].clone(),
+        "all_ips": ip_addresses,  // 提供所有可用IP
+        "version": "1.0",
+        "timestamp": chrono::Utc::now().timestamp_millis()
+    })
+    .to_string();
+    info!("启动广播，发送信息: {}", server_info);
+
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let clients = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    let broadcast_addr = format!("255.255.255.255:{}", config.client_port);
+    let mut stop = stop_rx.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BROADCAST_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = stop.changed() => {
+                    info!("停止发现服务广播");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Err(e) = socket.send_to(server_info.as_bytes(), &broadcast_addr).await {
+                        warn!("广播消息发送失败: {}", e);
+                    } else {
+                        // 每 2 秒一次，只在 trace 级别记录
+                        trace!("已发送广播消息");
+                    }
+                }
+            }
+        }
+    });
+
+    let listen_clients = clients.clone();
+    let server_ip = ip_addresses[0].clone();
+    let mut stop = stop_rx;
+    tokio::spawn(async move {
+        let mut buf = [0u8; 1024];
+        loop {
+            let (size, addr) = tokio::select! {
+                _ = stop.changed() => break,
+                received = listen_socket.recv_from(&mut buf) => match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        warn!("接收UDP消息失败: {}", e);
+                        continue;
+                    }
+                },
+            };
+
+            let Ok(msg) = std::str::from_utf8(&buf[..size]) else {
+                continue;
+            };
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(msg) else {
+                continue;
+            };
+            if json["type"] != "client_discovery" {
+                continue;
+            }
+            info!(client = %addr, "收到客户端发现请求");
+            debug!("客户端发现请求内容: {}", msg);
+
+            // 记录客户端地址
+            let client_addr = addr.ip().to_string();
+            {
+                let mut clients = listen_clients.lock().await;
+                if !clients.contains(&client_addr) {
+                    clients.push(client_addr);
+                }
+            }
+
+            let response = serde_json::json!({
+                "type": "server_response",
+                "name": config.server_name,
+                "http_port": config.http_port,
+                "ip": server_ip,                   // 添加服务器IP
+                "discovery_port": config.port,     // 添加发现服务端口
+                "timestamp": chrono::Utc::now().timestamp_millis()
+            })
+            .to_string();
+            debug!("发送回复: {} 到 {}", response, addr);
+
+            if let Err(e) = listen_socket.send_to(response.as_bytes(), addr).await {
+                error!("回复客户端失败: {}", e);
+            }
+        }
+        info!("停止发现服务监听");
+    });
+
+    Ok(DiscoveryHandle {
+        ip_addresses,
+        clients,
+        stop_tx,
+    })
+}
@@ -0,0 +1,374 @@
+use crate::calc::{self, TrajectoryPoint};
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+// 图层：平面图与剖面图各自的实测轨迹、设计轨迹、测点和文字
+pub const LAYER_PLAN_ACTUAL: &str = "PLAN_ACTUAL";
+pub const LAYER_PLAN_DESIGN: &str = "PLAN_DESIGN";
+pub const LAYER_PLAN_STATIONS: &str = "PLAN_STATIONS";
+pub const LAYER_PLAN_LABELS: &str = "PLAN_LABELS";
+pub const LAYER_PROFILE_ACTUAL: &str = "PROFILE_ACTUAL";
+pub const LAYER_PROFILE_DESIGN: &str = "PROFILE_DESIGN";
+pub const LAYER_PROFILE_STATIONS: &str = "PROFILE_STATIONS";
+pub const LAYER_PROFILE_LABELS: &str = "PROFILE_LABELS";
+
+/// 图层名称、颜色号（AutoCAD 索引色）及线型
+const LAYERS: [(&str, i32, &str); 8] = [
+    (LAYER_PLAN_ACTUAL, 1, "CONTINUOUS"),
+    (LAYER_PLAN_DESIGN, 3, "DASHED"),
+    (LAYER_PLAN_STATIONS, 5, "CONTINUOUS"),
+    (LAYER_PLAN_LABELS, 7, "CONTINUOUS"),
+    (LAYER_PROFILE_ACTUAL, 1, "CONTINUOUS"),
+    (LAYER_PROFILE_DESIGN, 3, "DASHED"),
+    (LAYER_PROFILE_STATIONS, 5, "CONTINUOUS"),
+    (LAYER_PROFILE_LABELS, 7, "CONTINUOUS"),
+];
+
+/// 图层颜色号，未知图层为 7（白/黑）
+pub fn layer_color(name: &str) -> i32 {
+    LAYERS
+        .iter()
+        .find(|(layer, ..)| *layer == name)
+        .map_or(7, |(_, color, _)| *color)
+}
+
+/// 非 ASCII 字符写成 AutoCAD 的 `\U+XXXX` 转义，DXF 与脚本在任何代码页下都能正确显示中文
+pub fn escape_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' => c.to_string(),
+            c if (c as u32) <= 0xFFFF => format!("\\U+{:04X}", c as u32),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// 轨迹上按深度排列的点，DXF 与脚本据此插值深度标注的位置
+pub trait DepthPoint: Copy {
+    fn depth(&self) -> f64;
+
+    /// 与下一个点之间按比例 `t`（0 ~ 1）线性插值
+    fn lerp(&self, next: &Self, t: f64) -> Self;
+}
+
+/// 按深度在轨迹上线性插值，深度超出轨迹范围时返回 None
+pub fn at_depth<P: DepthPoint>(points: &[P], depth: f64) -> Option<P> {
+    points.windows(2).find_map(|pair| {
+        let (a, b) = (&pair[0], &pair[1]);
+        if depth < a.depth() || depth > b.depth() || b.depth() <= a.depth() {
+            return None;
+        }
+        Some(a.lerp(b, (depth - a.depth()) / (b.depth() - a.depth())))
+    })
+}
+
+/// 需要标注深度的位置：按间隔取整深度，另加终孔深度；第一个点为孔口，不标注。
+/// 间隔为 0 时标注每个测点
+pub fn depth_labels<P: DepthPoint>(points: &[P], interval: f64) -> Vec<P> {
+    let last = match points.last() {
+        Some(last) if points.len() > 1 => *last,
+        _ => return Vec::new(),
+    };
+    if interval <= 0.0 {
+        return points[1..].to_vec();
+    }
+    let mut labels: Vec<P> = (1..)
+        .map(|k| k as f64 * interval)
+        .take_while(|depth| *depth < last.depth() - 1e-6)
+        .filter_map(|depth| at_depth(points, depth))
+        .collect();
+    labels.push(last);
+    labels
+}
+
+/// 孔口坐标（米），X 为东、Y 为北
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Collar {
+    pub east: f64,
+    pub north: f64,
+    pub elevation: f64,
+}
+
+/// DXF 与 AutoCAD 脚本的绘图参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DxfOptions {
+    // 深度标注间隔（米），为 0 时每个测点都标注
+    pub label_interval: f64,
+    // 文字高度（米）
+    pub text_height: f64,
+}
+
+impl Default for DxfOptions {
+    fn default() -> Self {
+        Self {
+            label_interval: 10.0,
+            text_height: 0.5,
+        }
+    }
+}
+
+/// 待绘制的钻孔
+pub struct DxfHole<'a> {
+    pub repo: &'a Repo,
+    pub stations: &'a [DataList],
+    pub collar: Collar,
+}
+
+/// 平面图与剖面图中的一个点
+#[derive(Debug, Clone, Copy)]
+struct ViewPoint {
+    depth: f64,
+    plan: (f64, f64),
+    // 沿剖面方向的水平距离与高程
+    profile: (f64, f64),
+}
+
+impl DepthPoint for ViewPoint {
+    fn depth(&self) -> f64 {
+        self.depth
+    }
+
+    fn lerp(&self, next: &Self, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        ViewPoint {
+            depth: lerp(self.depth, next.depth),
+            plan: (lerp(self.plan.0, next.plan.0), lerp(self.plan.1, next.plan.1)),
+            profile: (
+                lerp(self.profile.0, next.profile.0),
+                lerp(self.profile.1, next.profile.1),
+            ),
+        }
+    }
+}
+
+/// 单个钻孔在两个视图中的轨迹
+struct HoleViews {
+    name: String,
+    collar: Collar,
+    actual: Vec<ViewPoint>,
+    design: Option<Vec<ViewPoint>>,
+}
+
+impl HoleViews {
+    fn new(hole: &DxfHole, magnetic_declination: f64) -> Self {
+        let azimuth = calc::section_azimuth(hole.stations, magnetic_declination);
+        let project = |points: &[TrajectoryPoint]| -> Vec<ViewPoint> {
+            let (sin, cos) = azimuth.to_radians().sin_cos();
+            let collar = hole.collar;
+            std::iter::once((0.0, 0.0, 0.0, 0.0))
+                .chain(
+                    points
+                        .iter()
+                        .map(|p| (p.depth, p.north, p.east, p.elevation)),
+                )
+                .map(|(depth, north, east, elevation)| ViewPoint {
+                    depth,
+                    plan: (collar.east + east, collar.north + north),
+                    profile: (north * cos + east * sin, collar.elevation + elevation),
+                })
+                .collect()
+        };
+
+        let actual = project(&calc::trajectory(hole.stations, magnetic_declination));
+        let design = calc::design_trajectory(hole.stations).map(|points| project(&points));
+
+        Self {
+            name: hole.repo.name.clone(),
+            collar: hole.collar,
+            actual,
+            design,
+        }
+    }
+
+    /// 需要标注深度的位置
+    fn labels(&self, interval: f64) -> Vec<ViewPoint> {
+        depth_labels(&self.actual, interval)
+    }
+}
+
+/// DXF 组码写入器
+struct DxfWriter {
+    out: String,
+    decimal_places: usize,
+}
+
+impl DxfWriter {
+    fn pair(&mut self, code: i32, value: impl std::fmt::Display) {
+        let _ = write!(self.out, "{}\r\n{}\r\n", code, value);
+    }
+
+    fn num(&mut self, code: i32, value: f64) {
+        let value = format!("{:.*}", self.decimal_places, value);
+        self.pair(code, value);
+    }
+
+    fn polyline(&mut self, layer: &str, points: impl Iterator<Item = (f64, f64)>) {
+        self.pair(0, "POLYLINE");
+        self.pair(8, layer);
+        self.pair(66, 1);
+        self.pair(70, 0);
+        for (x, y) in points {
+            self.pair(0, "VERTEX");
+            self.pair(8, layer);
+            self.num(10, x);
+            self.num(20, y);
+            self.num(30, 0.0);
+        }
+        self.pair(0, "SEQEND");
+        self.pair(8, layer);
+    }
+
+    fn circle(&mut self, layer: &str, (x, y): (f64, f64), radius: f64) {
+        self.pair(0, "CIRCLE");
+        self.pair(8, layer);
+        self.num(10, x);
+        self.num(20, y);
+        self.num(30, 0.0);
+        self.num(40, radius);
+    }
+
+    fn text(&mut self, layer: &str, (x, y): (f64, f64), height: f64, text: &str) {
+        self.pair(0, "TEXT");
+        self.pair(8, layer);
+        self.num(10, x);
+        self.num(20, y);
+        self.num(30, 0.0);
+        self.num(40, height);
+        self.pair(1, escape_text(text));
+    }
+
+    fn tables(&mut self) {
+        self.pair(0, "SECTION");
+        self.pair(2, "TABLES");
+
+        self.pair(0, "TABLE");
+        self.pair(2, "LTYPE");
+        self.pair(70, 2);
+        self.pair(0, "LTYPE");
+        self.pair(2, "CONTINUOUS");
+        self.pair(70, 0);
+        self.pair(3, "Solid line");
+        self.pair(72, 65);
+        self.pair(73, 0);
+        self.pair(40, "0.0");
+        self.pair(0, "LTYPE");
+        self.pair(2, "DASHED");
+        self.pair(70, 0);
+        self.pair(3, "__ __ __");
+        self.pair(72, 65);
+        self.pair(73, 2);
+        self.pair(40, "1.5");
+        self.pair(49, "1.0");
+        self.pair(49, "-0.5");
+        self.pair(0, "ENDTAB");
+
+        self.pair(0, "TABLE");
+        self.pair(2, "LAYER");
+        self.pair(70, LAYERS.len());
+        for (name, color, linetype) in LAYERS {
+            self.pair(0, "LAYER");
+            self.pair(2, name);
+            self.pair(70, 0);
+            self.pair(62, color);
+            self.pair(6, linetype);
+        }
+        self.pair(0, "ENDTAB");
+
+        self.pair(0, "ENDSEC");
+    }
+}
+
+/// 平面图与剖面图 DXF（R12）
+///
+/// 平面图 X 为东、Y 为北，已加孔口坐标；剖面图横轴为沿设计方位角的水平距离、纵轴为高程，
+/// 所有钻孔的剖面以孔口对齐，整体放在平面图右侧。深度按 `label_interval` 标注。
+pub fn to_dxf(
+    holes: &[DxfHole],
+    magnetic_declination: f64,
+    decimal_places: usize,
+    options: &DxfOptions,
+) -> String {
+    let views: Vec<HoleViews> = holes
+        .iter()
+        .map(|hole| HoleViews::new(hole, magnetic_declination))
+        .collect();
+
+    // 剖面图原点：平面图范围右侧留出间距
+    let plan_points = || {
+        views
+            .iter()
+            .flat_map(|v| v.actual.iter().chain(v.design.iter().flatten()))
+    };
+    let max_x = plan_points()
+        .map(|p| p.plan.0)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let min_x = plan_points()
+        .map(|p| p.plan.0)
+        .fold(f64::INFINITY, f64::min);
+    let profile_x = if max_x.is_finite() {
+        max_x + ((max_x - min_x) * 0.2).max(options.text_height * 20.0)
+    } else {
+        0.0
+    };
+
+    let height = options.text_height;
+    let mut dxf = DxfWriter {
+        out: String::new(),
+        decimal_places,
+    };
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "HEADER");
+    dxf.pair(9, "$ACADVER");
+    dxf.pair(1, "AC1009");
+    // R12 按代码页解释文字，中文已转义为 \U+XXXX，文件只含 ASCII
+    dxf.pair(9, "$DWGCODEPAGE");
+    dxf.pair(3, "ANSI_1252");
+    dxf.pair(0, "ENDSEC");
+    dxf.tables();
+
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "ENTITIES");
+    for view in &views {
+        let profile = |p: &ViewPoint| (profile_x + p.profile.0, p.profile.1);
+
+        dxf.polyline(LAYER_PLAN_ACTUAL, view.actual.iter().map(|p| p.plan));
+        dxf.polyline(LAYER_PROFILE_ACTUAL, view.actual.iter().map(profile));
+        if let Some(design) = &view.design {
+            dxf.polyline(LAYER_PLAN_DESIGN, design.iter().map(|p| p.plan));
+            dxf.polyline(LAYER_PROFILE_DESIGN, design.iter().map(profile));
+        }
+
+        for p in view.actual.iter().skip(1) {
+            dxf.circle(LAYER_PLAN_STATIONS, p.plan, height / 4.0);
+            dxf.circle(LAYER_PROFILE_STATIONS, profile(p), height / 4.0);
+        }
+
+        let collar = (view.collar.east, view.collar.north);
+        dxf.text(
+            LAYER_PLAN_LABELS,
+            (collar.0 + height, collar.1 + height),
+            height,
+            &view.name,
+        );
+        let collar_profile = (profile_x, view.collar.elevation);
+        dxf.text(
+            LAYER_PROFILE_LABELS,
+            (collar_profile.0 + height, collar_profile.1 + height),
+            height,
+            &view.name,
+        );
+        for p in view.labels(options.label_interval) {
+            let text = format!("{:.1}m", p.depth);
+            let offset = |(x, y): (f64, f64)| (x + height / 2.0, y + height / 2.0);
+            dxf.text(LAYER_PLAN_LABELS, offset(p.plan), height, &text);
+            dxf.text(LAYER_PROFILE_LABELS, offset(profile(&p)), height, &text);
+        }
+    }
+    dxf.pair(0, "ENDSEC");
+    dxf.pair(0, "EOF");
+    dxf.out
+}
@@ -0,0 +1,811 @@
+use crate::calc::{self, DeviationSummary, QaCheck};
+use crate::i18n::{t, tf, Locale, Msg};
+use crate::models::data::DataList;
+use crate::models::excel::{BatchWorkbook, ExcelData};
+use crate::models::repo::{Repo, RepoFilter};
+use crate::services::db;
+use crate::services::dxf::{self, Collar, DxfHole, DxfOptions};
+use crate::services::kml;
+use crate::services::las;
+use crate::services::pdf::{self, PdfOptions};
+use crate::services::plot::{self, PlotFormat, PlotOptions};
+use crate::services::projection::Projection;
+use crate::services::records::{station_records, FieldValue, StationRecord};
+use crate::services::scr;
+use crate::services::settings::ToleranceSettings;
+use crate::services::templates::{ColumnField, ReportTemplate, TemplateColumn};
+use crate::services::witsml;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Xlsx,
+    Csv,
+    Dxf,
+    // AutoCAD 脚本
+    Scr,
+    // 钻孔信息、偏差汇总与测点记录
+    Json,
+    // 轨迹线、孔口与测点要素
+    GeoJson,
+    // 测量报告
+    Pdf,
+    // 轨迹图
+    Svg,
+    Png,
+    // LAS 2.0 测井数据
+    Las,
+    // WITSML 1.4.1 轨迹
+    Witsml,
+    // 地图概览：孔口点与三维轨迹线
+    Kml,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Dxf => "dxf",
+            ExportFormat::Scr => "scr",
+            ExportFormat::Json => "json",
+            ExportFormat::GeoJson => "geojson",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Svg => "svg",
+            ExportFormat::Png => "png",
+            ExportFormat::Las => "las",
+            ExportFormat::Witsml => "xml",
+            ExportFormat::Kml => "kml",
+        }
+    }
+}
+
+/// 导出参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    // 磁偏角（度）
+    pub magnetic_declination: f64,
+    // 坐标保留的小数位数
+    pub decimal_places: usize,
+    // 表头语言
+    #[serde(default)]
+    pub locale: Locale,
+    // 偏差容许值，用于报表的质量结论
+    #[serde(default)]
+    pub tolerance: ToleranceSettings,
+    // Excel 报表模板
+    #[serde(default)]
+    pub template: ReportTemplate,
+    // 孔口坐标，按钻孔 ID；未给出的钻孔孔口位于原点
+    #[serde(default)]
+    pub collars: HashMap<i32, Collar>,
+    // DXF 深度标注间隔与文字高度
+    #[serde(default)]
+    pub dxf: DxfOptions,
+    // CSV 分隔符与小数点
+    #[serde(default)]
+    pub csv: CsvOptions,
+    // PDF 报告的公司名称、标志与签字栏
+    #[serde(default)]
+    pub pdf: PdfOptions,
+    // 轨迹图的视图、尺寸与样式
+    #[serde(default)]
+    pub plot: PlotOptions,
+    // 孔口坐标所用的投影，KML 导出时据此反算经纬度
+    #[serde(default)]
+    pub projection: Option<Projection>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            magnetic_declination: 0.0,
+            decimal_places: 5,
+            locale: Locale::default(),
+            tolerance: ToleranceSettings::default(),
+            template: ReportTemplate::default(),
+            collars: HashMap::new(),
+            dxf: DxfOptions::default(),
+            csv: CsvOptions::default(),
+            pdf: PdfOptions::default(),
+            plot: PlotOptions::default(),
+            projection: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// 钻孔的孔口坐标
+    pub fn collar(&self, repo: &Repo) -> Collar {
+        repo.id
+            .and_then(|id| self.collars.get(&id).copied())
+            .unwrap_or_default()
+    }
+}
+
+/// CSV 格式参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    // 字段分隔符，小数点为逗号时通常用分号
+    pub delimiter: char,
+    // 小数点，`.` 或 `,`
+    pub decimal_separator: char,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            decimal_separator: '.',
+        }
+    }
+}
+
+impl CsvOptions {
+    /// 检查分隔符与小数点能否组成可解析的 CSV
+    pub fn validate(&self) -> Result<()> {
+        if !matches!(self.decimal_separator, '.' | ',') {
+            return Err(anyhow!(t(Msg::CsvDecimalSeparator)));
+        }
+        if self.delimiter == self.decimal_separator {
+            return Err(anyhow!(t(Msg::CsvDelimiterClash)));
+        }
+        if matches!(self.delimiter, '"' | '\r' | '\n') || self.delimiter.is_ascii_digit() {
+            return Err(anyhow!(tf(
+                Msg::CsvDelimiterInvalid,
+                &[("delimiter", &format!("{:?}", self.delimiter))],
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 导出文件名：`{id}-{名称}.{扩展名}`，名称中不能用于文件名的字符替换为下划线
+pub fn file_name(repo: &Repo, format: ExportFormat) -> String {
+    let name: String = repo
+        .name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    format!(
+        "{}-{}.{}",
+        repo.id.unwrap_or_default(),
+        name,
+        format.extension()
+    )
+}
+
+fn excel_data(
+    repo: &Repo,
+    stations: &[DataList],
+    path: &Path,
+    options: &ExportOptions,
+) -> ExcelData {
+    ExcelData {
+        file_path: path.to_string_lossy().to_string(),
+        data: stations.to_vec(),
+        magnetic_declination: options.magnetic_declination.to_string(),
+        decimal_places: options.decimal_places,
+        locale: options.locale,
+        repo: Some(repo.clone()),
+        tolerance: options.tolerance.clone(),
+        template: options.template.clone(),
+    }
+}
+
+/// 导出单个钻孔
+pub async fn export_hole(
+    repo: &Repo,
+    stations: &[DataList],
+    format: ExportFormat,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<()> {
+    match format {
+        ExportFormat::Xlsx => excel_data(repo, stations, path, options)
+            .save_to_excel()
+            .await,
+        ExportFormat::Csv => {
+            let records = station_records(stations, options.magnetic_declination);
+            std::fs::write(path, to_csv(&records, options)?)?;
+            Ok(())
+        }
+        ExportFormat::Dxf => {
+            let hole = DxfHole {
+                repo,
+                stations,
+                collar: options.collar(repo),
+            };
+            std::fs::write(path, to_dxf(&[hole], options))?;
+            Ok(())
+        }
+        ExportFormat::Scr => {
+            std::fs::write(path, scr::to_scr(repo, stations, options))?;
+            Ok(())
+        }
+        ExportFormat::Json => {
+            std::fs::write(path, to_json(repo, stations, options)?)?;
+            Ok(())
+        }
+        ExportFormat::GeoJson => {
+            std::fs::write(path, to_geojson(repo, stations, options)?)?;
+            Ok(())
+        }
+        ExportFormat::Pdf => {
+            std::fs::write(path, pdf::to_pdf(repo, stations, options)?)?;
+            Ok(())
+        }
+        ExportFormat::Las => {
+            std::fs::write(path, las::to_las(repo, stations, options))?;
+            Ok(())
+        }
+        ExportFormat::Witsml => {
+            std::fs::write(path, witsml::to_witsml(&[(repo, stations)], options))?;
+            Ok(())
+        }
+        ExportFormat::Kml => {
+            let collar = repo
+                .id
+                .and_then(|id| options.collars.get(&id).copied())
+                .ok_or_else(|| anyhow!(tf(Msg::KmlNeedsCollar, &[("name", &repo.name)])))?;
+            let hole = DxfHole {
+                repo,
+                stations,
+                collar,
+            };
+            std::fs::write(path, to_kml(&[hole], options)?)?;
+            Ok(())
+        }
+        ExportFormat::Svg | ExportFormat::Png => {
+            let hole = DxfHole {
+                repo,
+                stations,
+                collar: options.collar(repo),
+            };
+            let format = if format == ExportFormat::Svg {
+                PlotFormat::Svg
+            } else {
+                PlotFormat::Png
+            };
+            std::fs::write(path, to_plot(&[hole], format, options)?)?;
+            Ok(())
+        }
+    }
+}
+
+/// 批量导出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    // 一个工作簿：索引页加每个钻孔一页测点数据
+    Workbook,
+    // 输出目录中每个钻孔单独成文件
+    Folder,
+}
+
+/// 批量导出请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchExportRequest {
+    // 钻孔筛选条件，可按 ID、矿井、工作面和日期范围选择
+    #[serde(default)]
+    pub filter: RepoFilter,
+    pub mode: BatchMode,
+    // 工作簿文件路径或输出目录
+    pub path: String,
+    // 按目录导出时的文件格式，默认仅 Excel
+    #[serde(default = "default_batch_formats")]
+    pub formats: Vec<ExportFormat>,
+    // 孔口坐标，按钻孔 ID，补充到导出参数中；kml 格式没有孔口坐标的钻孔跳过
+    #[serde(default)]
+    pub collars: HashMap<i32, Collar>,
+    // 孔口坐标的投影，未指定时使用导出参数中的投影；kml 格式必须有投影
+    #[serde(default)]
+    pub projection: Option<Projection>,
+}
+
+fn default_batch_formats() -> Vec<ExportFormat> {
+    vec![ExportFormat::Xlsx]
+}
+
+/// 批量导出进度，每处理完一个钻孔通知一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub done: usize,
+    pub total: usize,
+    pub repo_id: Option<i32>,
+    pub repo_name: String,
+}
+
+/// 因测点数据校验未通过或写出失败而跳过的钻孔
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedRepo {
+    pub repo_id: Option<i32>,
+    pub repo_name: String,
+    pub problems: Vec<String>,
+}
+
+/// 批量导出结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchReport {
+    // 符合筛选条件的钻孔数
+    pub total: usize,
+    pub exported: usize,
+    pub skipped: Vec<SkippedRepo>,
+    // 已写出的文件
+    pub files: Vec<String>,
+    // 是否被取消；按工作簿导出时取消不会写出文件
+    pub cancelled: bool,
+}
+
+/// 批量导出的取消标记，可在其他任务中调用 `cancel`
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 批量导出符合条件的钻孔
+///
+/// 测点数据校验未通过或某个文件写出失败的钻孔跳过并记入结果，其余钻孔继续导出；
+/// 每处理一个钻孔前检查取消标记。按目录导出 kml 格式时必须能确定孔口坐标的投影。
+pub async fn export_batch(
+    request: &BatchExportRequest,
+    options: &ExportOptions,
+    cancel: &CancelFlag,
+    mut on_progress: impl FnMut(&BatchProgress),
+) -> Result<BatchReport> {
+    let mut options = options.clone();
+    options.collars.extend(&request.collars);
+    if request.projection.is_some() {
+        options.projection = request.projection;
+    }
+    let options = &options;
+    if request.mode == BatchMode::Folder {
+        if request.formats.is_empty() {
+            return Err(anyhow!(t(Msg::ExportNoFormat)));
+        }
+        if request.formats.contains(&ExportFormat::Kml) {
+            options
+                .projection
+                .as_ref()
+                .ok_or_else(|| anyhow!(t(Msg::KmlNeedsProjection)))?
+                .validate()?;
+        }
+    }
+    let repos = Repo::query_filtered(request.filter.clone()).await?;
+    let mut report = BatchReport {
+        total: repos.len(),
+        ..BatchReport::default()
+    };
+
+    let path = PathBuf::from(&request.path);
+    let mut workbook = match request.mode {
+        BatchMode::Workbook => {
+            Some(BatchWorkbook::new(&request.path, options.locale)?)
+        }
+        BatchMode::Folder => {
+            std::fs::create_dir_all(&path).map_err(|e| {
+                anyhow!(tf(
+                    Msg::CreateDirFailed,
+                    &[("path", &path.display()), ("error", &e)],
+                ))
+            })?;
+            None
+        }
+    };
+
+    for (index, repo) in repos.iter().enumerate() {
+        if cancel.is_cancelled() {
+            report.cancelled = true;
+            break;
+        }
+
+        let stations = db::query_data_list_by_repo_id(repo.id.unwrap_or_default()).await?;
+        let problems = calc::validate_stations(&stations);
+        if !problems.is_empty() {
+            report.skipped.push(SkippedRepo {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                problems,
+            });
+        } else if let Some(workbook) = workbook.as_mut() {
+            workbook.add_hole(&excel_data(repo, &stations, &path, options))?;
+            report.exported += 1;
+        } else {
+            let mut failures = Vec::new();
+            for format in &request.formats {
+                let file = path.join(file_name(repo, *format));
+                match export_hole(repo, &stations, *format, &file, options).await {
+                    Ok(()) => report.files.push(file.to_string_lossy().to_string()),
+                    Err(e) => failures.push(format!("{}: {:#}", file.display(), e)),
+                }
+            }
+            if failures.is_empty() {
+                report.exported += 1;
+            } else {
+                report.skipped.push(SkippedRepo {
+                    repo_id: repo.id,
+                    repo_name: repo.name.clone(),
+                    problems: failures,
+                });
+            }
+        }
+
+        on_progress(&BatchProgress {
+            done: index + 1,
+            total: report.total,
+            repo_id: repo.id,
+            repo_name: repo.name.clone(),
+        });
+    }
+
+    if let Some(workbook) = workbook {
+        if !report.cancelled {
+            workbook.save()?;
+            report.files.push(request.path.clone());
+        }
+    }
+    Ok(report)
+}
+
+/// CSV 中的列：测点原始读数在前，计算结果在后
+const CSV_COLUMNS: [ColumnField; 17] = [
+    ColumnField::Index,
+    ColumnField::Time,
+    ColumnField::Depth,
+    ColumnField::Pitch,
+    ColumnField::Roll,
+    ColumnField::Heading,
+    ColumnField::DesignPitch,
+    ColumnField::DesignHeading,
+    ColumnField::CorrectedHeading,
+    ColumnField::North,
+    ColumnField::East,
+    ColumnField::Elevation,
+    ColumnField::Lateral,
+    ColumnField::Vertical,
+    ColumnField::DesignLateral,
+    ColumnField::DesignVertical,
+    ColumnField::Dogleg,
+];
+
+/// CSV 字段，含分隔符、引号或换行时加引号
+fn csv_field(out: &mut String, value: &str, delimiter: char) {
+    if value.contains([delimiter, '"', '\r', '\n']) {
+        let _ = write!(out, "\"{}\"", value.replace('"', "\"\""));
+    } else {
+        out.push_str(value);
+    }
+}
+
+/// 测点原始数据与轨迹计算结果 CSV，带 UTF-8 BOM 以便 Excel 正确识别中文表头
+pub fn to_csv(records: &[StationRecord], options: &ExportOptions) -> Result<String> {
+    let csv = options.csv;
+    csv.validate()?;
+    let mut out = String::from("\u{feff}");
+    for (col, field) in CSV_COLUMNS.iter().enumerate() {
+        if col > 0 {
+            out.push(csv.delimiter);
+        }
+        let header = TemplateColumn::new(*field).header_text(options.locale);
+        csv_field(&mut out, &header, csv.delimiter);
+    }
+    out.push_str("\r\n");
+    for record in records {
+        for (col, field) in CSV_COLUMNS.iter().enumerate() {
+            if col > 0 {
+                out.push(csv.delimiter);
+            }
+            let text = match record.value(*field) {
+                FieldValue::Number(value) if *field == ColumnField::Index => value.to_string(),
+                FieldValue::Number(value) => {
+                    let text = format!("{:.*}", options.decimal_places, value);
+                    if csv.decimal_separator == ',' {
+                        text.replace('.', ",")
+                    } else {
+                        text
+                    }
+                }
+                FieldValue::Text(value) => value,
+                FieldValue::CadPoint(x, y) => FieldValue::cad_point(x, y, options.decimal_places),
+                FieldValue::Blank => String::new(),
+            };
+            csv_field(&mut out, &text, csv.delimiter);
+        }
+        out.push_str("\r\n");
+    }
+    Ok(out)
+}
+
+/// JSON 导出中的计算参数
+#[derive(Debug, Serialize)]
+struct JsonParameters {
+    magnetic_declination: f64,
+    collar: Collar,
+    tolerance: ToleranceSettings,
+}
+
+/// 单个钻孔的 JSON 文档
+#[derive(Debug, Serialize)]
+struct HoleDocument<'a> {
+    repo: &'a Repo,
+    parameters: JsonParameters,
+    summary: DeviationSummary,
+    qa: Vec<QaCheck>,
+    passed: bool,
+    stations: Vec<StationRecord>,
+}
+
+/// 结构化 JSON：钻孔信息、计算参数、偏差汇总、质量检查及每个测点的原始数据与计算结果
+pub fn to_json(repo: &Repo, stations: &[DataList], options: &ExportOptions) -> Result<String> {
+    let declination = options.magnetic_declination;
+    let summary = calc::summarize(stations, declination);
+    let document = HoleDocument {
+        repo,
+        parameters: JsonParameters {
+            magnetic_declination: declination,
+            collar: options.collar(repo),
+            tolerance: options.tolerance.clone(),
+        },
+        qa: summary.qa_checks(&options.tolerance),
+        passed: summary.passes(&options.tolerance),
+        summary,
+        stations: station_records(stations, declination),
+    };
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+/// GeoJSON 要素集合：实测轨迹线、孔口点和各测点
+///
+/// 坐标为 `[东, 北, 高程]`，即孔口坐标加上以孔口为原点的轨迹坐标，
+/// 与孔口坐标处在同一（矿区）坐标系中，而不是 RFC 7946 要求的 WGS84 经纬度。
+/// 未给出孔口坐标时孔口位于原点。
+pub fn to_geojson(repo: &Repo, stations: &[DataList], options: &ExportOptions) -> Result<String> {
+    let collar = options.collar(repo);
+    let records = station_records(stations, options.magnetic_declination);
+    let position = |east: f64, north: f64, elevation: f64| {
+        json!([
+            collar.east + east,
+            collar.north + north,
+            collar.elevation + elevation
+        ])
+    };
+    let hole = json!({
+        "repo_id": repo.id,
+        "name": repo.name,
+        "mine": repo.mine,
+        "working_face": repo.work,
+        "site": repo.factory,
+        "rig": repo.drilling,
+        "time": repo.mn_time,
+    });
+    let with_kind = |kind: &str, extra: serde_json::Value| {
+        let mut properties = hole.clone();
+        properties["kind"] = json!(kind);
+        if let (Some(properties), serde_json::Value::Object(extra)) =
+            (properties.as_object_mut(), extra)
+        {
+            properties.extend(extra);
+        }
+        properties
+    };
+
+    let mut line = vec![position(0.0, 0.0, 0.0)];
+    line.extend(
+        records
+            .iter()
+            .map(|r| position(r.east, r.north, r.elevation)),
+    );
+    let summary = calc::summarize(stations, options.magnetic_declination);
+    let mut features = vec![
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "LineString", "coordinates": line },
+            "properties": with_kind("trajectory", json!({
+                "surveyed_depth": summary.surveyed_depth,
+                "final_lateral": summary.final_lateral,
+                "final_vertical": summary.final_vertical,
+                "passed": summary.passes(&options.tolerance),
+            })),
+        }),
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": position(0.0, 0.0, 0.0) },
+            "properties": with_kind("collar", json!({})),
+        }),
+    ];
+    features.extend(records.iter().map(|record| {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": position(record.east, record.north, record.elevation),
+            },
+            "properties": with_kind("station", serde_json::to_value(record).unwrap_or_default()),
+        })
+    }));
+
+    let collection = json!({
+        "type": "FeatureCollection",
+        "name": repo.name,
+        "features": features,
+    });
+    Ok(serde_json::to_string_pretty(&collection)?)
+}
+
+/// 平面图与剖面图 DXF
+pub fn to_dxf(holes: &[DxfHole], options: &ExportOptions) -> String {
+    dxf::to_dxf(
+        holes,
+        options.magnetic_declination,
+        options.decimal_places,
+        &options.dxf,
+    )
+}
+
+/// 查询符合条件的钻孔及测点，测点数据校验未通过的钻孔记入结果并跳过
+async fn query_valid_holes(
+    filter: RepoFilter,
+) -> Result<(BatchReport, Vec<(Repo, Vec<DataList>)>)> {
+    let repos = Repo::query_filtered(filter).await?;
+    let mut report = BatchReport {
+        total: repos.len(),
+        ..BatchReport::default()
+    };
+
+    let mut holes = Vec::new();
+    for repo in repos {
+        let stations = db::query_data_list_by_repo_id(repo.id.unwrap_or_default()).await?;
+        let problems = calc::validate_stations(&stations);
+        if problems.is_empty() {
+            holes.push((repo, stations));
+        } else {
+            report.skipped.push(SkippedRepo {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                problems,
+            });
+        }
+    }
+    if holes.is_empty() {
+        return Err(anyhow!(t(Msg::ExportNoHoles)));
+    }
+    Ok((report, holes))
+}
+
+/// 把符合条件的钻孔画到同一个 DXF 文件，测点数据校验未通过的钻孔跳过
+pub async fn export_dxf(
+    filter: RepoFilter,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<BatchReport> {
+    let (mut report, holes) = query_valid_holes(filter).await?;
+    let holes: Vec<DxfHole> = holes
+        .iter()
+        .map(|(repo, stations)| DxfHole {
+            repo,
+            stations,
+            collar: options.collar(repo),
+        })
+        .collect();
+    std::fs::write(path, to_dxf(&holes, options))?;
+    report.exported = holes.len();
+    report.files.push(path.to_string_lossy().to_string());
+    Ok(report)
+}
+
+/// 平面图、剖面图或等轴测图，视图与样式取自 `options.plot`
+pub fn to_plot(holes: &[DxfHole], format: PlotFormat, options: &ExportOptions) -> Result<Vec<u8>> {
+    plot::render(
+        holes,
+        options.magnetic_declination,
+        options.locale,
+        &options.plot,
+        format,
+    )
+}
+
+/// 把符合条件的钻孔画到同一张轨迹图，测点数据校验未通过的钻孔跳过
+pub async fn export_plot(
+    filter: RepoFilter,
+    path: &Path,
+    format: PlotFormat,
+    options: &ExportOptions,
+) -> Result<BatchReport> {
+    options.plot.validate()?;
+    let (mut report, holes) = query_valid_holes(filter).await?;
+    let holes: Vec<DxfHole> = holes
+        .iter()
+        .map(|(repo, stations)| DxfHole {
+            repo,
+            stations,
+            collar: options.collar(repo),
+        })
+        .collect();
+    std::fs::write(path, to_plot(&holes, format, options)?)?;
+    report.exported = holes.len();
+    report.files.push(path.to_string_lossy().to_string());
+    Ok(report)
+}
+
+/// 把符合条件的钻孔写到同一个 WITSML 文档，每个钻孔一个 trajectory；测点数据校验未通过的钻孔跳过
+pub async fn export_witsml(
+    filter: RepoFilter,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<BatchReport> {
+    let (mut report, holes) = query_valid_holes(filter).await?;
+    let holes: Vec<(&Repo, &[DataList])> = holes
+        .iter()
+        .map(|(repo, stations)| (repo, stations.as_slice()))
+        .collect();
+    std::fs::write(path, witsml::to_witsml(&holes, options))?;
+    report.exported = holes.len();
+    report.files.push(path.to_string_lossy().to_string());
+    Ok(report)
+}
+
+/// 地图概览 KML，孔口坐标按 `options.projection` 反算经纬度
+pub fn to_kml(holes: &[DxfHole], options: &ExportOptions) -> Result<String> {
+    let projection = options
+        .projection
+        .as_ref()
+        .ok_or_else(|| anyhow!(t(Msg::KmlNeedsProjection)))?;
+    kml::to_kml(holes, projection, options)
+}
+
+/// 把符合条件的钻孔写到同一个 KML 文件；测点数据校验未通过或没有孔口坐标的钻孔跳过
+pub async fn export_kml(
+    filter: RepoFilter,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<BatchReport> {
+    if let Some(projection) = &options.projection {
+        projection.validate()?;
+    }
+    let (mut report, holes) = query_valid_holes(filter).await?;
+    let mut located = Vec::new();
+    for (repo, stations) in &holes {
+        match repo.id.and_then(|id| options.collars.get(&id).copied()) {
+            Some(collar) => located.push(DxfHole {
+                repo,
+                stations,
+                collar,
+            }),
+            None => report.skipped.push(SkippedRepo {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                problems: vec![t(Msg::KmlNoCollar).to_string()],
+            }),
+        }
+    }
+    if located.is_empty() {
+        return Err(anyhow!(t(Msg::KmlNoCollars)));
+    }
+    std::fs::write(path, to_kml(&located, options)?)?;
+    report.exported = located.len();
+    report.files.push(path.to_string_lossy().to_string());
+    Ok(report)
+}
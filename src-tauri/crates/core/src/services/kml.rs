@@ -0,0 +1,224 @@
+//! KML 导出，用于在 Google Earth 等地图软件中概览地面钻孔
+//!
+//! 文档按矿井、工作面分文件夹，每个钻孔一个文件夹，内有孔口点和实测轨迹线。
+//! 孔口坐标按投影参数反算为经纬度，轨迹各点的经纬度由孔口坐标加轨迹坐标后逐点反算；
+//! 高程为孔口高程加轨迹高程，按绝对高程（`absolute`）显示。
+//! 点击孔口弹出的气泡中列出钻孔信息与终孔偏差。
+
+use crate::calc;
+use crate::i18n::Msg;
+use crate::services::dxf::DxfHole;
+use crate::services::export::ExportOptions;
+use crate::services::projection::Projection;
+use anyhow::{Context, Result};
+use quick_xml::escape::escape;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// 各钻孔的颜色（RGB），与轨迹图一致，依次循环使用
+const PALETTE: [(u8, u8, u8); 8] = [
+    (31, 100, 191),
+    (214, 48, 49),
+    (39, 137, 62),
+    (230, 126, 34),
+    (142, 68, 173),
+    (22, 160, 133),
+    (192, 57, 43),
+    (52, 73, 94),
+];
+
+/// KML 颜色写作 aabbggrr
+fn kml_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("ff{:02x}{:02x}{:02x}", b, g, r)
+}
+
+/// 经纬度保留 8 位小数（约 1 毫米）
+fn coordinate(longitude: f64, latitude: f64, altitude: f64, decimal_places: usize) -> String {
+    format!(
+        "{:.8},{:.8},{:.*}",
+        longitude, latitude, decimal_places, altitude
+    )
+}
+
+/// 气泡内容：钻孔信息表格
+fn balloon(rows: &[(&str, String)]) -> String {
+    let mut html = String::from("<table>");
+    for (label, value) in rows {
+        let _ = write!(
+            html,
+            "<tr><th align=\"left\">{}</th><td>{}</td></tr>",
+            escape(label),
+            escape(value.as_str())
+        );
+    }
+    html.push_str("</table>");
+    html
+}
+
+/// 单个钻孔的文件夹：孔口点和实测轨迹线
+fn write_hole(
+    out: &mut String,
+    hole: &DxfHole,
+    style: usize,
+    projection: &Projection,
+    options: &ExportOptions,
+) -> Result<()> {
+    let text = |msg: Msg| msg.text(options.locale);
+    let dp = options.decimal_places;
+    let collar = hole.collar;
+    let repo = hole.repo;
+    let geographic = |east: f64, north: f64| {
+        projection
+            .to_geographic(east, north)
+            .with_context(|| Msg::HoleContext.format(options.locale, &[("name", &repo.name)]))
+    };
+
+    let (longitude, latitude) = geographic(collar.east, collar.north)?;
+    let mut line = vec![coordinate(longitude, latitude, collar.elevation, dp)];
+    for point in calc::trajectory(hole.stations, options.magnetic_declination) {
+        let (longitude, latitude) =
+            geographic(collar.east + point.east, collar.north + point.north)?;
+        line.push(coordinate(
+            longitude,
+            latitude,
+            collar.elevation + point.elevation,
+            dp,
+        ));
+    }
+
+    let summary = calc::summarize(hole.stations, options.magnetic_declination);
+    let verdict = if summary.passes(&options.tolerance) {
+        text(Msg::VerdictPass)
+    } else {
+        text(Msg::VerdictFail)
+    };
+    let mut rows = vec![
+        (text(Msg::FieldMine), repo.mine.clone()),
+        (text(Msg::FieldWorkingFace), repo.work.clone()),
+        (text(Msg::FieldDrillingSite), repo.factory.clone()),
+        (text(Msg::FieldRig), repo.drilling.clone()),
+        (text(Msg::FieldSurveyTime), repo.mn_time.clone()),
+        (text(Msg::FieldHoleLength), format!("{} m", repo.len)),
+        (
+            text(Msg::FieldSurveyedDepth),
+            format!("{:.2} m", summary.surveyed_depth),
+        ),
+        (
+            text(Msg::FieldStationCount),
+            summary.station_count.to_string(),
+        ),
+        (
+            text(Msg::FieldCollar),
+            format!(
+                "E {:.3}  N {:.3}  Z {:.3}",
+                collar.east, collar.north, collar.elevation
+            ),
+        ),
+        (
+            text(Msg::FieldLongitudeLatitude),
+            format!("{:.6}°, {:.6}°", longitude, latitude),
+        ),
+        (
+            text(Msg::FinalLateral),
+            format!("{:.*} m", dp, summary.final_lateral),
+        ),
+        (
+            text(Msg::FinalVertical),
+            format!("{:.*} m", dp, summary.final_vertical),
+        ),
+        (text(Msg::QaVerdict), verdict.to_string()),
+    ];
+    if let Some(notes) = repo.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        rows.push((text(Msg::FieldNotes), notes.to_string()));
+    }
+
+    let name = escape(repo.name.as_str());
+    let _ = writeln!(out, "<Folder>\n<name>{}</name>", name);
+    let _ = writeln!(
+        out,
+        "<Placemark>\n<name>{}</name>\n<styleUrl>#hole-{}</styleUrl>\n<description><![CDATA[{}]]></description>",
+        name,
+        style,
+        balloon(&rows)
+    );
+    let _ = writeln!(
+        out,
+        "<Point><altitudeMode>absolute</altitudeMode><coordinates>{}</coordinates></Point>\n</Placemark>",
+        line[0]
+    );
+    let _ = writeln!(
+        out,
+        "<Placemark>\n<name>{} {}</name>\n<styleUrl>#hole-{}</styleUrl>",
+        name,
+        escape(text(Msg::KmlTrajectory)),
+        style
+    );
+    let _ = writeln!(
+        out,
+        "<LineString><altitudeMode>absolute</altitudeMode><coordinates>{}</coordinates></LineString>\n</Placemark>",
+        line.join(" ")
+    );
+    out.push_str("</Folder>\n");
+    Ok(())
+}
+
+/// 把钻孔写到一个 KML 文档，按矿井、工作面分文件夹
+///
+/// 孔口或轨迹坐标超出投影带范围时返回错误，指明钻孔名称。
+pub fn to_kml(
+    holes: &[DxfHole],
+    projection: &Projection,
+    options: &ExportOptions,
+) -> Result<String> {
+    projection.validate()?;
+    let unspecified = Msg::KmlUnspecified.text(options.locale);
+    let folder_name = |name: &str| {
+        if name.trim().is_empty() {
+            unspecified.to_string()
+        } else {
+            name.trim().to_string()
+        }
+    };
+
+    // 矿井 → 工作面 → 钻孔序号，钻孔保持原顺序
+    let mut folders: BTreeMap<String, BTreeMap<String, Vec<usize>>> = BTreeMap::new();
+    for (index, hole) in holes.iter().enumerate() {
+        folders
+            .entry(folder_name(&hole.repo.mine))
+            .or_default()
+            .entry(folder_name(&hole.repo.work))
+            .or_default()
+            .push(index);
+    }
+
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n<name>DrillTrack</name>\n",
+    );
+    for (index, color) in PALETTE.iter().enumerate() {
+        let color = kml_color(*color);
+        let _ = writeln!(
+            out,
+            "<Style id=\"hole-{}\"><IconStyle><color>{}</color></IconStyle><LineStyle><color>{}</color><width>3</width></LineStyle></Style>",
+            index, color, color
+        );
+    }
+    for (mine, faces) in &folders {
+        let _ = writeln!(out, "<Folder>\n<name>{}</name>", escape(mine.as_str()));
+        for (face, indices) in faces {
+            let _ = writeln!(out, "<Folder>\n<name>{}</name>", escape(face.as_str()));
+            for &index in indices {
+                write_hole(
+                    &mut out,
+                    &holes[index],
+                    index % PALETTE.len(),
+                    projection,
+                    options,
+                )?;
+            }
+            out.push_str("</Folder>\n");
+        }
+        out.push_str("</Folder>\n");
+    }
+    out.push_str("</Document>\n</kml>\n");
+    Ok(out)
+}
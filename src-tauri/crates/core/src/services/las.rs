@@ -0,0 +1,174 @@
+//! LAS 2.0 测井数据格式导出
+//!
+//! 以测深为索引，每个测点一行，曲线为俯仰角、翻滚角、方位角、坐标、垂深和狗腿度。
+//! 助记符与说明按 LAS 惯例用英文；钻孔名称等取自数据库的文本原样写出（UTF-8）。
+
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use crate::services::export::ExportOptions;
+use crate::services::records::station_records;
+use std::fmt::Write as _;
+
+/// 缺失值，原样写出，不受小数位数影响
+const NULL_VALUE: &str = "-999.25";
+
+/// 曲线：助记符、单位、说明
+const CURVES: [(&str, &str, &str); 9] = [
+    ("DEPT", "M", "Measured depth along hole"),
+    ("PITCH", "DEG", "Pitch, positive upward"),
+    ("ROLL", "DEG", "Tool roll"),
+    ("HDG", "DEG", "Magnetic heading"),
+    ("AZIM", "DEG", "Azimuth corrected for declination"),
+    ("NORTH", "M", "Northing"),
+    ("EAST", "M", "Easting"),
+    ("TVD", "M", "True vertical depth below collar"),
+    ("DLS", "DEG/30M", "Dogleg severity"),
+];
+
+/// 头部一行：`MNEM.UNIT  数据 : 说明`
+///
+/// LAS 2.0 以一行中最后一个冒号分隔数据与说明，数据中可以含冒号（如时间），说明中不能。
+fn header_line(out: &mut String, mnemonic: &str, unit: &str, data: &str, description: &str) {
+    let data: String = data
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let _ = writeln!(
+        out,
+        " {:<16} {:<32} : {}",
+        format!("{}.{}", mnemonic, unit),
+        data.trim(),
+        description
+    );
+}
+
+/// 测点的测深间隔；间隔不均匀时为 0（LAS 规定的可变步长）
+fn depth_step(stations: &[DataList]) -> f64 {
+    let mut steps = stations
+        .windows(2)
+        .map(|pair| pair[1].depth - pair[0].depth);
+    match steps.next() {
+        Some(first) if steps.all(|step| (step - first).abs() < 1e-6) => first,
+        _ => 0.0,
+    }
+}
+
+/// 单个钻孔的 LAS 2.0 文件
+///
+/// 北、东坐标为孔口坐标加轨迹坐标，垂深自孔口向下为正；孔口坐标与磁偏角写在参数段中。
+pub fn to_las(repo: &Repo, stations: &[DataList], options: &ExportOptions) -> String {
+    let dp = options.decimal_places;
+    let collar = options.collar(repo);
+    let records = station_records(stations, options.magnetic_declination);
+    let number = |value: f64| format!("{:.*}", dp, value);
+    let mut out = String::new();
+
+    out.push_str("~Version Information\n");
+    header_line(
+        &mut out,
+        "VERS",
+        "",
+        "2.0",
+        "CWLS LOG ASCII STANDARD - VERSION 2.0",
+    );
+    header_line(&mut out, "WRAP", "", "NO", "One line per depth step");
+
+    out.push_str("~Well Information\n");
+    out.push_str("#MNEM.UNIT        DATA                             : DESCRIPTION\n");
+    let first = stations.first().map_or(0.0, |s| s.depth);
+    let last = stations.last().map_or(0.0, |s| s.depth);
+    header_line(&mut out, "STRT", "M", &number(first), "Start depth");
+    header_line(&mut out, "STOP", "M", &number(last), "Stop depth");
+    header_line(&mut out, "STEP", "M", &number(depth_step(stations)), "Step");
+    header_line(&mut out, "NULL", "", NULL_VALUE, "Null value");
+    let company = options.pdf.company.as_deref().unwrap_or_default();
+    header_line(&mut out, "COMP", "", company, "Company");
+    header_line(&mut out, "WELL", "", &repo.name, "Hole name");
+    header_line(&mut out, "FLD", "", &repo.mine, "Mine");
+    header_line(&mut out, "LOC", "", &repo.work, "Working face");
+    header_line(&mut out, "SITE", "", &repo.factory, "Drilling site");
+    header_line(&mut out, "RIG", "", &repo.drilling, "Drilling rig");
+    header_line(&mut out, "DATE", "", &repo.mn_time, "Survey date");
+    let uwi = repo
+        .uuid
+        .clone()
+        .unwrap_or_else(|| repo.id.unwrap_or_default().to_string());
+    header_line(&mut out, "UWI", "", &uwi, "Unique hole identifier");
+
+    out.push_str("~Curve Information\n");
+    out.push_str("#MNEM.UNIT        API CODE                         : DESCRIPTION\n");
+    for (mnemonic, unit, description) in CURVES {
+        header_line(&mut out, mnemonic, unit, "", description);
+    }
+
+    out.push_str("~Parameter Information\n");
+    header_line(
+        &mut out,
+        "XCOORD",
+        "M",
+        &number(collar.east),
+        "Collar easting",
+    );
+    header_line(
+        &mut out,
+        "YCOORD",
+        "M",
+        &number(collar.north),
+        "Collar northing",
+    );
+    header_line(
+        &mut out,
+        "EGL",
+        "M",
+        &number(collar.elevation),
+        "Collar elevation",
+    );
+    header_line(
+        &mut out,
+        "MAGDEC",
+        "DEG",
+        &options.magnetic_declination.to_string(),
+        "Magnetic declination, east positive",
+    );
+
+    // 备注原样放在 Other 段，行首避开段标记 `~`
+    if let Some(notes) = repo.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        out.push_str("~Other\n");
+        for line in notes.lines() {
+            let _ = writeln!(out, " {}", line.trim_end());
+        }
+    }
+
+    let width = dp + 9;
+    out.push_str("~A ");
+    for (mnemonic, ..) in CURVES {
+        let _ = write!(out, " {:>width$}", mnemonic);
+    }
+    out.push('\n');
+    for record in &records {
+        let values = [
+            Some(record.depth),
+            record.pitch,
+            record.roll,
+            record.heading,
+            Some(record.corrected_heading),
+            Some(collar.north + record.north),
+            Some(collar.east + record.east),
+            Some(-record.elevation),
+            Some(record.dogleg),
+        ];
+        out.push_str("   ");
+        for value in values {
+            match value {
+                Some(value) => {
+                    let _ = write!(out, " {:>width$.dp$}", value);
+                }
+                None => {
+                    let _ = write!(out, " {:>width$}", NULL_VALUE);
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
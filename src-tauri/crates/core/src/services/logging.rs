@@ -0,0 +1,127 @@
+use crate::i18n::{t, tf, Msg};
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// 日志文件名前缀与后缀，按天滚动生成 drilltrack.YYYY-MM-DD.log
+const LOG_PREFIX: &str = "drilltrack";
+const LOG_SUFFIX: &str = "log";
+
+/// 保留的日志文件天数
+const MAX_LOG_FILES: usize = 14;
+
+/// 默认返回的日志行数上限
+const DEFAULT_LOG_LINES: usize = 200;
+
+/// 日志目录
+static LOG_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// 后台写线程的守卫，程序退出前必须保持存活以免丢失缓冲中的日志
+static LOG_GUARD: OnceCell<WorkerGuard> = OnceCell::new();
+
+/// 日志行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub file: String,
+    pub line: String,
+}
+
+/// 初始化日志：写入应用数据目录下 logs 中按天滚动的文件，调试构建同时输出到控制台
+///
+/// 默认级别为 info（调试构建为 debug），可通过 RUST_LOG 环境变量覆盖。
+pub fn init_logging(app_data_dir: &Path) -> Result<PathBuf> {
+    let dir = app_data_dir.join("logs");
+    std::fs::create_dir_all(&dir)?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let default_level = if cfg!(debug_assertions) { "debug" } else { "info" };
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("{},hyper=info,warp=info", default_level)));
+
+    let file_layer = fmt::layer().with_writer(writer).with_ansi(false);
+    let console_layer = cfg!(debug_assertions).then(fmt::layer);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(console_layer)
+        .try_init()
+        .map_err(|e| anyhow!(tf(Msg::LoggingInitFailed, &[("error", &e)])))?;
+
+    let _ = LOG_GUARD.set(guard);
+    let _ = LOG_DIR.set(dir.clone());
+    Ok(dir)
+}
+
+/// 日志目录
+pub fn log_dir() -> Result<&'static PathBuf> {
+    LOG_DIR
+        .get()
+        .ok_or_else(|| anyhow!(t(Msg::LoggingNotInitialized)))
+}
+
+/// 按文件名倒序（即按日期从新到旧）列出日志文件
+fn log_files() -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(log_dir()?)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(LOG_PREFIX) && n.ends_with(LOG_SUFFIX))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+/// 读取最近的日志行，按时间顺序返回
+///
+/// `level` 指定时只保留该级别的行（如 "WARN"、"ERROR"），用于支持界面快速定位问题。
+pub fn recent_logs(limit: Option<usize>, level: Option<&str>) -> Result<Vec<LogLine>> {
+    let limit = limit.unwrap_or(DEFAULT_LOG_LINES);
+    let level = level.map(|l| format!(" {} ", l.trim().to_uppercase()));
+    let mut lines = Vec::new();
+
+    for path in log_files()? {
+        if lines.len() >= limit {
+            break;
+        }
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let text = String::from_utf8_lossy(&std::fs::read(&path)?).to_string();
+
+        // 从文件末尾向前取，保证优先返回最新的行
+        let mut from_file: Vec<LogLine> = text
+            .lines()
+            .rev()
+            .filter(|line| !line.trim().is_empty())
+            .filter(|line| level.as_ref().map(|l| line.contains(l.as_str())).unwrap_or(true))
+            .take(limit - lines.len())
+            .map(|line| LogLine {
+                file: file.clone(),
+                line: line.to_string(),
+            })
+            .collect();
+        lines.append(&mut from_file);
+    }
+
+    lines.reverse();
+    Ok(lines)
+}
@@ -0,0 +1,344 @@
+use crate::i18n::{t, Msg};
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use crate::services::archive::{self, ArchiveHole, ExistingRepos, HolePreview, HoleStatus};
+use crate::services::backup;
+use crate::services::db::{get_conn, get_db_status, DbError};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 附加数据库使用的别名
+const SOURCE_SCHEMA: &str = "merge_src";
+
+/// 合并报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeReport {
+    pub source_path: String,
+    pub source_repo_count: usize,
+    // 是否只做比对、未写入
+    pub dry_run: bool,
+    pub copied: Vec<HolePreview>,
+    pub duplicates: Vec<HolePreview>,
+    pub conflicts: Vec<HolePreview>,
+    pub new_repo_ids: Vec<i64>,
+}
+
+/// 附加库中是否存在某列（旧版本数据库没有后来新增的列）
+fn source_has_column(c: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = c.prepare(&format!("PRAGMA {}.table_info({})", SOURCE_SCHEMA, table))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        if name == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 读取附加库中的全部钻孔及测点
+fn read_source_holes(c: &Connection) -> rusqlite::Result<Vec<ArchiveHole>> {
+    let optional = |table: &str, column: &'static str| -> rusqlite::Result<&'static str> {
+        Ok(if source_has_column(c, table, column)? {
+            column
+        } else {
+            "NULL"
+        })
+    };
+
+    let mut stmt = c.prepare(&format!(
+        "SELECT id, name, mn_time, len, mine, work, factory, drilling, {}, {}
+         FROM {}.repo ORDER BY id",
+        optional("repo", "notes")?,
+        optional("repo", "uuid")?,
+        SOURCE_SCHEMA
+    ))?;
+    let repos = stmt
+        .query_map([], |row| {
+            Ok(Repo {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                mn_time: row.get(2)?,
+                len: row.get(3)?,
+                mine: row.get(4)?,
+                work: row.get(5)?,
+                factory: row.get(6)?,
+                drilling: row.get(7)?,
+                notes: row.get(8)?,
+                uuid: row.get(9)?,
+                mine_id: None,
+                working_face_id: None,
+                drilling_site_id: None,
+                rig_id: None,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut station_stmt = c.prepare(&format!(
+        "SELECT id, time, depth, pitch, roll, heading, repo_id, design_pitch, design_heading, {}
+         FROM {}.data_list
+         WHERE repo_id = ?
+         ORDER BY depth",
+        optional("data_list", "uuid")?,
+        SOURCE_SCHEMA
+    ))?;
+
+    let mut holes = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let stations = station_stmt
+            .query_map(params![repo.id], DataList::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        holes.push(ArchiveHole { repo, stations });
+    }
+    Ok(holes)
+}
+
+/// 比对并复制不冲突的钻孔，写入在同一事务内完成，dry_run 时回滚
+fn merge_attached(c: &mut Connection, report: &mut MergeReport) -> rusqlite::Result<()> {
+    let holes = read_source_holes(c)?;
+    report.source_repo_count = holes.len();
+
+    let tx = c.transaction()?;
+    let mut existing = ExistingRepos::load(&tx)?;
+
+    for hole in &holes {
+        let preview = archive::classify(&tx, &existing, hole)?;
+        match preview.status {
+            HoleStatus::New => {
+                // dry_run 时同样写入、最后回滚，源库内重复的钻孔与实际合并一样记为重复
+                let repo_id = archive::insert_hole(&tx, hole, None)?;
+                existing.insert(&hole.repo, repo_id);
+                if !report.dry_run {
+                    report.new_repo_ids.push(repo_id);
+                }
+                report.copied.push(preview);
+            }
+            HoleStatus::Duplicate => report.duplicates.push(preview),
+            HoleStatus::Conflict => report.conflicts.push(preview),
+        }
+    }
+
+    if report.dry_run {
+        tx.rollback()
+    } else {
+        tx.commit()
+    }
+}
+
+/// 把另一个 DrillTrack 数据库合并到当前数据库
+///
+/// 优先按 UUID、其次按 名称 + 矿井 + 工作面 + 测量时间 识别同一钻孔：数据完全一致的记为重复，
+/// 数据不同的记为冲突且不复制，其余钻孔连同测点一起复制。
+pub async fn merge_database(source_path: &str, dry_run: bool) -> Result<MergeReport, DbError> {
+    backup::validate_backup(source_path)?;
+
+    if let Some(current) = get_db_status().await.path {
+        let same = match (Path::new(&current).canonicalize(), Path::new(source_path).canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => current == source_path,
+        };
+        if same {
+            return Err(DbError::Invalid(t(Msg::MergeSelf).to_string()));
+        }
+    }
+
+    let conn_guard = get_conn().await?;
+    let conn = conn_guard.as_ref().unwrap();
+    let source = source_path.to_string();
+
+    conn.call(move |c| {
+        c.execute(
+            &format!("ATTACH DATABASE ? AS {}", SOURCE_SCHEMA),
+            params![source],
+        )?;
+
+        let mut report = MergeReport {
+            source_path: source,
+            dry_run,
+            ..Default::default()
+        };
+        let result = merge_attached(c, &mut report);
+
+        // 无论成功与否都要分离附加库，两者都失败时返回合并的错误
+        let detached = c.execute(&format!("DETACH DATABASE {}", SOURCE_SCHEMA), []);
+        result?;
+        detached?;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| DbError::Other(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::db::create_schema;
+
+    /// 目标库，附加一个旧版本结构（没有 notes、uuid 列）的源库
+    fn connection_with_legacy_source() -> Connection {
+        let mut c = Connection::open_in_memory().unwrap();
+        create_schema(&mut c).unwrap();
+        c.execute_batch(&format!(
+            "ATTACH DATABASE ':memory:' AS {schema};
+             CREATE TABLE {schema}.repo (
+                 id INTEGER PRIMARY KEY, name TEXT NOT NULL, mn_time TEXT NOT NULL,
+                 len INTEGER NOT NULL, mine TEXT NOT NULL, work TEXT NOT NULL,
+                 factory TEXT NOT NULL, drilling TEXT NOT NULL
+             );
+             CREATE TABLE {schema}.data_list (
+                 id INTEGER PRIMARY KEY, time TEXT, depth REAL NOT NULL, pitch REAL,
+                 roll REAL, heading REAL, repo_id INTEGER, design_pitch REAL,
+                 design_heading REAL
+             );",
+            schema = SOURCE_SCHEMA
+        ))
+        .unwrap();
+        c
+    }
+
+    fn repo(name: &str) -> Repo {
+        Repo {
+            id: None,
+            uuid: None,
+            name: name.to_string(),
+            mn_time: "2024-05-06 08:30:00".to_string(),
+            len: 20,
+            mine: "一矿".to_string(),
+            work: "1201".to_string(),
+            factory: "3#".to_string(),
+            drilling: "ZDY-6000".to_string(),
+            notes: None,
+            mine_id: None,
+            working_face_id: None,
+            drilling_site_id: None,
+            rig_id: None,
+        }
+    }
+
+    fn stations(headings: &[f64]) -> Vec<DataList> {
+        headings
+            .iter()
+            .enumerate()
+            .map(|(i, heading)| DataList {
+                id: None,
+                uuid: None,
+                time: None,
+                depth: (i + 1) as f64 * 10.0,
+                pitch: Some(-2.0),
+                roll: None,
+                heading: Some(*heading),
+                repo_id: None,
+                design_pitch: Some(-2.0),
+                design_heading: Some(90.0),
+            })
+            .collect()
+    }
+
+    fn add_local(c: &Connection, name: &str, headings: &[f64]) {
+        let hole = ArchiveHole {
+            repo: repo(name),
+            stations: stations(headings),
+        };
+        archive::insert_hole(c, &hole, None).unwrap();
+    }
+
+    fn add_source(c: &Connection, name: &str, headings: &[f64]) {
+        let r = repo(name);
+        c.execute(
+            &format!(
+                "INSERT INTO {}.repo (name, mn_time, len, mine, work, factory, drilling)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                SOURCE_SCHEMA
+            ),
+            params![r.name, r.mn_time, r.len, r.mine, r.work, r.factory, r.drilling],
+        )
+        .unwrap();
+        let repo_id = c.last_insert_rowid();
+        for s in stations(headings) {
+            c.execute(
+                &format!(
+                    "INSERT INTO {}.data_list
+                     (depth, pitch, heading, repo_id, design_pitch, design_heading)
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                    SOURCE_SCHEMA
+                ),
+                params![
+                    s.depth,
+                    s.pitch,
+                    s.heading,
+                    repo_id,
+                    s.design_pitch,
+                    s.design_heading
+                ],
+            )
+            .unwrap();
+        }
+    }
+
+    fn count(c: &Connection, table: &str) -> i64 {
+        c.query_row(&format!("SELECT COUNT(*) FROM main.{}", table), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    /// 源库：与本地相同、与本地冲突、新钻孔各一个，新钻孔在源库中重复出现
+    fn fixture() -> Connection {
+        let c = connection_with_legacy_source();
+        add_local(&c, "ZK-1", &[90.0, 91.0]);
+        add_local(&c, "ZK-2", &[90.0]);
+        add_source(&c, "ZK-1", &[90.0, 91.0]);
+        add_source(&c, "ZK-2", &[95.0]);
+        add_source(&c, "ZK-3", &[88.0, 89.0]);
+        add_source(&c, "ZK-3", &[88.0, 89.0]);
+        c
+    }
+
+    fn names(previews: &[HolePreview]) -> Vec<&str> {
+        previews.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn merge_copies_only_new_holes() {
+        let mut c = fixture();
+        let mut report = MergeReport::default();
+        merge_attached(&mut c, &mut report).unwrap();
+
+        assert_eq!(report.source_repo_count, 4);
+        assert_eq!(names(&report.copied), ["ZK-3"]);
+        assert_eq!(names(&report.duplicates), ["ZK-1", "ZK-3"]);
+        assert_eq!(names(&report.conflicts), ["ZK-2"]);
+        assert_eq!(report.new_repo_ids.len(), 1);
+        assert_eq!(count(&c, "repo"), 3);
+        assert_eq!(count(&c, "data_list"), 5);
+
+        // 旧版本源库没有 UUID，复制时生成新的 UUID
+        let uuid: Option<String> = c
+            .query_row(
+                "SELECT uuid FROM main.repo WHERE id = ?",
+                [report.new_repo_ids[0]],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(uuid.is_some());
+    }
+
+    #[test]
+    fn dry_run_reports_without_writing() {
+        let mut c = fixture();
+        let mut report = MergeReport {
+            dry_run: true,
+            ..Default::default()
+        };
+        merge_attached(&mut c, &mut report).unwrap();
+
+        // 与实际合并的结果一致
+        assert_eq!(names(&report.copied), ["ZK-3"]);
+        assert_eq!(names(&report.duplicates), ["ZK-1", "ZK-3"]);
+        assert_eq!(names(&report.conflicts), ["ZK-2"]);
+        assert!(report.new_repo_ids.is_empty());
+        assert_eq!(count(&c, "repo"), 2);
+        assert_eq!(count(&c, "data_list"), 3);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::models::location;
+use crate::models::repo::new_uuid;
+use rusqlite::{params, Connection, Transaction};
+
+/// 当前程序支持的最新结构版本（保存在 PRAGMA user_version 中）
+pub const LATEST_VERSION: i32 = 3;
+
+/// 按 PRAGMA user_version 记录的版本号依次执行尚未应用的迁移
+pub fn run(c: &mut Connection) -> rusqlite::Result<()> {
+    let version: i32 = c.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if version < 1 {
+        let tx = c.transaction()?;
+        migrate_v1_repo_search(&tx)?;
+        tx.pragma_update(None, "user_version", 1)?;
+        tx.commit()?;
+    }
+
+    if version < 2 {
+        let tx = c.transaction()?;
+        migrate_v2_location_hierarchy(&tx)?;
+        tx.pragma_update(None, "user_version", 2)?;
+        tx.commit()?;
+    }
+
+    if version < 3 {
+        let tx = c.transaction()?;
+        migrate_v3_uuids(&tx)?;
+        tx.pragma_update(None, "user_version", 3)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// 检查表中是否已存在某列
+fn has_column(tx: &Transaction, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        if name == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// v1: repo 增加备注列，并建立 FTS5 全文索引及同步触发器
+///
+/// 使用 trigram 分词器，使中文矿井、工作面名称可以按任意连续三个字符检索。
+fn migrate_v1_repo_search(tx: &Transaction) -> rusqlite::Result<()> {
+    if !has_column(tx, "repo", "notes")? {
+        tx.execute_batch("ALTER TABLE repo ADD COLUMN notes TEXT;")?;
+    }
+
+    tx.execute_batch(
+        "
+        CREATE VIRTUAL TABLE IF NOT EXISTS repo_fts USING fts5(
+            name, mn_time, mine, work, factory, drilling, notes,
+            content='repo',
+            content_rowid='id',
+            tokenize='trigram'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS repo_fts_ai AFTER INSERT ON repo BEGIN
+            INSERT INTO repo_fts(rowid, name, mn_time, mine, work, factory, drilling, notes)
+            VALUES (new.id, new.name, new.mn_time, new.mine, new.work, new.factory, new.drilling, new.notes);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS repo_fts_ad AFTER DELETE ON repo BEGIN
+            INSERT INTO repo_fts(repo_fts, rowid, name, mn_time, mine, work, factory, drilling, notes)
+            VALUES ('delete', old.id, old.name, old.mn_time, old.mine, old.work, old.factory, old.drilling, old.notes);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS repo_fts_au AFTER UPDATE ON repo BEGIN
+            INSERT INTO repo_fts(repo_fts, rowid, name, mn_time, mine, work, factory, drilling, notes)
+            VALUES ('delete', old.id, old.name, old.mn_time, old.mine, old.work, old.factory, old.drilling, old.notes);
+            INSERT INTO repo_fts(rowid, name, mn_time, mine, work, factory, drilling, notes)
+            VALUES (new.id, new.name, new.mn_time, new.mine, new.work, new.factory, new.drilling, new.notes);
+        END;
+
+        INSERT INTO repo_fts(repo_fts) VALUES ('rebuild');
+        ",
+    )
+}
+
+/// v2: 矿井 / 工作面 / 钻场 / 钻机规范化建表，并把已有 repo 的文本字段去重后关联
+fn migrate_v2_location_hierarchy(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS mine (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            name_key TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS working_face (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            mine_id INTEGER NOT NULL REFERENCES mine(id),
+            name TEXT NOT NULL,
+            name_key TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE (mine_id, name_key)
+        );
+
+        CREATE TABLE IF NOT EXISTS drilling_site (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            working_face_id INTEGER NOT NULL REFERENCES working_face(id),
+            name TEXT NOT NULL,
+            name_key TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE (working_face_id, name_key)
+        );
+
+        CREATE TABLE IF NOT EXISTS rig (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            name_key TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        ",
+    )?;
+
+    for column in ["mine_id", "working_face_id", "drilling_site_id", "rig_id"] {
+        if !has_column(tx, "repo", column)? {
+            tx.execute_batch(&format!("ALTER TABLE repo ADD COLUMN {} INTEGER;", column))?;
+        }
+    }
+
+    tx.execute_batch(
+        "
+        CREATE INDEX IF NOT EXISTS idx_repo_mine_id ON repo(mine_id);
+        CREATE INDEX IF NOT EXISTS idx_repo_working_face_id ON repo(working_face_id);
+        CREATE INDEX IF NOT EXISTS idx_repo_drilling_site_id ON repo(drilling_site_id);
+        CREATE INDEX IF NOT EXISTS idx_repo_rig_id ON repo(rig_id);
+        ",
+    )?;
+
+    // 出现次数多的写法优先建节点，作为规范名称
+    let groups: Vec<(String, String, String, String)> = {
+        let mut stmt = tx.prepare(
+            "SELECT mine, work, factory, drilling FROM repo
+             GROUP BY mine, work, factory, drilling
+             ORDER BY COUNT(*) DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    for (mine, work, factory, drilling) in groups {
+        let ids = location::resolve_ids(tx, &mine, &work, &factory, &drilling)?;
+        tx.execute(
+            "UPDATE repo SET mine_id = ?, working_face_id = ?, drilling_site_id = ?, rig_id = ?
+             WHERE mine = ? AND work = ? AND factory = ? AND drilling = ?",
+            params![
+                ids.mine_id,
+                ids.working_face_id,
+                ids.drilling_site_id,
+                ids.rig_id,
+                mine,
+                work,
+                factory,
+                drilling
+            ],
+        )?;
+    }
+
+    // 文本列统一为规范名称
+    location::sync_repo_names(tx, None)
+}
+
+/// v3: repo 与 data_list 增加全局唯一 UUID，并为已有数据补齐
+fn migrate_v3_uuids(tx: &Transaction) -> rusqlite::Result<()> {
+    for table in ["repo", "data_list"] {
+        if !has_column(tx, table, "uuid")? {
+            tx.execute_batch(&format!("ALTER TABLE {} ADD COLUMN uuid TEXT;", table))?;
+        }
+
+        let ids: Vec<i64> = {
+            let mut stmt = tx.prepare(&format!("SELECT id FROM {} WHERE uuid IS NULL", table))?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut update = tx.prepare(&format!("UPDATE {} SET uuid = ? WHERE id = ?", table))?;
+        for id in ids {
+            update.execute(params![new_uuid(), id])?;
+        }
+
+        tx.execute_batch(&format!(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_{table}_uuid ON {table}(uuid);",
+            table = table
+        ))?;
+    }
+    Ok(())
+}
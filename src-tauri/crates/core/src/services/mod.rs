@@ -0,0 +1,23 @@
+pub mod archive;
+pub mod backup;
+pub mod db; 
+pub mod discovery;
+pub mod dxf;
+pub mod export;
+pub mod kml;
+pub mod las;
+pub mod logging;
+pub mod merge;
+pub mod migrations;
+pub mod pdf;
+pub mod plot;
+pub mod projection;
+pub mod records;
+pub mod router;
+pub mod scr;
+pub mod search;
+pub mod server;
+pub mod settings;
+pub mod templates;
+pub mod witsml;
+pub mod workspace;
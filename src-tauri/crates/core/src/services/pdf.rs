@@ -0,0 +1,1273 @@
+use crate::calc::{self, QaItem};
+use crate::i18n::{t, tf, Locale, Msg};
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use crate::services::export::ExportOptions;
+use crate::services::plot::{self, Anchor, ChartSeries, Scene, Shape};
+use crate::services::records::{station_records, FieldValue, StationRecord};
+use crate::services::templates::{ColumnField, TemplateColumn, ValueKind};
+use anyhow::{anyhow, Context, Result};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+// A4 纸张尺寸与页边距（点）
+const PAGE_WIDTH: f64 = 595.28;
+const PAGE_HEIGHT: f64 = 841.89;
+const MARGIN: f64 = 40.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - 2.0 * MARGIN;
+// 页眉、页脚占用的高度
+const HEADER_HEIGHT: f64 = 50.0;
+const FOOTER_HEIGHT: f64 = 30.0;
+// 页眉标志的最大尺寸
+const LOGO_HEIGHT: f64 = 30.0;
+const LOGO_MAX_WIDTH: f64 = 120.0;
+
+type Rgb = (f64, f64, f64);
+
+const BLACK: Rgb = (0.0, 0.0, 0.0);
+const GRAY: Rgb = (0.45, 0.45, 0.45);
+const LIGHT_GRAY: Rgb = (0.85, 0.85, 0.85);
+const HEADER_FILL: Rgb = (0.85, 0.9, 0.96);
+const ACTUAL_COLOR: Rgb = (0.12, 0.39, 0.75);
+// 折线图中实测与设计曲线的颜色，与轨迹图的前两种颜色相同
+const ACTUAL_SERIES: plot::Color = (31, 100, 191);
+const DESIGN_SERIES: plot::Color = (214, 48, 49);
+const PASS_COLOR: Rgb = (0.1, 0.5, 0.2);
+const FAIL_COLOR: Rgb = (0.8, 0.1, 0.1);
+
+/// 测点数据表的列，与 CSV 一样取自共用的导出记录
+const STATION_COLUMNS: [ColumnField; 13] = [
+    ColumnField::Index,
+    ColumnField::Depth,
+    ColumnField::Pitch,
+    ColumnField::Heading,
+    ColumnField::DesignPitch,
+    ColumnField::DesignHeading,
+    ColumnField::Lateral,
+    ColumnField::Vertical,
+    ColumnField::DesignVertical,
+    ColumnField::North,
+    ColumnField::East,
+    ColumnField::Elevation,
+    ColumnField::Dogleg,
+];
+
+/// Helvetica 中 ASCII 可打印字符（空格到 `~`）的宽度，单位为 1/1000 字号
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+    278, // 空格 - /
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // 0 - 9
+    278, 278, 584, 584, 584, 556, 1015, // : - @
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667,
+    611, 722, 667, 944, 667, 667, 611, // A - Z
+    278, 278, 278, 469, 556, 333, // [ - `
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500,
+    278, 556, 500, 722, 500, 500, 500, // a - z
+    334, 260, 334, 584, // { - ~
+];
+
+/// 签字栏
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureBlock {
+    // 栏目名称，如“审核人”
+    pub label: String,
+    // 预先印上的姓名，为空时留给手写
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// PDF 报告的页眉与签字栏
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfOptions {
+    // 公司名称，印在每页页眉
+    pub company: Option<String>,
+    // 公司标志图片（JPEG 或 PNG）的路径，印在页眉左侧
+    pub logo: Option<String>,
+    // 签字栏；未指定时为测量人、审核人、批准人，给出空列表则不印签字栏
+    pub signatures: Option<Vec<SignatureBlock>>,
+}
+
+impl PdfOptions {
+    fn signature_blocks(&self, locale: Locale) -> Vec<SignatureBlock> {
+        match &self.signatures {
+            Some(blocks) => blocks.clone(),
+            None => [Msg::SignSurveyor, Msg::SignReviewer, Msg::SignApprover]
+                .iter()
+                .map(|msg| SignatureBlock {
+                    label: msg.text(locale).to_string(),
+                    name: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// 字符在 Helvetica（WinAnsi 编码）中的宽度，没有该字符时为 None
+fn helvetica_width(c: char) -> Option<u16> {
+    match c {
+        ' '..='~' => Some(HELVETICA_WIDTHS[c as usize - 0x20]),
+        '°' => Some(400),
+        _ => None,
+    }
+}
+
+/// 能否用 Helvetica 书写，否则改用中文字体
+fn is_latin(text: &str) -> bool {
+    text.chars().all(|c| helvetica_width(c).is_some())
+}
+
+/// 文字宽度（点）；中文字体中 ASCII 字符为半角
+fn text_width(text: &str, size: f64) -> f64 {
+    let units: f64 = if is_latin(text) {
+        text.chars()
+            .filter_map(helvetica_width)
+            .map(f64::from)
+            .sum()
+    } else {
+        text.chars()
+            .map(|c| if c.is_ascii() { 500.0 } else { 1000.0 })
+            .sum()
+    };
+    units * size / 1000.0
+}
+
+/// 按宽度折行：西文在空格处断开，中文可在任意字符处断开
+fn wrap(text: &str, size: f64, width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        // 西文单词连同其后的空格作为一段，中文每个字符一段
+        let mut pieces = Vec::new();
+        let mut word = String::new();
+        for c in paragraph.chars() {
+            word.push(c);
+            if c == ' ' || helvetica_width(c).is_none() {
+                pieces.push(std::mem::take(&mut word));
+            }
+        }
+        pieces.push(word);
+
+        let mut line = String::new();
+        for piece in pieces {
+            let candidate = format!("{}{}", line, piece);
+            if !line.is_empty() && text_width(candidate.trim_end(), size) > width {
+                lines.push(line.trim_end().to_string());
+                line = piece.trim_start().to_string();
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// 轨迹图模块的 8 位颜色换算为 PDF 颜色
+fn rgb((r, g, b): plot::Color) -> Rgb {
+    (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+}
+
+/// 一页的绘图指令，坐标以页面左上角为原点、向下为正
+#[derive(Default)]
+struct Canvas {
+    ops: String,
+}
+
+impl Canvas {
+    fn color(&mut self, (r, g, b): Rgb, operator: &str) {
+        let _ = writeln!(self.ops, "{:.3} {:.3} {:.3} {}", r, g, b, operator);
+    }
+
+    /// 文字字符串：西文用 Helvetica，其余用中文字体（UTF-16 十六进制）
+    fn string(&mut self, text: &str, size: f64) {
+        if is_latin(text) {
+            let escaped = text
+                .replace('\\', "\\\\")
+                .replace('(', "\\(")
+                .replace(')', "\\)")
+                .replace('°', "\\260");
+            let _ = write!(self.ops, "/F1 {:.2} Tf ({}) Tj", size, escaped);
+        } else {
+            let _ = write!(self.ops, "/F2 {:.2} Tf <", size);
+            for c in text.chars() {
+                let unit = u16::try_from(c as u32).unwrap_or('?' as u16);
+                let _ = write!(self.ops, "{:04X}", unit);
+            }
+            self.ops.push_str("> Tj");
+        }
+    }
+
+    /// 在 (x, y) 处书写一行文字，y 为基线
+    fn text(&mut self, x: f64, y: f64, size: f64, text: &str, align: Align, color: Rgb) {
+        if text.is_empty() {
+            return;
+        }
+        let x = match align {
+            Align::Left => x,
+            Align::Center => x - text_width(text, size) / 2.0,
+            Align::Right => x - text_width(text, size),
+        };
+        self.ops.push_str("BT ");
+        self.color(color, "rg");
+        let _ = write!(self.ops, "{:.2} {:.2} Td ", x, PAGE_HEIGHT - y);
+        self.string(text, size);
+        self.ops.push_str(" ET\n");
+    }
+
+    /// 逆时针旋转 90° 的文字，以 (x, y) 为中心，用于纵轴标题
+    fn text_vertical(&mut self, x: f64, y: f64, size: f64, text: &str, color: Rgb) {
+        let y = y + text_width(text, size) / 2.0;
+        self.ops.push_str("BT ");
+        self.color(color, "rg");
+        let _ = write!(self.ops, "0 1 -1 0 {:.2} {:.2} Tm ", x, PAGE_HEIGHT - y);
+        self.string(text, size);
+        self.ops.push_str(" ET\n");
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)], width: f64, color: Rgb, dashed: bool) {
+        if points.len() < 2 {
+            return;
+        }
+        self.ops.push_str("q\n");
+        self.color(color, "RG");
+        let _ = writeln!(self.ops, "{:.2} w", width);
+        if dashed {
+            self.ops.push_str("[4 2] 0 d\n");
+        }
+        for (i, (x, y)) in points.iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            let _ = writeln!(self.ops, "{:.2} {:.2} {}", x, PAGE_HEIGHT - y, op);
+        }
+        self.ops.push_str("S\nQ\n");
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), width: f64, color: Rgb) {
+        self.polyline(&[from, to], width, color, false);
+    }
+
+    fn rect(&mut self, x: f64, y: f64, w: f64, h: f64, stroke: Option<Rgb>, fill: Option<Rgb>) {
+        self.ops.push_str("q\n0.5 w\n");
+        let operator = match (stroke, fill) {
+            (Some(stroke), Some(fill)) => {
+                self.color(stroke, "RG");
+                self.color(fill, "rg");
+                "B"
+            }
+            (Some(stroke), None) => {
+                self.color(stroke, "RG");
+                "S"
+            }
+            (None, Some(fill)) => {
+                self.color(fill, "rg");
+                "f"
+            }
+            (None, None) => "n",
+        };
+        let _ = writeln!(
+            self.ops,
+            "{:.2} {:.2} {:.2} {:.2} re {}\nQ",
+            x,
+            PAGE_HEIGHT - y - h,
+            w,
+            h,
+            operator
+        );
+    }
+
+    /// 填充多边形
+    fn polygon(&mut self, points: &[(f64, f64)], color: Rgb) {
+        if points.len() < 3 {
+            return;
+        }
+        self.ops.push_str("q\n");
+        self.color(color, "rg");
+        for (i, (x, y)) in points.iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            let _ = writeln!(self.ops, "{:.2} {:.2} {}", x, PAGE_HEIGHT - y, op);
+        }
+        self.ops.push_str("h f\nQ\n");
+    }
+
+    /// 圆，用四段贝塞尔曲线近似
+    fn circle(
+        &mut self,
+        (cx, cy): (f64, f64),
+        radius: f64,
+        stroke: Option<Rgb>,
+        fill: Option<Rgb>,
+    ) {
+        const K: f64 = 0.5523;
+        let operator = match (stroke, fill) {
+            (Some(_), Some(_)) => "B",
+            (Some(_), None) => "S",
+            (None, Some(_)) => "f",
+            (None, None) => return,
+        };
+        self.ops.push_str("q\n0.5 w\n");
+        if let Some(stroke) = stroke {
+            self.color(stroke, "RG");
+        }
+        if let Some(fill) = fill {
+            self.color(fill, "rg");
+        }
+        let (x, y, r, k) = (cx, PAGE_HEIGHT - cy, radius, radius * K);
+        let _ = writeln!(self.ops, "{:.2} {:.2} m", x + r, y);
+        for [c1, c2, end] in [
+            [(x + r, y + k), (x + k, y + r), (x, y + r)],
+            [(x - k, y + r), (x - r, y + k), (x - r, y)],
+            [(x - r, y - k), (x - k, y - r), (x, y - r)],
+            [(x + k, y - r), (x + r, y - k), (x + r, y)],
+        ] {
+            let _ = writeln!(
+                self.ops,
+                "{:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c",
+                c1.0, c1.1, c2.0, c2.1, end.0, end.1
+            );
+        }
+        let _ = writeln!(self.ops, "{}\nQ", operator);
+    }
+
+    /// 以 (x, y) 为左上角绘制轨迹图模块排好的场景，场景坐标按点计
+    fn scene(&mut self, scene: &Scene, x: f64, y: f64) {
+        let place = |points: &[(f64, f64)]| -> Vec<(f64, f64)> {
+            points.iter().map(|(px, py)| (x + px, y + py)).collect()
+        };
+        for shape in &scene.shapes {
+            match shape {
+                Shape::Line {
+                    points,
+                    color,
+                    width,
+                    dashed,
+                } => self.polyline(&place(points), *width, rgb(*color), *dashed),
+                Shape::Polygon { points, fill } => self.polygon(&place(points), rgb(*fill)),
+                Shape::Rect {
+                    x: rx,
+                    y: ry,
+                    w,
+                    h,
+                    stroke,
+                    fill,
+                } => self.rect(x + rx, y + ry, *w, *h, stroke.map(rgb), fill.map(rgb)),
+                Shape::Circle {
+                    center,
+                    radius,
+                    stroke,
+                    fill,
+                } => self.circle(
+                    (x + center.0, y + center.1),
+                    *radius,
+                    stroke.map(rgb),
+                    fill.map(rgb),
+                ),
+                Shape::Text {
+                    at,
+                    text,
+                    size,
+                    anchor,
+                    color,
+                    vertical,
+                } => {
+                    let (tx, ty) = (x + at.0, y + at.1);
+                    if *vertical {
+                        self.text_vertical(tx, ty, *size, text, rgb(*color));
+                    } else {
+                        let align = match anchor {
+                            Anchor::Start => Align::Left,
+                            Anchor::Middle => Align::Center,
+                            Anchor::End => Align::Right,
+                        };
+                        self.text(tx, ty, *size, text, align, rgb(*color));
+                    }
+                }
+            }
+        }
+    }
+
+    fn image(&mut self, name: &str, x: f64, y: f64, w: f64, h: f64) {
+        let _ = writeln!(
+            self.ops,
+            "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /{} Do Q",
+            w,
+            h,
+            x,
+            PAGE_HEIGHT - y - h,
+            name
+        );
+    }
+}
+
+/// 嵌入 PDF 的图片
+struct Image {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+    bits: u8,
+    filter: &'static str,
+    data: Vec<u8>,
+    // CMYK JPEG 通常为反相存储
+    invert: bool,
+    // 透明度（8 位灰度，已压缩）
+    alpha: Option<Vec<u8>>,
+}
+
+impl Image {
+    fn load(path: &str) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).with_context(|| tf(Msg::LogoReadFailed, &[("path", &path)]))?;
+        if bytes.starts_with(&[0xFF, 0xD8]) {
+            Self::jpeg(bytes)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Self::png(&bytes)
+        } else {
+            Err(anyhow!(tf(Msg::LogoUnsupportedFormat, &[("path", &path)])))
+        }
+        .with_context(|| tf(Msg::LogoInvalid, &[("path", &path)]))
+    }
+
+    /// JPEG 原样嵌入，只需从 SOF 段读出尺寸和颜色分量数
+    fn jpeg(bytes: Vec<u8>) -> Result<Self> {
+        let mut pos = 2;
+        while pos + 4 <= bytes.len() {
+            if bytes[pos] != 0xFF {
+                pos += 1;
+                continue;
+            }
+            let marker = bytes[pos + 1];
+            if marker == 0xFF || marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+                pos += if marker == 0xFF { 1 } else { 2 };
+                continue;
+            }
+            let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+            let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_sof && pos + 10 <= bytes.len() {
+                let height = u16::from_be_bytes([bytes[pos + 5], bytes[pos + 6]]) as u32;
+                let width = u16::from_be_bytes([bytes[pos + 7], bytes[pos + 8]]) as u32;
+                let color_space = match bytes[pos + 9] {
+                    1 => "DeviceGray",
+                    3 => "DeviceRGB",
+                    4 => "DeviceCMYK",
+                    n => {
+                        return Err(anyhow!(tf(
+                            Msg::JpegUnsupportedComponents,
+                            &[("count", &n)]
+                        )))
+                    }
+                };
+                return Ok(Self {
+                    width,
+                    height,
+                    color_space,
+                    bits: 8,
+                    filter: "DCTDecode",
+                    invert: color_space == "DeviceCMYK",
+                    data: bytes,
+                    alpha: None,
+                });
+            }
+            pos += 2 + length;
+        }
+        Err(anyhow!(t(Msg::JpegNoSize)))
+    }
+
+    /// PNG 解码为 8 位灰度或 RGB，透明通道单独作为软蒙版
+    fn png(bytes: &[u8]) -> Result<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        // 调色板与低位深展开为 8 位，16 位取高字节，tRNS 展开为透明通道
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer)?;
+        let (color_channels, gray) = match frame.color_type {
+            png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => (1, true),
+            png::ColorType::Rgb | png::ColorType::Rgba => (3, false),
+            other => {
+                return Err(anyhow!(tf(
+                    Msg::PngUnsupportedColor,
+                    &[("color", &format!("{:?}", other))]
+                )))
+            }
+        };
+
+        let pixels = (frame.width * frame.height) as usize;
+        let mut color = Vec::with_capacity(pixels * color_channels);
+        let mut alpha = Vec::with_capacity(pixels);
+        for pixel in buffer[..frame.buffer_size()].chunks_exact(frame.color_type.samples()) {
+            color.extend_from_slice(&pixel[..color_channels]);
+            alpha.push(pixel.get(color_channels).copied().unwrap_or(255));
+        }
+
+        let alpha = if alpha.iter().any(|a| *a < 255) {
+            Some(deflate(&alpha)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            width: frame.width,
+            height: frame.height,
+            color_space: if gray { "DeviceGray" } else { "DeviceRGB" },
+            bits: 8,
+            filter: "FlateDecode",
+            data: deflate(&color)?,
+            invert: false,
+            alpha,
+        })
+    }
+
+    /// 按页眉高度缩放后的显示尺寸
+    fn display_size(&self) -> (f64, f64) {
+        let aspect = self.width as f64 / self.height as f64;
+        let width = (LOGO_HEIGHT * aspect).min(LOGO_MAX_WIDTH);
+        (width, width / aspect)
+    }
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// PDF 文件的对象表，对象编号从 1 开始
+#[derive(Default)]
+struct PdfObjects {
+    objects: Vec<Vec<u8>>,
+}
+
+impl PdfObjects {
+    fn reserve(&mut self) -> usize {
+        self.objects.push(Vec::new());
+        self.objects.len()
+    }
+
+    fn set(&mut self, id: usize, body: impl Into<Vec<u8>>) {
+        self.objects[id - 1] = body.into();
+    }
+
+    fn add(&mut self, body: impl Into<Vec<u8>>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn add_stream(&mut self, dict: &str, data: &[u8]) -> usize {
+        let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.add(body)
+    }
+
+    /// 写出完整文件：文件头、对象、交叉引用表和文件尾
+    fn finish(self, root: usize, info: usize) -> Vec<u8> {
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (index, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            self.objects.len() + 1,
+            root,
+            info,
+            xref
+        );
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+/// 文档信息中的文本字符串（UTF-16BE，带字节序标记）
+fn info_string(text: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        let _ = write!(out, "{:04X}", unit);
+    }
+    out.push('>');
+    out
+}
+
+/// 报告排版：按顺序往下排，空间不足时换页
+struct Report {
+    locale: Locale,
+    // 已排完的页
+    done: Vec<Canvas>,
+    // 正在排的页，报告至少有这一页
+    page: Canvas,
+    y: f64,
+}
+
+impl Report {
+    fn new(locale: Locale) -> Self {
+        Self {
+            locale,
+            done: Vec::new(),
+            page: Canvas::default(),
+            y: MARGIN + HEADER_HEIGHT,
+        }
+    }
+
+    fn text(&self, msg: Msg) -> &'static str {
+        msg.text(self.locale)
+    }
+
+    fn canvas(&mut self) -> &mut Canvas {
+        &mut self.page
+    }
+
+    fn new_page(&mut self) {
+        self.done.push(std::mem::take(&mut self.page));
+        self.y = MARGIN + HEADER_HEIGHT;
+    }
+
+    /// 排版结束，按顺序返回全部页
+    fn into_pages(self) -> Vec<Canvas> {
+        let mut pages = self.done;
+        pages.push(self.page);
+        pages
+    }
+
+    fn bottom() -> f64 {
+        PAGE_HEIGHT - MARGIN - FOOTER_HEIGHT
+    }
+
+    /// 剩余空间不足 height 时换页
+    fn ensure(&mut self, height: f64) {
+        if self.y + height > Self::bottom() {
+            self.new_page();
+        }
+    }
+
+    fn section(&mut self, title: &str) {
+        // 标题不单独留在页尾
+        self.ensure(60.0);
+        let y = self.y + 14.0;
+        self.canvas()
+            .text(MARGIN, y, 12.0, title, Align::Left, BLACK);
+        self.canvas().line(
+            (MARGIN, y + 4.0),
+            (MARGIN + CONTENT_WIDTH, y + 4.0),
+            0.8,
+            ACTUAL_COLOR,
+        );
+        self.y += 26.0;
+    }
+
+    /// 两栏的“名称：值”列表，值过长时折行
+    fn fields(&mut self, fields: &[(&str, String)]) {
+        const SIZE: f64 = 9.0;
+        const LINE: f64 = 14.0;
+        const LABEL_WIDTH: f64 = 95.0;
+        let column_width = CONTENT_WIDTH / 2.0;
+        for pair in fields.chunks(2) {
+            let cells: Vec<(&str, Vec<String>)> = pair
+                .iter()
+                .map(|(label, value)| (*label, wrap(value, SIZE, column_width - LABEL_WIDTH - 8.0)))
+                .collect();
+            let lines = cells.iter().map(|(_, v)| v.len()).max().unwrap_or(1);
+            self.ensure(lines as f64 * LINE);
+            for (col, (label, values)) in cells.iter().enumerate() {
+                let x = MARGIN + col as f64 * column_width;
+                let y = self.y + 10.0;
+                self.canvas().text(x, y, SIZE, label, Align::Left, GRAY);
+                for (i, value) in values.iter().enumerate() {
+                    let y = y + i as f64 * LINE;
+                    self.canvas()
+                        .text(x + LABEL_WIDTH, y, SIZE, value, Align::Left, BLACK);
+                }
+            }
+            self.y += lines as f64 * LINE;
+        }
+        self.y += 8.0;
+    }
+
+    /// 表格，换页时重复表头；单元格可指定文字颜色
+    fn table(&mut self, columns: &[(String, f64)], rows: &[Vec<(String, Rgb)>], size: f64) {
+        let row_height = size + 6.0;
+        let header_lines: Vec<Vec<String>> = columns
+            .iter()
+            .map(|(title, width)| wrap(title, size, width - 4.0))
+            .collect();
+        let header_height =
+            header_lines.iter().map(Vec::len).max().unwrap_or(1) as f64 * (size + 2.0) + 6.0;
+
+        let draw_header = |report: &mut Report| {
+            let mut x = MARGIN;
+            let y = report.y;
+            for ((_, width), lines) in columns.iter().zip(&header_lines) {
+                let canvas = report.canvas();
+                canvas.rect(x, y, *width, header_height, Some(GRAY), Some(HEADER_FILL));
+                let top = y + (header_height - lines.len() as f64 * (size + 2.0)) / 2.0;
+                for (i, line) in lines.iter().enumerate() {
+                    let baseline = top + (i + 1) as f64 * (size + 2.0) - 2.0;
+                    canvas.text(x + width / 2.0, baseline, size, line, Align::Center, BLACK);
+                }
+                x += width;
+            }
+            report.y += header_height;
+        };
+
+        self.ensure(header_height + row_height);
+        draw_header(self);
+        for row in rows {
+            if self.y + row_height > Self::bottom() {
+                self.new_page();
+                draw_header(self);
+            }
+            let mut x = MARGIN;
+            let y = self.y;
+            for ((_, width), (value, color)) in columns.iter().zip(row) {
+                let canvas = self.canvas();
+                canvas.rect(x, y, *width, row_height, Some(LIGHT_GRAY), None);
+                canvas.text(
+                    x + width - 3.0,
+                    y + row_height - 4.0,
+                    size,
+                    value,
+                    Align::Right,
+                    *color,
+                );
+                x += width;
+            }
+            self.y += row_height;
+        }
+        self.y += 12.0;
+    }
+
+    /// 折线图：横轴为深度，纵轴为位移，由轨迹图模块排版
+    fn plot(&mut self, title: &str, x_label: &str, y_label: &str, series: &[ChartSeries]) {
+        const HEIGHT: f64 = 250.0;
+        self.ensure(HEIGHT);
+        let scene = plot::build_chart(title, x_label, y_label, series, CONTENT_WIDTH, HEIGHT);
+        let y = self.y;
+        self.canvas().scene(&scene, MARGIN, y);
+        self.y += HEIGHT + 10.0;
+    }
+
+    /// 签字栏：每行最多四栏，每栏有栏目、姓名、签名和日期
+    fn signatures(&mut self, blocks: &[SignatureBlock]) {
+        const HEIGHT: f64 = 78.0;
+        let signature = self.text(Msg::FieldSignature);
+        let date = self.text(Msg::FieldDate);
+        for row in blocks.chunks(4) {
+            self.ensure(HEIGHT + 10.0);
+            let width = CONTENT_WIDTH / row.len() as f64;
+            let y = self.y;
+            for (i, block) in row.iter().enumerate() {
+                let x = MARGIN + i as f64 * width;
+                let canvas = self.canvas();
+                canvas.rect(x + 4.0, y, width - 8.0, HEIGHT, Some(GRAY), None);
+                canvas.text(x + 10.0, y + 16.0, 10.0, &block.label, Align::Left, BLACK);
+                if let Some(name) = &block.name {
+                    canvas.text(x + 10.0, y + 32.0, 9.0, name, Align::Left, BLACK);
+                }
+                for (line, label) in [(52.0, signature), (70.0, date)] {
+                    canvas.text(x + 10.0, y + line, 8.0, label, Align::Left, GRAY);
+                    let start = x + 14.0 + text_width(label, 8.0);
+                    canvas.line(
+                        (start, y + line + 1.0),
+                        (x + width - 14.0, y + line + 1.0),
+                        0.5,
+                        GRAY,
+                    );
+                }
+            }
+            self.y += HEIGHT + 10.0;
+        }
+    }
+}
+
+/// 数值按类型格式化：长度、角度两位小数，坐标按导出设置的小数位数（最多三位）
+fn format_value(field: ColumnField, value: &FieldValue, decimal_places: usize) -> String {
+    match value {
+        FieldValue::Number(value) if field == ColumnField::Index => format!("{}", value),
+        FieldValue::Number(value) => match field.kind() {
+            ValueKind::Coordinate => format!("{:.*}", decimal_places.min(3), value),
+            _ => format!("{:.2}", value),
+        },
+        FieldValue::Text(text) => text.clone(),
+        FieldValue::CadPoint(x, y) => FieldValue::cad_point(*x, *y, decimal_places),
+        FieldValue::Blank => String::new(),
+    }
+}
+
+/// 测量报告 PDF：钻孔信息、计算参数、偏差汇总与质量结论、平面图和剖面图、测点数据及签字栏
+///
+/// 西文用 Helvetica，中文用 Adobe 亚洲字体包中的 STSong-Light，两者都由阅读器提供，不嵌入文件。
+pub fn to_pdf(repo: &Repo, stations: &[DataList], options: &ExportOptions) -> Result<Vec<u8>> {
+    let locale = options.locale;
+    let pdf = &options.pdf;
+    let logo = pdf.logo.as_deref().map(Image::load).transpose()?;
+    let declination = options.magnetic_declination;
+    let records = station_records(stations, declination);
+    let summary = calc::summarize(stations, declination);
+    let tolerance = &options.tolerance;
+    let checks = summary.qa_checks(tolerance);
+    let passed = summary.passes(tolerance);
+    let collar = options.collar(repo);
+    let generated_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let mut report = Report::new(locale);
+    let t = |msg: Msg| msg.text(locale);
+    let title = t(Msg::ReportTitle);
+    report.y += 10.0;
+    let y = report.y + 20.0;
+    report
+        .canvas()
+        .text(PAGE_WIDTH / 2.0, y, 18.0, title, Align::Center, BLACK);
+    report.canvas().text(
+        PAGE_WIDTH / 2.0,
+        y + 22.0,
+        12.0,
+        &repo.name,
+        Align::Center,
+        GRAY,
+    );
+    report.y += 56.0;
+
+    report.section(t(Msg::SectionHole));
+    report.fields(&[
+        (t(Msg::FieldHoleName), repo.name.clone()),
+        (t(Msg::FieldSurveyTime), repo.mn_time.clone()),
+        (t(Msg::FieldMine), repo.mine.clone()),
+        (t(Msg::FieldWorkingFace), repo.work.clone()),
+        (t(Msg::FieldDrillingSite), repo.factory.clone()),
+        (t(Msg::FieldRig), repo.drilling.clone()),
+        (t(Msg::FieldHoleLength), format!("{} m", repo.len)),
+        (t(Msg::FieldStationCount), summary.station_count.to_string()),
+        (
+            t(Msg::FieldSurveyedDepth),
+            format!("{:.2} m", summary.surveyed_depth),
+        ),
+        (t(Msg::FieldNotes), repo.notes.clone().unwrap_or_default()),
+    ]);
+
+    report.section(t(Msg::SectionParameters));
+    report.fields(&[
+        (
+            t(Msg::FieldMethod),
+            t(Msg::MethodMinimumCurvature).to_string(),
+        ),
+        (t(Msg::FieldDeclination), format!("{}°", declination)),
+        (
+            t(Msg::FieldCollar),
+            format!(
+                "E {:.3}  N {:.3}  Z {:.3}",
+                collar.east, collar.north, collar.elevation
+            ),
+        ),
+        (
+            t(Msg::FieldDecimalPlaces),
+            options.decimal_places.to_string(),
+        ),
+        (
+            t(Msg::FieldToleranceLateral),
+            format!("{} m", tolerance.lateral),
+        ),
+        (
+            t(Msg::FieldToleranceVertical),
+            format!("{} m", tolerance.vertical),
+        ),
+        (t(Msg::FieldTolerancePitch), format!("{}°", tolerance.pitch)),
+        (
+            t(Msg::FieldToleranceHeading),
+            format!("{}°", tolerance.heading),
+        ),
+    ]);
+
+    report.section(t(Msg::SheetSummary));
+    let verdict = |passed: bool| {
+        if passed {
+            (t(Msg::VerdictPass).to_string(), PASS_COLOR)
+        } else {
+            (t(Msg::VerdictFail).to_string(), FAIL_COLOR)
+        }
+    };
+    let columns: Vec<(String, f64)> = [
+        (t(Msg::ColItem).to_string(), 135.0),
+        (t(Msg::ColMaxDeviation).to_string(), 80.0),
+        (t(Msg::ColUnit).to_string(), 55.0),
+        (format!("{} (m)", t(Msg::ColAtDepth)), 80.0),
+        (t(Msg::ColTolerance).to_string(), 75.0),
+        (t(Msg::ColVerdict).to_string(), CONTENT_WIDTH - 425.0),
+    ]
+    .into();
+    let mut rows: Vec<Vec<(String, Rgb)>> = checks
+        .iter()
+        .map(|check| {
+            let (label, unit) = match check.item {
+                QaItem::Lateral => (Msg::QaLateral, "m"),
+                QaItem::Vertical => (Msg::QaVertical, "m"),
+                QaItem::Pitch => (Msg::QaPitch, "°"),
+                QaItem::Heading => (Msg::QaHeading, "°"),
+            };
+            vec![
+                (t(label).to_string(), BLACK),
+                (format!("{:.3}", check.max.value), BLACK),
+                (unit.to_string(), BLACK),
+                (format!("{:.2}", check.max.depth), BLACK),
+                (format!("{}", check.tolerance), BLACK),
+                verdict(check.passed),
+            ]
+        })
+        .collect();
+    rows.push(vec![
+        (t(Msg::MaxDogleg).to_string(), BLACK),
+        (format!("{:.3}", summary.max_dogleg.value), BLACK),
+        ("°/30m".to_string(), BLACK),
+        (format!("{:.2}", summary.max_dogleg.depth), BLACK),
+        (String::new(), BLACK),
+        (String::new(), BLACK),
+    ]);
+    for (label, value) in [
+        (Msg::FinalLateral, summary.final_lateral),
+        (Msg::FinalVertical, summary.final_vertical),
+    ] {
+        rows.push(vec![
+            (t(label).to_string(), BLACK),
+            (format!("{:.3}", value), BLACK),
+            ("m".to_string(), BLACK),
+            (format!("{:.2}", summary.surveyed_depth), BLACK),
+            (String::new(), BLACK),
+            (String::new(), BLACK),
+        ]);
+    }
+    report.table(&columns, &rows, 9.0);
+    let (verdict_text, verdict_color) = verdict(passed);
+    report.ensure(24.0);
+    let y = report.y + 12.0;
+    let colon = match locale {
+        Locale::ZhCn => "：",
+        Locale::EnUs => ": ",
+    };
+    let label = format!("{}{}", t(Msg::QaVerdict), colon);
+    report
+        .canvas()
+        .text(MARGIN, y, 13.0, &label, Align::Left, BLACK);
+    report.canvas().text(
+        MARGIN + text_width(&label, 13.0),
+        y,
+        13.0,
+        &verdict_text,
+        Align::Left,
+        verdict_color,
+    );
+    report.y += 28.0;
+
+    // 平面图与剖面图的数据与 Excel 轨迹图相同，均含孔口
+    let series = |value: fn(&StationRecord) -> f64| -> Vec<(f64, f64)> {
+        std::iter::once((0.0, 0.0))
+            .chain(records.iter().map(|r| (r.depth, value(r))))
+            .collect()
+    };
+    let header = |field: ColumnField| TemplateColumn::new(field).header_text(locale);
+    report.new_page();
+    report.section(t(Msg::SheetCharts));
+    report.plot(
+        t(Msg::ChartPlanTitle),
+        &header(ColumnField::Depth),
+        &header(ColumnField::Lateral),
+        &[
+            ChartSeries {
+                label: t(Msg::SeriesDesign),
+                points: series(|r| r.design_lateral),
+                color: DESIGN_SERIES,
+                dashed: true,
+            },
+            ChartSeries {
+                label: t(Msg::SeriesActual),
+                points: series(|r| r.lateral),
+                color: ACTUAL_SERIES,
+                dashed: false,
+            },
+        ],
+    );
+    report.plot(
+        t(Msg::ChartProfileTitle),
+        &header(ColumnField::Depth),
+        &header(ColumnField::Vertical),
+        &[
+            ChartSeries {
+                label: t(Msg::SeriesDesign),
+                points: series(|r| r.design_vertical),
+                color: DESIGN_SERIES,
+                dashed: true,
+            },
+            ChartSeries {
+                label: t(Msg::SeriesActual),
+                points: series(|r| r.vertical),
+                color: ACTUAL_SERIES,
+                dashed: false,
+            },
+        ],
+    );
+
+    report.new_page();
+    report.section(t(Msg::SheetStations));
+    let index_width = 26.0;
+    let other_width = (CONTENT_WIDTH - index_width) / (STATION_COLUMNS.len() - 1) as f64;
+    let columns: Vec<(String, f64)> = STATION_COLUMNS
+        .iter()
+        .map(|field| {
+            let width = if *field == ColumnField::Index {
+                index_width
+            } else {
+                other_width
+            };
+            (header(*field), width)
+        })
+        .collect();
+    let rows: Vec<Vec<(String, Rgb)>> = records
+        .iter()
+        .map(|record| {
+            STATION_COLUMNS
+                .iter()
+                .map(|field| {
+                    let value = format_value(*field, &record.value(*field), options.decimal_places);
+                    (value, BLACK)
+                })
+                .collect()
+        })
+        .collect();
+    report.table(&columns, &rows, 6.5);
+
+    let blocks = pdf.signature_blocks(locale);
+    if !blocks.is_empty() {
+        report.section(t(Msg::SectionSignatures));
+        report.signatures(&blocks);
+    }
+
+    // 页眉与页脚在排版完成、总页数确定后补画
+    let mut pages = report.into_pages();
+    let total = pages.len();
+    for (index, canvas) in pages.iter_mut().enumerate() {
+        let mut x = MARGIN;
+        if let Some(logo) = &logo {
+            let (w, h) = logo.display_size();
+            canvas.image("Im1", x, MARGIN + (LOGO_HEIGHT - h) / 2.0, w, h);
+            x += w + 8.0;
+        }
+        if let Some(company) = &pdf.company {
+            canvas.text(x, MARGIN + 20.0, 11.0, company, Align::Left, BLACK);
+        }
+        let right = MARGIN + CONTENT_WIDTH;
+        canvas.text(right, MARGIN + 12.0, 8.0, title, Align::Right, GRAY);
+        canvas.text(right, MARGIN + 24.0, 8.0, &repo.name, Align::Right, GRAY);
+        canvas.line(
+            (MARGIN, MARGIN + LOGO_HEIGHT + 6.0),
+            (right, MARGIN + LOGO_HEIGHT + 6.0),
+            0.5,
+            GRAY,
+        );
+
+        let footer = PAGE_HEIGHT - MARGIN;
+        canvas.line((MARGIN, footer - 12.0), (right, footer - 12.0), 0.5, GRAY);
+        let generated = format!("{} {}", t(Msg::FieldGeneratedAt), generated_at);
+        canvas.text(MARGIN, footer, 8.0, &generated, Align::Left, GRAY);
+        let page = t(Msg::PageOf)
+            .replace("{page}", &(index + 1).to_string())
+            .replace("{pages}", &total.to_string());
+        canvas.text(right, footer, 8.0, &page, Align::Right, GRAY);
+    }
+
+    let mut objects = PdfObjects::default();
+    let catalog = objects.reserve();
+    let pages_id = objects.reserve();
+    let helvetica = objects
+        .add("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>");
+    let descriptor = objects.add(
+        "<< /Type /FontDescriptor /FontName /STSong-Light /Flags 6 \
+         /FontBBox [-25 -254 1000 880] /ItalicAngle 0 /Ascent 880 /Descent -120 \
+         /CapHeight 880 /StemV 93 >>",
+    );
+    let cid_font = objects.add(format!(
+        "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /STSong-Light \
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (GB1) /Supplement 2 >> \
+         /FontDescriptor {} 0 R /DW 1000 /W [1 95 500 814 907 500] >>",
+        descriptor
+    ));
+    let song = objects.add(format!(
+        "<< /Type /Font /Subtype /Type0 /BaseFont /STSong-Light /Encoding /UniGB-UCS2-H \
+         /DescendantFonts [{} 0 R] >>",
+        cid_font
+    ));
+    let mut resources = format!("<< /Font << /F1 {} 0 R /F2 {} 0 R >>", helvetica, song);
+    if let Some(logo) = &logo {
+        let mask = logo.alpha.as_ref().map(|alpha| {
+            objects.add_stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                     /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode",
+                    logo.width, logo.height
+                ),
+                alpha,
+            )
+        });
+        let mut dict = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} \
+             /BitsPerComponent {} /Filter /{}",
+            logo.width, logo.height, logo.color_space, logo.bits, logo.filter
+        );
+        if logo.invert {
+            dict.push_str(" /Decode [1 0 1 0 1 0 1 0]");
+        }
+        if let Some(mask) = mask {
+            let _ = write!(dict, " /SMask {} 0 R", mask);
+        }
+        let image = objects.add_stream(&dict, &logo.data);
+        let _ = write!(resources, " /XObject << /Im1 {} 0 R >>", image);
+    }
+    resources.push_str(" >>");
+
+    let mut kids = Vec::with_capacity(total);
+    for canvas in &pages {
+        let content = objects.add_stream("/Filter /FlateDecode", &deflate(canvas.ops.as_bytes())?);
+        kids.push(objects.add(format!(
+            "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] /Resources {} /Contents {} 0 R >>",
+            pages_id, PAGE_WIDTH, PAGE_HEIGHT, resources, content
+        )));
+    }
+    let kids: Vec<String> = kids.iter().map(|id| format!("{} 0 R", id)).collect();
+    objects.set(
+        pages_id,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            total
+        ),
+    );
+    objects.set(
+        catalog,
+        format!("<< /Type /Catalog /Pages {} 0 R >>", pages_id),
+    );
+    let info = objects.add(format!(
+        "<< /Title {} /Subject {} /Producer {} /CreationDate (D:{}) >>",
+        info_string(&format!("{} {}", title, repo.name)),
+        info_string(&repo.name),
+        info_string(pdf.company.as_deref().unwrap_or("drilltrack")),
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    Ok(objects.finish(catalog, info))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo() -> Repo {
+        Repo {
+            id: Some(1),
+            uuid: None,
+            name: "ZK-1 西".to_string(),
+            mn_time: "2024-05-06 08:30:00".to_string(),
+            len: 300,
+            mine: "一矿".to_string(),
+            work: "1201".to_string(),
+            factory: "3#".to_string(),
+            drilling: "ZDY-6000".to_string(),
+            notes: Some("备注 (括号) \\ 反斜杠".to_string()),
+            mine_id: None,
+            working_face_id: None,
+            drilling_site_id: None,
+            rig_id: None,
+        }
+    }
+
+    fn stations(count: usize) -> Vec<DataList> {
+        (1..=count)
+            .map(|i| DataList {
+                id: None,
+                uuid: None,
+                time: Some("2024-05-06 08:30:00".to_string()),
+                depth: i as f64 * 3.0,
+                pitch: Some(-2.0 + i as f64 * 0.01),
+                roll: Some(10.0),
+                heading: Some(95.0),
+                repo_id: None,
+                design_pitch: Some(-2.0),
+                design_heading: Some(90.0),
+            })
+            .collect()
+    }
+
+    fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|w| w == needle)
+    }
+
+    #[test]
+    fn pdf_has_header_trailer_and_valid_xref() {
+        let pdf = to_pdf(&repo(), &stations(100), &ExportOptions::default()).unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        let tail = String::from_utf8_lossy(&pdf[pdf.len() - 64..]).to_string();
+        let startxref: usize = tail
+            .split("startxref\n")
+            .nth(1)
+            .and_then(|rest| rest.lines().next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[startxref..].starts_with(b"xref\n0 "));
+
+        // 交叉引用表中每个对象的偏移都指向 "N 0 obj"
+        let table = String::from_utf8_lossy(&pdf[startxref..]).to_string();
+        let mut lines = table.lines().skip(1);
+        let count: usize = lines
+            .next()
+            .unwrap()
+            .split(' ')
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+        for id in 1..count {
+            let entry = lines.next().unwrap();
+            assert!(entry.ends_with(" 00000 n "), "{}", entry);
+            let offset: usize = entry[..10].parse().unwrap();
+            let header = format!("{} 0 obj\n", id);
+            assert!(
+                pdf[offset..].starts_with(header.as_bytes()),
+                "object {}",
+                id
+            );
+        }
+        assert!(find(&pdf, format!("/Size {} ", count).as_bytes()).is_some());
+
+        // 测点较多时分多页，每页都写出
+        let pages = find(&pdf, b"/Type /Pages").unwrap();
+        let rest = String::from_utf8_lossy(&pdf[pages..]).to_string();
+        let total: usize = rest
+            .split("/Count ")
+            .nth(1)
+            .and_then(|s| s.split(' ').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(total > 1);
+        let page_objects = pdf
+            .windows(b"/Type /Page ".len())
+            .filter(|w| *w == b"/Type /Page ")
+            .count();
+        assert_eq!(page_objects, total);
+    }
+}
@@ -0,0 +1,1118 @@
+//! 轨迹图：平面图、剖面图和等轴测图
+//!
+//! 先把图形组成与输出格式无关的 `Scene`，再写成 SVG；PNG 由 SVG 经 resvg 光栅化，
+//! 文字使用系统字体，中文标注需要系统装有中文字体（微软雅黑、苹方或 Noto Sans CJK 等）。
+
+use crate::calc::{self, TrajectoryPoint};
+use crate::i18n::{t, tf, Locale, Msg};
+use crate::services::dxf::DxfHole;
+use crate::services::templates::{ColumnField, TemplateColumn};
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{self, fontdb};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+/// 视图
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlotView {
+    // 平面图：东坐标 - 北坐标
+    #[default]
+    Plan,
+    // 剖面图：沿第一个钻孔设计方位的水平距离 - 高程
+    Profile,
+    // 等轴测图
+    Isometric,
+}
+
+/// 图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlotFormat {
+    Svg,
+    Png,
+}
+
+impl PlotFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlotFormat::Svg => "svg",
+            PlotFormat::Png => "png",
+        }
+    }
+}
+
+/// 绘图参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlotOptions {
+    pub view: PlotView,
+    // 图片尺寸（像素）
+    pub width: u32,
+    pub height: u32,
+    // 是否画坐标网格
+    pub grid: bool,
+    // 是否标出测点
+    pub stations: bool,
+    // 是否画设计轨迹
+    pub design: bool,
+    // 剖面图的高程放大倍数，偏差很小时可放大以便观察
+    pub vertical_exaggeration: f64,
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            view: PlotView::Plan,
+            width: 1200,
+            height: 900,
+            grid: true,
+            stations: true,
+            design: true,
+            vertical_exaggeration: 1.0,
+        }
+    }
+}
+
+impl PlotOptions {
+    pub fn validate(&self) -> Result<()> {
+        for (label, size) in [(Msg::PlotWidth, self.width), (Msg::PlotHeight, self.height)] {
+            if !(200..=8000).contains(&size) {
+                return Err(anyhow!(tf(Msg::PlotSizeRange, &[("field", &t(label))])));
+            }
+        }
+        if self.width as u64 * self.height as u64 > MAX_PIXELS {
+            return Err(anyhow!(t(Msg::PlotAreaTooLarge)));
+        }
+        if !self.vertical_exaggeration.is_finite() || self.vertical_exaggeration <= 0.0 {
+            return Err(anyhow!(t(Msg::PlotExaggerationInvalid)));
+        }
+        Ok(())
+    }
+}
+
+/// 图片的最大像素数，宽和高都可到 8000，但乘积不超过 4096×4096
+const MAX_PIXELS: u64 = 4096 * 4096;
+
+pub type Color = (u8, u8, u8);
+
+const WHITE: Color = (255, 255, 255);
+const BLACK: Color = (0, 0, 0);
+const GRAY: Color = (110, 110, 110);
+const GRID: Color = (222, 222, 222);
+/// 各钻孔的颜色，依次循环使用
+const PALETTE: [Color; 8] = [
+    (31, 100, 191),
+    (214, 48, 49),
+    (39, 137, 62),
+    (230, 126, 34),
+    (142, 68, 173),
+    (22, 160, 133),
+    (192, 57, 43),
+    (52, 73, 94),
+];
+
+// 绘图区四周留白（像素）
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 30.0;
+const MARGIN_TOP: f64 = 56.0;
+const MARGIN_BOTTOM: f64 = 64.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// 场景中的图形，坐标为图片像素，原点在左上角
+pub enum Shape {
+    Line {
+        points: Vec<(f64, f64)>,
+        color: Color,
+        width: f64,
+        dashed: bool,
+    },
+    Polygon {
+        points: Vec<(f64, f64)>,
+        fill: Color,
+    },
+    Rect {
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        stroke: Option<Color>,
+        fill: Option<Color>,
+    },
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+        stroke: Option<Color>,
+        fill: Option<Color>,
+    },
+    Text {
+        at: (f64, f64),
+        text: String,
+        size: f64,
+        anchor: Anchor,
+        color: Color,
+        // 逆时针旋转 90°，用于纵轴标题
+        vertical: bool,
+    },
+}
+
+/// 与输出格式无关的图形，SVG、PNG 和 PDF 报告都从它绘制
+pub struct Scene {
+    pub width: u32,
+    pub height: u32,
+    pub shapes: Vec<Shape>,
+}
+
+impl Scene {
+    fn line(&mut self, points: Vec<(f64, f64)>, color: Color, width: f64, dashed: bool) {
+        self.shapes.push(Shape::Line {
+            points,
+            color,
+            width,
+            dashed,
+        });
+    }
+
+    fn text(&mut self, at: (f64, f64), text: impl Into<String>, size: f64, anchor: Anchor) {
+        self.shapes.push(Shape::Text {
+            at,
+            text: text.into(),
+            size,
+            anchor,
+            color: BLACK,
+            vertical: false,
+        });
+    }
+
+    fn to_svg(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Helvetica, Arial, 'Microsoft YaHei', sans-serif">"#,
+            w = self.width,
+            h = self.height
+        );
+        let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let hex = |(r, g, b): Color| format!("#{:02x}{:02x}{:02x}", r, g, b);
+        let paint = |color: Option<Color>| color.map_or("none".to_string(), hex);
+        let points = |points: &[(f64, f64)]| {
+            points
+                .iter()
+                .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        for shape in &self.shapes {
+            match shape {
+                Shape::Line {
+                    points: p,
+                    color,
+                    width,
+                    dashed,
+                } => {
+                    let dash = if *dashed {
+                        format!(
+                            r#" stroke-dasharray="{:.1} {:.1}""#,
+                            width * 5.0,
+                            width * 3.0
+                        )
+                    } else {
+                        String::new()
+                    };
+                    let _ = writeln!(
+                        out,
+                        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{:.2}" stroke-linejoin="round" stroke-linecap="round"{}/>"#,
+                        points(p),
+                        hex(*color),
+                        width,
+                        dash
+                    );
+                }
+                Shape::Polygon { points: p, fill } => {
+                    let _ = writeln!(
+                        out,
+                        r#"<polygon points="{}" fill="{}"/>"#,
+                        points(p),
+                        hex(*fill)
+                    );
+                }
+                Shape::Rect {
+                    x,
+                    y,
+                    w,
+                    h,
+                    stroke,
+                    fill,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" stroke="{}"/>"#,
+                        x,
+                        y,
+                        w,
+                        h,
+                        paint(*fill),
+                        paint(*stroke)
+                    );
+                }
+                Shape::Circle {
+                    center: (x, y),
+                    radius,
+                    stroke,
+                    fill,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}" stroke="{}"/>"#,
+                        x,
+                        y,
+                        radius,
+                        paint(*fill),
+                        paint(*stroke)
+                    );
+                }
+                Shape::Text {
+                    at: (x, y),
+                    text,
+                    size,
+                    anchor,
+                    color,
+                    vertical,
+                } => {
+                    let anchor = match anchor {
+                        Anchor::Start => "start",
+                        Anchor::Middle => "middle",
+                        Anchor::End => "end",
+                    };
+                    let transform = if *vertical {
+                        format!(r#" transform="rotate(-90 {:.2} {:.2})""#, x, y)
+                    } else {
+                        String::new()
+                    };
+                    let text = text
+                        .replace('&', "&amp;")
+                        .replace('<', "&lt;")
+                        .replace('>', "&gt;");
+                    let _ = writeln!(
+                        out,
+                        r#"<text x="{:.2}" y="{:.2}" font-size="{:.1}" text-anchor="{}" fill="{}"{}>{}</text>"#,
+                        x,
+                        y,
+                        size,
+                        anchor,
+                        hex(*color),
+                        transform,
+                        text
+                    );
+                }
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// 由 SVG 光栅化，文字用系统字体绘制，缺字时从其他系统字体中回退
+    fn to_png(&self) -> Result<Vec<u8>> {
+        let options = usvg::Options {
+            fontdb: system_fonts(),
+            ..usvg::Options::default()
+        };
+        let tree = usvg::Tree::from_str(&self.to_svg(), &options)?;
+        let mut pixmap = Pixmap::new(self.width, self.height)
+            .ok_or_else(|| anyhow!(tf(Msg::PlotSizeRange, &[("field", &t(Msg::PlotWidth))])))?;
+        resvg::render(&tree, Transform::default(), &mut pixmap.as_mut());
+        Ok(pixmap.encode_png()?)
+    }
+}
+
+/// PNG 默认字体的候选，优先选含中文的字体，都没有时用常见的西文字体
+const SANS_SERIF_FONTS: [&str; 9] = [
+    "Microsoft YaHei",
+    "PingFang SC",
+    "Noto Sans CJK SC",
+    "Source Han Sans SC",
+    "WenQuanYi Micro Hei",
+    "Arial",
+    "Helvetica",
+    "Liberation Sans",
+    "DejaVu Sans",
+];
+
+/// 系统字体库，第一次输出 PNG 时加载
+///
+/// SVG 的 sans-serif 指向系统中第一个可用的候选字体，默认的 Arial 不一定存在。
+fn system_fonts() -> Arc<fontdb::Database> {
+    static FONTS: OnceCell<Arc<fontdb::Database>> = OnceCell::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = fontdb::Database::new();
+            fonts.load_system_fonts();
+            let installed = SANS_SERIF_FONTS.into_iter().find(|name| {
+                fonts
+                    .faces()
+                    .any(|face| face.families.iter().any(|(family, _)| family == name))
+            });
+            if let Some(name) = installed {
+                fonts.set_sans_serif_family(name);
+            }
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// 钻孔在世界坐标系中的轨迹，点为 [东, 北, 高程]
+struct HoleTrack {
+    label: String,
+    actual: Vec<[f64; 3]>,
+    design: Option<Vec<[f64; 3]>>,
+    // 剖面方向（方位角，度）
+    azimuth: f64,
+}
+
+impl HoleTrack {
+    fn new(hole: &DxfHole, magnetic_declination: f64) -> Self {
+        let collar = hole.collar;
+        let world = |points: Vec<TrajectoryPoint>| -> Vec<[f64; 3]> {
+            std::iter::once([collar.east, collar.north, collar.elevation])
+                .chain(points.iter().map(|p| {
+                    [
+                        collar.east + p.east,
+                        collar.north + p.north,
+                        collar.elevation + p.elevation,
+                    ]
+                }))
+                .collect()
+        };
+        Self {
+            label: hole.repo.name.trim().to_string(),
+            actual: world(calc::trajectory(hole.stations, magnetic_declination)),
+            design: calc::design_trajectory(hole.stations).map(world),
+            azimuth: calc::section_azimuth(hole.stations, magnetic_declination),
+        }
+    }
+}
+
+/// 世界坐标到视图平面的投影
+struct Projection {
+    view: PlotView,
+    // 剖面的起点（第一个钻孔的孔口）与方向
+    origin: (f64, f64),
+    azimuth: f64,
+    exaggeration: f64,
+}
+
+impl Projection {
+    fn project(&self, [east, north, elevation]: [f64; 3]) -> (f64, f64) {
+        match self.view {
+            PlotView::Plan => (east, north),
+            PlotView::Profile => {
+                let (sin, cos) = self.azimuth.to_radians().sin_cos();
+                let distance = (east - self.origin.0) * sin + (north - self.origin.1) * cos;
+                (distance, elevation * self.exaggeration)
+            }
+            PlotView::Isometric => {
+                let (sin, cos) = 30f64.to_radians().sin_cos();
+                ((east - north) * cos, (east + north) * sin + elevation)
+            }
+        }
+    }
+}
+
+/// 视图平面到图片像素的映射，两个方向比例相同
+struct Frame {
+    min: (f64, f64),
+    max: (f64, f64),
+    scale: f64,
+    // 绘图区
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+    fn new(points: &[(f64, f64)], options: &PlotOptions) -> Self {
+        let (mut min, mut max) = points.iter().fold(
+            ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN)),
+            |(min, max), (x, y)| {
+                (
+                    (min.0.min(*x), min.1.min(*y)),
+                    (max.0.max(*x), max.1.max(*y)),
+                )
+            },
+        );
+        // 留出 5% 的边距，轨迹几乎是直线时至少留 1 米
+        let pad_x = ((max.0 - min.0) * 0.05).max(1.0);
+        let pad_y = ((max.1 - min.1) * 0.05).max(1.0);
+        min = (min.0 - pad_x, min.1 - pad_y);
+        max = (max.0 + pad_x, max.1 + pad_y);
+
+        let width = options.width as f64 - MARGIN_LEFT - MARGIN_RIGHT;
+        let height = options.height as f64 - MARGIN_TOP - MARGIN_BOTTOM;
+        let scale = (width / (max.0 - min.0)).min(height / (max.1 - min.1));
+        // 比例受较长方向限制时，另一方向居中扩展
+        let extra_x = (width / scale - (max.0 - min.0)) / 2.0;
+        let extra_y = (height / scale - (max.1 - min.1)) / 2.0;
+        Self {
+            min: (min.0 - extra_x, min.1 - extra_y),
+            max: (max.0 + extra_x, max.1 + extra_y),
+            scale,
+            left: MARGIN_LEFT,
+            top: MARGIN_TOP,
+            width,
+            height,
+        }
+    }
+
+    fn to_pixel(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.left + (x - self.min.0) * self.scale,
+            self.top + self.height - (y - self.min.1) * self.scale,
+        )
+    }
+}
+
+/// 刻度间隔：取 1、2、5 乘 10 的幂，使区间约分成 target 段
+fn nice_step(range: f64, target: f64) -> f64 {
+    let rough = (range / target).max(1e-9);
+    let magnitude = 10f64.powf(rough.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// 比例尺长度：不超过 max 的 1、2、5 乘 10 的幂
+fn scale_length(max: f64) -> f64 {
+    let magnitude = 10f64.powf(max.max(1e-9).log10().floor());
+    [5.0, 2.0, 1.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|length| *length <= max)
+        .unwrap_or(magnitude)
+}
+
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = if step >= 1.0 {
+        0
+    } else {
+        (-step.log10()).ceil() as usize
+    };
+    let text = format!("{:.*}", decimals, value);
+    if text
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
+
+/// 估计文字宽度（像素），用于摆放图例
+fn label_width(text: &str, size: f64) -> f64 {
+    text.chars()
+        .map(|c| if c.is_ascii() { 0.75 } else { 1.0 })
+        .sum::<f64>()
+        * size
+}
+
+/// 等轴测图的水平网格线：覆盖全部轨迹的东、北范围，位于最低高程处
+fn isometric_grid(tracks: &[HoleTrack]) -> Vec<[[f64; 3]; 2]> {
+    let (e0, n0, e1, n1, z) = tracks
+        .iter()
+        .flat_map(|t| t.actual.iter().chain(t.design.iter().flatten()))
+        .fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN, f64::MAX),
+            |(e0, n0, e1, n1, z), p| {
+                (
+                    e0.min(p[0]),
+                    n0.min(p[1]),
+                    e1.max(p[0]),
+                    n1.max(p[1]),
+                    z.min(p[2]),
+                )
+            },
+        );
+    if e0 > e1 {
+        return Vec::new();
+    }
+    let step = nice_step((e1 - e0).max(n1 - n0).max(1.0), 6.0);
+    let (first_e, last_e) = ((e0 / step).floor() as i64, (e1 / step).ceil() as i64);
+    let (first_n, last_n) = ((n0 / step).floor() as i64, (n1 / step).ceil() as i64);
+    let (e0, e1) = (first_e as f64 * step, last_e as f64 * step);
+    let (n0, n1) = (first_n as f64 * step, last_n as f64 * step);
+    let along_north = (first_e..=last_e).map(|k| {
+        let e = k as f64 * step;
+        [[e, n0, z], [e, n1, z]]
+    });
+    let along_east = (first_n..=last_n).map(|k| {
+        let n = k as f64 * step;
+        [[e0, n, z], [e1, n, z]]
+    });
+    along_north.chain(along_east).collect()
+}
+
+/// 画出一个或多个钻孔的轨迹图
+fn build_scene(
+    holes: &[DxfHole],
+    magnetic_declination: f64,
+    locale: Locale,
+    options: &PlotOptions,
+) -> Scene {
+    let t = |msg: Msg| msg.text(locale);
+    let tracks: Vec<HoleTrack> = holes
+        .iter()
+        .map(|hole| HoleTrack::new(hole, magnetic_declination))
+        .collect();
+    let first = tracks.first();
+    let projection = Projection {
+        view: options.view,
+        origin: first.map_or((0.0, 0.0), |t| (t.actual[0][0], t.actual[0][1])),
+        azimuth: first.map_or(0.0, |t| t.azimuth),
+        exaggeration: options.vertical_exaggeration,
+    };
+    let mut scene = Scene {
+        width: options.width,
+        height: options.height,
+        shapes: Vec::new(),
+    };
+
+    let project_all = |points: &[[f64; 3]]| -> Vec<(f64, f64)> {
+        points.iter().map(|p| projection.project(*p)).collect()
+    };
+    // 等轴测图在最低高程处画水平网格，网格也要完整画进图中
+    let iso_grid = if options.view == PlotView::Isometric && options.grid {
+        isometric_grid(&tracks)
+    } else {
+        Vec::new()
+    };
+    let mut extent: Vec<(f64, f64)> = Vec::new();
+    for track in &tracks {
+        extent.extend(project_all(&track.actual));
+        if let (true, Some(design)) = (options.design, &track.design) {
+            extent.extend(project_all(design));
+        }
+    }
+    for line in &iso_grid {
+        extent.extend(project_all(line));
+    }
+    if extent.is_empty() {
+        extent.push((0.0, 0.0));
+    }
+    let frame = Frame::new(&extent, options);
+
+    // 网格与坐标刻度
+    let step = nice_step(
+        (frame.max.0 - frame.min.0).max(frame.max.1 - frame.min.1),
+        8.0,
+    );
+    let ticks = |min: f64, max: f64| {
+        let first = (min / step).ceil() as i64;
+        let last = (max / step).floor() as i64;
+        (first..=last)
+            .map(|k| k as f64 * step)
+            .collect::<Vec<f64>>()
+    };
+    if options.view == PlotView::Isometric {
+        for line in &iso_grid {
+            let points = line
+                .iter()
+                .map(|p| frame.to_pixel(projection.project(*p)))
+                .collect();
+            scene.line(points, GRID, 1.0, false);
+        }
+    } else {
+        let bottom = frame.top + frame.height;
+        let right = frame.left + frame.width;
+        for x in ticks(frame.min.0, frame.max.0) {
+            let (px, _) = frame.to_pixel((x, 0.0));
+            if options.grid {
+                scene.line(vec![(px, frame.top), (px, bottom)], GRID, 1.0, false);
+            }
+            scene.line(vec![(px, bottom), (px, bottom + 5.0)], GRAY, 1.0, false);
+            scene.text(
+                (px, bottom + 18.0),
+                format_tick(x, step),
+                11.0,
+                Anchor::Middle,
+            );
+        }
+        for y in ticks(frame.min.1, frame.max.1) {
+            let (_, py) = frame.to_pixel((0.0, y));
+            if options.grid {
+                scene.line(vec![(frame.left, py), (right, py)], GRID, 1.0, false);
+            }
+            scene.line(
+                vec![(frame.left - 5.0, py), (frame.left, py)],
+                GRAY,
+                1.0,
+                false,
+            );
+            // 剖面图纵轴标注实际高程
+            let exaggeration = if options.view == PlotView::Profile {
+                options.vertical_exaggeration
+            } else {
+                1.0
+            };
+            scene.text(
+                (frame.left - 8.0, py + 4.0),
+                format_tick(y / exaggeration, step / exaggeration),
+                11.0,
+                Anchor::End,
+            );
+        }
+        scene.shapes.push(Shape::Rect {
+            x: frame.left,
+            y: frame.top,
+            w: frame.width,
+            h: frame.height,
+            stroke: Some(GRAY),
+            fill: None,
+        });
+
+        let header = |field: ColumnField| TemplateColumn::new(field).header_text(locale);
+        let (x_label, y_label) = match options.view {
+            PlotView::Plan => (header(ColumnField::East), header(ColumnField::North)),
+            _ => (
+                format!("{} (m)", t(Msg::PlotSectionDistance)),
+                header(ColumnField::Elevation),
+            ),
+        };
+        scene.text(
+            (frame.left + frame.width / 2.0, bottom + 42.0),
+            x_label,
+            12.0,
+            Anchor::Middle,
+        );
+        scene.shapes.push(Shape::Text {
+            at: (22.0, frame.top + frame.height / 2.0),
+            text: y_label,
+            size: 12.0,
+            anchor: Anchor::Middle,
+            color: BLACK,
+            vertical: true,
+        });
+    }
+
+    // 轨迹：设计为虚线，实测为实线，测点为小圆
+    for (index, track) in tracks.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let to_pixels = |points: &[[f64; 3]]| -> Vec<(f64, f64)> {
+            points
+                .iter()
+                .map(|p| frame.to_pixel(projection.project(*p)))
+                .collect()
+        };
+        if let (true, Some(design)) = (options.design, &track.design) {
+            scene.line(to_pixels(design), color, 1.5, true);
+        }
+        let actual = to_pixels(&track.actual);
+        scene.line(actual.clone(), color, 2.2, false);
+        if options.stations {
+            for point in actual.iter().skip(1) {
+                scene.shapes.push(Shape::Circle {
+                    center: *point,
+                    radius: 2.5,
+                    stroke: Some(color),
+                    fill: Some(WHITE),
+                });
+            }
+        }
+        if let Some(collar) = actual.first() {
+            scene.shapes.push(Shape::Circle {
+                center: *collar,
+                radius: 4.0,
+                stroke: Some(BLACK),
+                fill: Some(color),
+            });
+            scene.text(
+                (collar.0 + 7.0, collar.1 - 7.0),
+                track.label.clone(),
+                11.0,
+                Anchor::Start,
+            );
+        }
+    }
+
+    // 标题
+    let title = match options.view {
+        PlotView::Plan => t(Msg::PlotPlanTitle),
+        PlotView::Profile => t(Msg::PlotProfileTitle),
+        PlotView::Isometric => t(Msg::PlotIsometricTitle),
+    };
+    let names: Vec<&str> = tracks.iter().map(|t| t.label.as_str()).collect();
+    let mut title = format!("{}  {}", title, names.join(", "));
+    if options.view == PlotView::Profile && options.vertical_exaggeration != 1.0 {
+        let _ = write!(
+            title,
+            "  ({} x{})",
+            t(Msg::PlotVerticalExaggeration),
+            options.vertical_exaggeration
+        );
+    }
+    scene.text(
+        (options.width as f64 / 2.0, 30.0),
+        title,
+        16.0,
+        Anchor::Middle,
+    );
+
+    // 图例：各钻孔颜色，以及实测、设计线型
+    let mut entries: Vec<(String, Color, bool)> = tracks
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.label.clone(), PALETTE[i % PALETTE.len()], false))
+        .collect();
+    entries.push((t(Msg::SeriesActual).to_string(), GRAY, false));
+    if options.design {
+        entries.push((t(Msg::SeriesDesign).to_string(), GRAY, true));
+    }
+    let legend_width = entries
+        .iter()
+        .map(|(label, ..)| label_width(label, 11.0))
+        .fold(0.0, f64::max)
+        + 48.0;
+    let (lx, ly) = (frame.left + 10.0, frame.top + 10.0);
+    scene.shapes.push(Shape::Rect {
+        x: lx,
+        y: ly,
+        w: legend_width,
+        h: entries.len() as f64 * 18.0 + 8.0,
+        stroke: Some(GRID),
+        fill: Some(WHITE),
+    });
+    for (i, (label, color, dashed)) in entries.into_iter().enumerate() {
+        let y = ly + 16.0 + i as f64 * 18.0;
+        scene.line(
+            vec![(lx + 8.0, y - 4.0), (lx + 34.0, y - 4.0)],
+            color,
+            2.0,
+            dashed,
+        );
+        scene.text((lx + 40.0, y), label, 11.0, Anchor::Start);
+    }
+
+    // 比例尺：不超过绘图区宽度的五分之一，黑白相间；等轴测图各方向比例不同，不画比例尺
+    if options.view != PlotView::Isometric {
+        let length = scale_length((frame.max.0 - frame.min.0) / 5.0);
+        let bar = length * frame.scale;
+        let (bx, by) = (
+            frame.left + frame.width - bar - 16.0,
+            frame.top + frame.height - 24.0,
+        );
+        for i in 0..4 {
+            let segment = bar / 4.0;
+            scene.shapes.push(Shape::Rect {
+                x: bx + i as f64 * segment,
+                y: by,
+                w: segment,
+                h: 6.0,
+                stroke: Some(BLACK),
+                fill: Some(if i % 2 == 0 { BLACK } else { WHITE }),
+            });
+        }
+        scene.text((bx, by - 4.0), "0", 10.0, Anchor::Middle);
+        scene.text(
+            (bx + bar, by - 4.0),
+            format!("{} m", format_tick(length, length)),
+            10.0,
+            Anchor::Middle,
+        );
+    }
+
+    // 指北针：平面图朝上，等轴测图沿北方向的投影
+    if options.view != PlotView::Profile {
+        let (north_x, north_y) = match options.view {
+            PlotView::Isometric => {
+                let (sin, cos) = 30f64.to_radians().sin_cos();
+                (-cos, sin)
+            }
+            _ => (0.0, 1.0),
+        };
+        // 图片坐标向下为正
+        let (dx, dy) = (north_x, -north_y);
+        let norm = (dx * dx + dy * dy).sqrt();
+        let (dx, dy) = (dx / norm, dy / norm);
+        let center = (frame.left + frame.width - 40.0, frame.top + 50.0);
+        let tip = (center.0 + dx * 26.0, center.1 + dy * 26.0);
+        let tail = (center.0 - dx * 18.0, center.1 - dy * 18.0);
+        let (nx, ny) = (-dy, dx);
+        scene.shapes.push(Shape::Polygon {
+            points: vec![tip, (tail.0 + nx * 9.0, tail.1 + ny * 9.0), center],
+            fill: BLACK,
+        });
+        scene.shapes.push(Shape::Polygon {
+            points: vec![tip, center, (tail.0 - nx * 9.0, tail.1 - ny * 9.0)],
+            fill: GRAY,
+        });
+        scene.text(
+            (tip.0 + dx * 12.0, tip.1 + dy * 12.0 + 5.0),
+            "N",
+            14.0,
+            Anchor::Middle,
+        );
+    }
+
+    scene
+}
+
+/// 折线图中的一条曲线
+pub struct ChartSeries<'a> {
+    pub label: &'a str,
+    pub points: Vec<(f64, f64)>,
+    pub color: Color,
+    pub dashed: bool,
+}
+
+/// 折线图的坐标轴范围：约分成 5 段，两端扩展到刻度间隔的整数倍
+fn chart_axis(min: f64, max: f64) -> (f64, f64, f64) {
+    let (min, max) = if (max - min).abs() < 1e-9 {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+    let step = nice_step(max - min, 5.0);
+    (
+        (min / step).floor() * step,
+        (max / step).ceil() * step,
+        step,
+    )
+}
+
+/// 折线图：横纵轴各自缩放，坐标范围包含原点；用于 PDF 报告中的深度 - 位移图，尺寸与字号以点为单位
+pub fn build_chart(
+    title: &str,
+    x_label: &str,
+    y_label: &str,
+    series: &[ChartSeries],
+    width: f64,
+    height: f64,
+) -> Scene {
+    const LEFT: f64 = 50.0;
+    const BOTTOM: f64 = 34.0;
+    const TOP: f64 = 22.0;
+    const RIGHT: f64 = 10.0;
+    let (x0, y0) = (LEFT, TOP);
+    let (plot_width, plot_height) = (width - LEFT - RIGHT, height - TOP - BOTTOM);
+    let mut scene = Scene {
+        width: width.round() as u32,
+        height: height.round() as u32,
+        shapes: Vec::new(),
+    };
+
+    let (x_min, x_max, y_min, y_max) = series.iter().flat_map(|s| &s.points).fold(
+        (0.0f64, 0.0f64, 0.0f64, 0.0f64),
+        |(x1, x2, y1, y2), (x, y)| (x1.min(*x), x2.max(*x), y1.min(*y), y2.max(*y)),
+    );
+    let (x_lo, x_hi, x_step) = chart_axis(x_min, x_max);
+    let (y_lo, y_hi, y_step) = chart_axis(y_min, y_max);
+    let to_pixel = |(x, y): (f64, f64)| {
+        (
+            x0 + (x - x_lo) / (x_hi - x_lo) * plot_width,
+            y0 + plot_height - (y - y_lo) / (y_hi - y_lo) * plot_height,
+        )
+    };
+    let label = |at: (f64, f64), text: String, size: f64, anchor: Anchor, color: Color| {
+        Shape::Text {
+            at,
+            text,
+            size,
+            anchor,
+            color,
+            vertical: false,
+        }
+    };
+
+    scene.text((width / 2.0, y0 - 8.0), title, 10.0, Anchor::Middle);
+
+    // 网格与刻度
+    let mut value = x_lo;
+    while value <= x_hi + x_step / 2.0 {
+        let (x, _) = to_pixel((value, y_lo));
+        scene.line(vec![(x, y0), (x, y0 + plot_height)], GRID, 0.3, false);
+        scene.shapes.push(label(
+            (x, y0 + plot_height + 10.0),
+            format_tick(value, x_step),
+            7.0,
+            Anchor::Middle,
+            GRAY,
+        ));
+        value += x_step;
+    }
+    let mut value = y_lo;
+    while value <= y_hi + y_step / 2.0 {
+        let (_, y) = to_pixel((x_lo, value));
+        scene.line(vec![(x0, y), (x0 + plot_width, y)], GRID, 0.3, false);
+        scene.shapes.push(label(
+            (x0 - 3.0, y + 2.5),
+            format_tick(value, y_step),
+            7.0,
+            Anchor::End,
+            GRAY,
+        ));
+        value += y_step;
+    }
+    scene.shapes.push(Shape::Rect {
+        x: x0,
+        y: y0,
+        w: plot_width,
+        h: plot_height,
+        stroke: Some(BLACK),
+        fill: None,
+    });
+    scene.text(
+        (x0 + plot_width / 2.0, y0 + plot_height + 24.0),
+        x_label,
+        8.0,
+        Anchor::Middle,
+    );
+    scene.shapes.push(Shape::Text {
+        at: (10.0, y0 + plot_height / 2.0),
+        text: y_label.to_string(),
+        size: 8.0,
+        anchor: Anchor::Middle,
+        color: BLACK,
+        vertical: true,
+    });
+
+    for s in series {
+        let points = s.points.iter().copied().map(to_pixel).collect();
+        scene.line(points, s.color, 1.2, s.dashed);
+    }
+
+    // 图例放在右上角，文字右对齐
+    let right = x0 + plot_width - 6.0;
+    for (i, s) in series.iter().enumerate() {
+        let y = y0 + 10.0 + i as f64 * 11.0;
+        let sample = right - label_width(s.label, 8.0) - 6.0;
+        scene.line(
+            vec![(sample - 20.0, y - 3.0), (sample, y - 3.0)],
+            s.color,
+            1.2,
+            s.dashed,
+        );
+        scene.text((right, y), s.label, 8.0, Anchor::End);
+    }
+    scene
+}
+
+/// 轨迹图，格式由 format 决定；SVG 为 UTF-8 文本
+pub fn render(
+    holes: &[DxfHole],
+    magnetic_declination: f64,
+    locale: Locale,
+    options: &PlotOptions,
+    format: PlotFormat,
+) -> Result<Vec<u8>> {
+    options.validate()?;
+    let scene = build_scene(holes, magnetic_declination, locale, options);
+    match format {
+        PlotFormat::Svg => Ok(scene.to_svg().into_bytes()),
+        PlotFormat::Png => scene.to_png(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::data::DataList;
+    use crate::models::repo::Repo;
+    use crate::services::dxf::Collar;
+
+    fn station(depth: f64) -> DataList {
+        DataList {
+            id: None,
+            uuid: None,
+            time: None,
+            depth,
+            pitch: Some(-2.0),
+            roll: None,
+            heading: Some(95.0),
+            repo_id: None,
+            design_pitch: Some(0.0),
+            design_heading: Some(90.0),
+        }
+    }
+
+    fn plot(format: PlotFormat, width: u32, height: u32) -> Vec<u8> {
+        let repo = Repo {
+            id: Some(1),
+            uuid: None,
+            name: "ZK-1 西".to_string(),
+            mn_time: "2024-05-06 08:30:00".to_string(),
+            len: 50,
+            mine: String::new(),
+            work: String::new(),
+            factory: String::new(),
+            drilling: String::new(),
+            notes: None,
+            mine_id: None,
+            working_face_id: None,
+            drilling_site_id: None,
+            rig_id: None,
+        };
+        let stations: Vec<DataList> = (1..=5).map(|i| station(i as f64 * 10.0)).collect();
+        let hole = DxfHole {
+            repo: &repo,
+            stations: &stations,
+            collar: Collar {
+                east: 500.0,
+                north: 300.0,
+                elevation: 120.0,
+            },
+        };
+        let options = PlotOptions {
+            width,
+            height,
+            ..PlotOptions::default()
+        };
+        render(&[hole], 0.0, Locale::ZhCn, &options, format).unwrap()
+    }
+
+    #[test]
+    fn nice_step_rounds_up_to_1_2_5() {
+        assert_eq!(nice_step(100.0, 10.0), 10.0);
+        assert_eq!(nice_step(120.0, 10.0), 20.0);
+        assert_eq!(nice_step(300.0, 10.0), 50.0);
+        assert_eq!(nice_step(0.7, 8.0), 0.1);
+        assert_eq!(nice_step(8.0, 8.0), 1.0);
+    }
+
+    #[test]
+    fn format_tick_uses_step_decimals() {
+        assert_eq!(format_tick(120.0, 20.0), "120");
+        assert_eq!(format_tick(0.25, 0.05), "0.25");
+        assert_eq!(format_tick(1.5, 0.5), "1.5");
+        // 负零写成 0
+        assert_eq!(format_tick(-0.0001, 0.1), "0.0");
+        assert_eq!(format_tick(-3.0, 1.0), "-3");
+    }
+
+    #[test]
+    fn svg_keeps_size_and_hole_name() {
+        let svg = String::from_utf8(plot(PlotFormat::Svg, 640, 480)).unwrap();
+        assert!(svg.contains(r#"width="640" height="480" viewBox="0 0 640 480""#));
+        assert!(svg.contains("ZK-1 西"));
+        assert!(svg.contains("平面图"), "{}", svg);
+    }
+
+    #[test]
+    fn png_has_requested_size() {
+        let png = plot(PlotFormat::Png, 640, 480);
+        let decoder = png::Decoder::new(png.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (640, 480));
+    }
+}
@@ -0,0 +1,223 @@
+//! 高斯-克吕格（横轴墨卡托）投影坐标反算经纬度
+//!
+//! 孔口坐标通常是矿区使用的 3° 或 6° 带高斯投影坐标，KML 需要经纬度。这里只做投影反算，
+//! 不做基准转换：CGCS2000 与 WGS84 相差在厘米级，北京 54、西安 80 坐标直接按所在椭球反算，
+//! 与 WGS84 经纬度可能相差数十米，用于地图概览足够，不能用于放样。
+//!
+//! UTM 是比例因子为 0.9996 的横轴墨卡托投影，按对应的中央子午线、比例因子和假东（北）距设置即可。
+
+use crate::i18n::{t, tf, Msg};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// 参考椭球
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ellipsoid {
+    #[default]
+    Cgcs2000,
+    Wgs84,
+    // 北京 54，克拉索夫斯基椭球
+    Beijing54,
+    // 西安 80，IAG-75 椭球
+    Xian80,
+}
+
+impl Ellipsoid {
+    /// 长半轴（米）与扁率
+    fn parameters(self) -> (f64, f64) {
+        match self {
+            Ellipsoid::Cgcs2000 => (6_378_137.0, 1.0 / 298.257_222_101),
+            Ellipsoid::Wgs84 => (6_378_137.0, 1.0 / 298.257_223_563),
+            Ellipsoid::Beijing54 => (6_378_245.0, 1.0 / 298.3),
+            Ellipsoid::Xian80 => (6_378_140.0, 1.0 / 298.257),
+        }
+    }
+}
+
+fn default_scale_factor() -> f64 {
+    1.0
+}
+
+fn default_false_easting() -> f64 {
+    500_000.0
+}
+
+/// 横轴墨卡托投影参数，孔口坐标的东、北坐标按此反算经纬度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Projection {
+    #[serde(default)]
+    pub ellipsoid: Ellipsoid,
+    // 中央子午线经度（度），东经为正
+    pub central_meridian: f64,
+    // 中央子午线上的比例因子，高斯投影为 1，UTM 为 0.9996
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f64,
+    // 假东距（米）；东坐标带有带号时（如 39500000）应把带号计入假东距
+    #[serde(default = "default_false_easting")]
+    pub false_easting: f64,
+    // 假北距（米），南半球 UTM 为 10000000
+    #[serde(default)]
+    pub false_northing: f64,
+}
+
+impl Projection {
+    pub fn validate(&self) -> Result<()> {
+        if !self.central_meridian.is_finite() || self.central_meridian.abs() > 180.0 {
+            return Err(anyhow!(t(Msg::ProjectionMeridianRange)));
+        }
+        if !self.scale_factor.is_finite() || !(0.9..=1.1).contains(&self.scale_factor) {
+            return Err(anyhow!(t(Msg::ProjectionScaleRange)));
+        }
+        if !self.false_easting.is_finite() || !self.false_northing.is_finite() {
+            return Err(anyhow!(t(Msg::ProjectionOffsetInvalid)));
+        }
+        Ok(())
+    }
+
+    /// 东、北坐标（米）反算经度、纬度（度）
+    ///
+    /// 离中央子午线超过 9° 时级数展开误差过大，视为坐标与投影不匹配，
+    /// 多半是东坐标含带号而假东距未计入带号，或中央子午线设错。
+    pub fn to_geographic(&self, east: f64, north: f64) -> Result<(f64, f64)> {
+        let (a, f) = self.ellipsoid.parameters();
+        let k0 = self.scale_factor;
+        let e2 = f * (2.0 - f);
+        let ep2 = e2 / (1.0 - e2);
+        let e4 = e2 * e2;
+        let e6 = e4 * e2;
+
+        // 底点纬度
+        let m = (north - self.false_northing) / k0;
+        let mu = m / (a * (1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0));
+        let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+        let phi1 = mu
+            + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+            + (21.0 * e1.powi(2) / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+            + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+            + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+        let (sin1, cos1) = phi1.sin_cos();
+        let tan1 = phi1.tan();
+        let c1 = ep2 * cos1 * cos1;
+        let t1 = tan1 * tan1;
+        let w = 1.0 - e2 * sin1 * sin1;
+        let n1 = a / w.sqrt();
+        let r1 = a * (1.0 - e2) / w.powf(1.5);
+        let d = (east - self.false_easting) / (n1 * k0);
+
+        let latitude = phi1
+            - (n1 * tan1 / r1)
+                * (d.powi(2) / 2.0
+                    - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                    + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1
+                        - 252.0 * ep2
+                        - 3.0 * c1 * c1)
+                        * d.powi(6)
+                        / 720.0);
+        let delta = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+            + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1)
+                * d.powi(5)
+                / 120.0)
+            / cos1;
+
+        let longitude = self.central_meridian + delta.to_degrees();
+        let latitude = latitude.to_degrees();
+        if !longitude.is_finite()
+            || !latitude.is_finite()
+            || delta.to_degrees().abs() > 9.0
+            || latitude.abs() > 89.0
+        {
+            return Err(anyhow!(tf(
+                Msg::ProjectionOutOfZone,
+                &[
+                    ("east", &format!("{:.3}", east)),
+                    ("north", &format!("{:.3}", north)),
+                ],
+            )));
+        }
+        let longitude = (longitude + 540.0).rem_euclid(360.0) - 180.0;
+        Ok((longitude, latitude))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 参考坐标由 Krüger 六阶级数正算得到，精度优于 1 mm
+    const EPS: f64 = 1e-7;
+
+    fn projection(central_meridian: f64, false_easting: f64) -> Projection {
+        Projection {
+            ellipsoid: Ellipsoid::Cgcs2000,
+            central_meridian,
+            scale_factor: 1.0,
+            false_easting,
+            false_northing: 0.0,
+        }
+    }
+
+    fn assert_geographic(projection: &Projection, (east, north): (f64, f64), expected: (f64, f64)) {
+        let (longitude, latitude) = projection.to_geographic(east, north).unwrap();
+        assert!((longitude - expected.0).abs() < EPS, "{}", longitude);
+        assert!((latitude - expected.1).abs() < EPS, "{}", latitude);
+    }
+
+    #[test]
+    fn cgcs2000_3_degree_zone() {
+        let projection = projection(114.0, 500_000.0);
+        assert_geographic(&projection, (547_999.761, 3_375_648.033), (114.5, 30.5));
+    }
+
+    #[test]
+    fn cgcs2000_6_degree_zone_with_zone_number() {
+        // 20 带，东坐标含带号
+        let projection = projection(117.0, 20_500_000.0);
+        assert_geographic(
+            &projection,
+            (20_510_730.161, 4_057_676.654),
+            (117.12, 36.65),
+        );
+        // 靠近带边缘
+        assert_geographic(&projection, (20_759_344.264, 4_061_589.674), (119.9, 36.65));
+    }
+
+    #[test]
+    fn utm_zones() {
+        let north = Projection {
+            ellipsoid: Ellipsoid::Wgs84,
+            central_meridian: 117.0,
+            scale_factor: 0.9996,
+            false_easting: 500_000.0,
+            false_northing: 0.0,
+        };
+        assert_geographic(&north, (448_709.380, 4_416_830.562), (116.4, 39.9));
+
+        let south = Projection {
+            central_meridian: 153.0,
+            false_northing: 10_000_000.0,
+            ..north
+        };
+        assert_geographic(&south, (334_416.394, 6_251_925.360), (151.21, -33.86));
+    }
+
+    #[test]
+    fn central_meridian_maps_to_itself() {
+        let projection = projection(114.0, 500_000.0);
+        let (longitude, latitude) = projection.to_geographic(500_000.0, 0.0).unwrap();
+        assert!((longitude - 114.0).abs() < EPS);
+        assert!(latitude.abs() < EPS);
+    }
+
+    #[test]
+    fn coordinates_outside_zone_are_rejected() {
+        // 东坐标含带号而假东距未计入带号
+        let projection = projection(117.0, 500_000.0);
+        assert!(projection
+            .to_geographic(20_510_730.161, 4_057_676.654)
+            .is_err());
+        // 纬度超出范围
+        assert!(projection.to_geographic(500_000.0, 20_000_000.0).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! 导出记录：测点原始数据与轨迹计算结果
+//!
+//! Excel、CSV、JSON 与 GeoJSON 导出都从这里取值，保证各格式中同一列的数值一致。
+
+use crate::calc::{self, TrajectoryPoint};
+use crate::models::data::DataList;
+use crate::services::templates::ColumnField;
+use serde::{Deserialize, Serialize};
+
+/// 一个测点的原始数据及计算结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationRecord {
+    // 测点序号，从 1 开始
+    pub index: usize,
+    pub time: Option<String>,
+    pub depth: f64,
+    // 仪器原始读数
+    pub pitch: Option<f64>,
+    pub roll: Option<f64>,
+    pub heading: Option<f64>,
+    pub design_pitch: Option<f64>,
+    pub design_heading: Option<f64>,
+    // 改正后的方位角（实测减磁偏角），缺失时沿用上一测点
+    pub corrected_heading: f64,
+    // 以孔口为原点的坐标，高程向上为正
+    pub north: f64,
+    pub east: f64,
+    pub elevation: f64,
+    // 相对设计方位的左右、上下位移
+    pub lateral: f64,
+    pub vertical: f64,
+    pub design_lateral: f64,
+    pub design_vertical: f64,
+    // 狗腿度（度/30米）
+    pub dogleg: f64,
+}
+
+impl StationRecord {
+    fn new(index: usize, station: &DataList, point: &TrajectoryPoint) -> Self {
+        Self {
+            index: index + 1,
+            time: station.time.clone(),
+            depth: station.depth,
+            pitch: station.pitch,
+            roll: station.roll,
+            heading: station.heading,
+            design_pitch: station.design_pitch,
+            design_heading: station.design_heading,
+            corrected_heading: point.heading,
+            north: point.north,
+            east: point.east,
+            elevation: point.elevation,
+            lateral: point.lateral,
+            vertical: point.vertical,
+            // 设计左右位移恒为 0
+            design_lateral: 0.0,
+            design_vertical: point.design_vertical,
+            dogleg: point.dogleg,
+        }
+    }
+
+    /// 按列取值
+    pub fn value(&self, field: ColumnField) -> FieldValue {
+        let optional = |value: Option<f64>| value.map_or(FieldValue::Blank, FieldValue::Number);
+        match field {
+            ColumnField::Index => FieldValue::Number(self.index as f64),
+            ColumnField::Time => FieldValue::Text(self.time.clone().unwrap_or_default()),
+            ColumnField::Depth => FieldValue::Number(self.depth),
+            ColumnField::Pitch => optional(self.pitch),
+            ColumnField::Roll => optional(self.roll),
+            ColumnField::Heading => optional(self.heading),
+            ColumnField::DesignPitch => optional(self.design_pitch),
+            ColumnField::DesignHeading => optional(self.design_heading),
+            ColumnField::CorrectedHeading => FieldValue::Number(self.corrected_heading),
+            ColumnField::Lateral => FieldValue::Number(self.lateral),
+            ColumnField::Vertical => FieldValue::Number(self.vertical),
+            ColumnField::DesignLateral => FieldValue::Number(self.design_lateral),
+            ColumnField::DesignVertical => FieldValue::Number(self.design_vertical),
+            ColumnField::North => FieldValue::Number(self.north),
+            ColumnField::East => FieldValue::Number(self.east),
+            ColumnField::Elevation => FieldValue::Number(self.elevation),
+            ColumnField::Dogleg => FieldValue::Number(self.dogleg),
+            ColumnField::CadPlan => FieldValue::CadPoint(self.lateral, self.vertical),
+            ColumnField::CadProfile => FieldValue::CadPoint(0.0, self.vertical),
+        }
+    }
+}
+
+/// 单元格的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Number(f64),
+    Text(String),
+    // CAD 相对坐标，写出时格式化为 `@x,y`
+    CadPoint(f64, f64),
+    Blank,
+}
+
+impl FieldValue {
+    /// CAD 相对坐标文本，如 `@1.23456,-0.12345`
+    pub fn cad_point(x: f64, y: f64, decimal_places: usize) -> String {
+        format!("@{:.*},{:.*}", decimal_places, x, decimal_places, y)
+    }
+}
+
+/// 计算钻孔全部测点的导出记录
+pub fn station_records(stations: &[DataList], magnetic_declination: f64) -> Vec<StationRecord> {
+    records_from_points(stations, &calc::trajectory(stations, magnetic_declination))
+}
+
+/// 由已算好的轨迹点生成导出记录，`points` 与 `stations` 一一对应
+pub fn records_from_points(
+    stations: &[DataList],
+    points: &[TrajectoryPoint],
+) -> Vec<StationRecord> {
+    stations
+        .iter()
+        .zip(points)
+        .enumerate()
+        .map(|(index, (station, point))| StationRecord::new(index, station, point))
+        .collect()
+}
@@ -0,0 +1,323 @@
+use crate::i18n::{t, Msg};
+use crate::models::data::DataList;
+use crate::models::repo::{new_uuid, Repo, Upload};
+use crate::services::archive::{self, ConflictPolicy};
+use crate::services::export::ExportOptions;
+use crate::services::{db, settings, witsml};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info};
+use warp::http::StatusCode;
+use warp::{Filter, Reply};
+
+/// WITSML 上传文件的大小上限
+const WITSML_BODY_LIMIT: u64 = 16 * 1024 * 1024;
+
+fn status_route() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("api")
+        .and(warp::path("status"))
+        .and(warp::get())
+        .map(|| {
+            warp::reply::json(&serde_json::json!({
+                "status": "running",
+                "message": t(Msg::ServerRunning)
+            }))
+        })
+}
+
+fn data_status_route() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("api")
+        .and(warp::path("data"))
+        .and(warp::path("status"))
+        .and(warp::get())
+        .and_then(handle_data_status)
+}
+
+async fn handle_data_status() -> Result<impl warp::Reply, warp::Rejection> {
+    let resp = match db::query_all_repos().await {
+        Ok(repos) => {
+            debug!("获取数据成功: {} 条 repo", repos.len());
+            warp::reply::json(&serde_json::json!({
+                "status": "running",
+                "message": t(Msg::ServerRunning),
+                "data": repos
+            }))
+        }
+        Err(e) => warp::reply::json(&serde_json::json!({
+            "status": "error",
+            "message": format!("{}: {}", t(Msg::FetchDataFailed), e)
+        })),
+    };
+
+    Ok(resp)
+}
+
+fn data_route() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("api")
+        .and(warp::path("data"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(handle_input)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct DrillData {
+    timestamp: i64, // 毫秒时间戳，用 i64 表示
+
+    #[serde(alias = "deviceId")]
+    device_id: String,
+
+    #[serde(alias = "dataType")]
+    data_type: String,
+
+    values: Repo,
+    data_list: Vec<DataList>, // data_list 是对象数组
+}
+
+async fn handle_input(data: DrillData) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut model_repo = data.values;
+    let model_data_list = data.data_list;
+
+    info!(
+        device_id = %data.device_id,
+        data_type = %data.data_type,
+        name = %model_repo.name,
+        stations = model_data_list.len(),
+        "收到钻孔数据"
+    );
+    // 完整上传内容包含现场测量数据，只在 debug 级别记录
+    debug!(repo = ?model_repo, data_list = ?model_data_list, "上传内容");
+
+    // 客户端未提供 UUID 时由服务端生成；已存在的 UUID 视为重复上传
+    let uuid = model_repo.uuid.get_or_insert_with(new_uuid).clone();
+    let resp = match Repo::insert_upload(model_repo, model_data_list).await {
+        Ok(Upload::Inserted(id)) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "success",
+                "message": t(Msg::RepoReceived),
+                "id": id,
+                "uuid": uuid
+            })),
+            StatusCode::OK,
+        ),
+        Ok(Upload::Duplicate(id)) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "success",
+                "message": t(Msg::RepoDuplicate),
+                "id": id,
+                "uuid": uuid,
+                "duplicate": true
+            })),
+            StatusCode::OK,
+        ),
+        Err(e) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "error",
+                "message": format!("{}: {}", t(Msg::DbWriteFailed), e)
+            })),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    };
+
+    Ok(resp)
+}
+
+fn repo_by_uuid_route() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "repos" / String)
+        .and(warp::get())
+        .and_then(handle_repo_by_uuid)
+}
+
+async fn handle_repo_by_uuid(uuid: String) -> Result<impl warp::Reply, warp::Rejection> {
+    let repo = match Repo::query_repo_by_uuid(uuid).await {
+        Ok(Some(repo)) => repo,
+        Ok(None) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "status": "error",
+                    "message": t(Msg::RepoNotFound)
+                })),
+                StatusCode::NOT_FOUND,
+            ));
+        }
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "status": "error",
+                    "message": format!("{}: {}", t(Msg::FetchDataFailed), e)
+                })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ));
+        }
+    };
+
+    let resp = match db::query_data_list_by_repo_id(repo.id.unwrap_or_default()).await {
+        Ok(data_list) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "success",
+                "repo": repo,
+                "data_list": data_list
+            })),
+            StatusCode::OK,
+        ),
+        Err(e) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "error",
+                "message": format!("{}: {}", t(Msg::FetchDataFailed), e)
+            })),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    };
+
+    Ok(resp)
+}
+
+fn witsml_import_route() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "witsml" / "trajectory")
+        .and(warp::post())
+        .and(warp::query::<WitsmlImportQuery>())
+        .and(warp::body::content_length_limit(WITSML_BODY_LIMIT))
+        .and(warp::body::bytes())
+        .and_then(handle_witsml_import)
+}
+
+#[derive(Deserialize, Debug)]
+struct WitsmlImportQuery {
+    // 冲突处理方式，默认跳过；接口不做身份验证，不允许 replace 覆盖已有钻孔
+    on_conflict: Option<ConflictPolicy>,
+}
+
+async fn handle_witsml_import(
+    query: WitsmlImportQuery,
+    body: warp::hyper::body::Bytes,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let policy = query.on_conflict.unwrap_or(ConflictPolicy::Skip);
+    if policy == ConflictPolicy::Replace {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "error",
+                "message": t(Msg::WitsmlReplaceForbidden)
+            })),
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    let holes = match std::str::from_utf8(&body)
+        .map_err(anyhow::Error::from)
+        .and_then(witsml::read_trajectories)
+    {
+        Ok(holes) => holes,
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "status": "error",
+                    "message": format!("{}: {:#}", t(Msg::ImportWitsmlFailed), e)
+                })),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+    };
+    info!(trajectories = holes.len(), "收到 WITSML 轨迹");
+
+    let resp = match archive::import_holes(holes, policy).await {
+        Ok(report) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "success",
+                "message": t(Msg::WitsmlImported),
+                "report": report
+            })),
+            StatusCode::OK,
+        ),
+        Err(e) => {
+            error!("WITSML 导入失败: {}", e);
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "status": "error",
+                    "message": format!("{}: {}", t(Msg::DbWriteFailed), e)
+                })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        }
+    };
+
+    Ok(resp)
+}
+
+fn repo_witsml_route() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "repos" / String / "witsml")
+        .and(warp::get())
+        .and_then(handle_repo_witsml)
+}
+
+async fn handle_repo_witsml(uuid: String) -> Result<warp::reply::Response, warp::Rejection> {
+    let error = |message: String, status: StatusCode| {
+        warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "status": "error",
+                "message": message
+            })),
+            status,
+        )
+        .into_response()
+    };
+
+    let repo = match Repo::query_repo_by_uuid(uuid).await {
+        Ok(Some(repo)) => repo,
+        Ok(None) => {
+            return Ok(error(
+                t(Msg::RepoNotFound).to_string(),
+                StatusCode::NOT_FOUND,
+            ))
+        }
+        Err(e) => {
+            return Ok(error(
+                format!("{}: {}", t(Msg::FetchDataFailed), e),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ));
+        }
+    };
+    let data_list = match db::query_data_list_by_repo_id(repo.id.unwrap_or_default()).await {
+        Ok(data_list) => data_list,
+        Err(e) => {
+            return Ok(error(
+                format!("{}: {}", t(Msg::FetchDataFailed), e),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ));
+        }
+    };
+
+    let current = settings::current();
+    let options = ExportOptions {
+        magnetic_declination: current.survey.magnetic_declination,
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        ..ExportOptions::default()
+    };
+    let xml = witsml::to_witsml(&[(&repo, data_list.as_slice())], &options);
+    Ok(
+        warp::reply::with_header(xml, "content-type", "application/xml; charset=utf-8")
+            .into_response(),
+    )
+}
+
+pub fn init_route() -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    // 把每个功能的路由提取成单独函数
+    let data_route = data_route();
+    let status_route = status_route();
+    let data_status_route = data_status_route();
+    let repo_by_uuid_route = repo_by_uuid_route();
+    let witsml_import_route = witsml_import_route();
+    let repo_witsml_route = repo_witsml_route();
+    // 更多路由可以继续添加
+    // let user_route = user_route();
+    // let info_route = info_route();
+
+    // 合并所有路由，每个请求在独立的 span 中记录
+    data_route
+        .or(status_route)
+        .or(data_status_route)
+        .or(repo_by_uuid_route)
+        .or(witsml_import_route)
+        .or(repo_witsml_route)
+        // .or(info_route)
+        .with(warp::trace::request())
+}
@@ -0,0 +1,202 @@
+use crate::calc;
+use crate::i18n::Msg;
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use crate::services::dxf::{
+    self, LAYER_PLAN_ACTUAL, LAYER_PLAN_DESIGN, LAYER_PLAN_LABELS, LAYER_PLAN_STATIONS,
+    LAYER_PROFILE_ACTUAL, LAYER_PROFILE_DESIGN, LAYER_PROFILE_LABELS, LAYER_PROFILE_STATIONS,
+};
+use crate::services::export::ExportOptions;
+use std::fmt::Write as _;
+
+/// 脚本中的一个点：深度及该深度处的纵坐标
+type Point = (f64, f64);
+
+impl dxf::DepthPoint for Point {
+    fn depth(&self) -> f64 {
+        self.0
+    }
+
+    fn lerp(&self, next: &Self, t: f64) -> Self {
+        (
+            self.0 + (next.0 - self.0) * t,
+            self.1 + (next.1 - self.1) * t,
+        )
+    }
+}
+
+/// 单个视图中的实测与设计轨迹
+struct View {
+    actual: Vec<Point>,
+    design: Vec<Point>,
+}
+
+impl View {
+    /// 纵坐标范围，包含孔口
+    fn y_range(&self) -> (f64, f64) {
+        self.actual
+            .iter()
+            .chain(&self.design)
+            .fold((0.0, 0.0), |(min, max), (_, y)| {
+                (f64::min(min, *y), f64::max(max, *y))
+            })
+    }
+
+    /// 需要标注深度的位置
+    fn labels(&self, interval: f64) -> Vec<Point> {
+        dxf::depth_labels(&self.actual, interval)
+    }
+}
+
+/// 脚本写入器，每行对应一次命令行输入
+struct Script {
+    out: String,
+    decimal_places: usize,
+}
+
+impl Script {
+    fn line(&mut self, input: &str) {
+        self.out.push_str(input);
+        self.out.push_str("\r\n");
+    }
+
+    /// 坐标前加 `_non`，只对这一点关闭对象捕捉，不改动用户的 OSMODE 设置
+    fn point(&mut self, (x, y): Point) {
+        self.line("_non");
+        let _ = write!(
+            self.out,
+            "{:.*},{:.*}\r\n",
+            self.decimal_places, x, self.decimal_places, y
+        );
+    }
+
+    fn number(&mut self, value: f64) {
+        let _ = write!(self.out, "{:.*}\r\n", self.decimal_places, value);
+    }
+
+    /// 新建（或切换到）图层并设为当前图层
+    fn layer(&mut self, name: &str, color: i32) {
+        self.line("_.-LAYER");
+        self.line("_M");
+        self.line(name);
+        self.line("_C");
+        self.line(&color.to_string());
+        self.line(name);
+        self.line("");
+    }
+
+    fn polyline(&mut self, points: &[Point], offset: f64) {
+        if points.len() < 2 {
+            return;
+        }
+        self.line("_.PLINE");
+        for (x, y) in points {
+            self.point((*x, y + offset));
+        }
+        self.line("");
+    }
+
+    fn circle(&mut self, (x, y): Point, radius: f64) {
+        self.line("_.CIRCLE");
+        self.point((x, y));
+        self.number(radius);
+    }
+
+    fn text(&mut self, at: Point, height: f64, text: &str) {
+        self.line("_.TEXT");
+        self.point(at);
+        self.number(height);
+        self.line("0");
+        self.line(&dxf::escape_text(text));
+    }
+}
+
+/// AutoCAD 脚本（.scr）：平面图与剖面图的实测、设计轨迹、测点及深度标注
+///
+/// 横轴为深度，平面图纵轴为左右位移，剖面图纵轴为上下位移，取值与 Excel 中的左右位移、
+/// 上下位移列相同。平面图孔口位于原点，剖面图放在平面图下方。
+/// 文字按当前文字样式书写，样式需为非固定字高。
+pub fn to_scr(repo: &Repo, stations: &[DataList], options: &ExportOptions) -> String {
+    let declination = options.magnetic_declination;
+    let collar = std::iter::once((0.0, 0.0));
+    let view = |value: &dyn Fn(&DataList) -> f64| -> Vec<Point> {
+        collar
+            .clone()
+            .chain(stations.iter().map(|s| (s.depth, value(s))))
+            .collect()
+    };
+    // 左右位移取自轨迹计算，缺失的角度沿用上一测点，与 Excel 一致
+    let plan = View {
+        actual: collar
+            .clone()
+            .chain(
+                calc::trajectory(stations, declination)
+                    .iter()
+                    .map(|p| (p.depth, p.lateral)),
+            )
+            .collect(),
+        // 设计左右位移恒为 0
+        design: view(&|_| 0.0),
+    };
+    let profile = View {
+        actual: view(&calc::vertical_displacement),
+        design: view(&calc::design_vertical_displacement),
+    };
+
+    let height = options.dxf.text_height;
+    let (plan_min, _) = plan.y_range();
+    let (_, profile_max) = profile.y_range();
+    let gap = height * 10.0;
+    let offsets = [(0.0, &plan), (plan_min - gap - profile_max, &profile)];
+    let layers = [
+        (
+            LAYER_PLAN_ACTUAL,
+            LAYER_PLAN_DESIGN,
+            LAYER_PLAN_STATIONS,
+            LAYER_PLAN_LABELS,
+            Msg::ChartPlanTitle,
+        ),
+        (
+            LAYER_PROFILE_ACTUAL,
+            LAYER_PROFILE_DESIGN,
+            LAYER_PROFILE_STATIONS,
+            LAYER_PROFILE_LABELS,
+            Msg::ChartProfileTitle,
+        ),
+    ];
+
+    let mut script = Script {
+        out: String::new(),
+        decimal_places: options.decimal_places,
+    };
+    for ((offset, view), (actual, design, markers, labels, title)) in
+        offsets.into_iter().zip(layers)
+    {
+        script.layer(design, dxf::layer_color(design));
+        script.polyline(&view.design, offset);
+
+        script.layer(actual, dxf::layer_color(actual));
+        script.polyline(&view.actual, offset);
+
+        script.layer(markers, dxf::layer_color(markers));
+        for (x, y) in view.actual.iter().skip(1) {
+            script.circle((*x, y + offset), height / 4.0);
+        }
+
+        script.layer(labels, dxf::layer_color(labels));
+        let (_, max) = view.y_range();
+        script.text(
+            (0.0, offset + max + height * 2.0),
+            height,
+            &format!("{} {}", repo.name, title.text(options.locale)),
+        );
+        for (depth, y) in view.labels(options.dxf.label_interval) {
+            let at = (depth + height / 2.0, y + offset + height / 2.0);
+            script.text(at, height, &format!("{:.1}m", depth));
+        }
+    }
+
+    script.line("_.ZOOM");
+    script.line("_E");
+    script.out
+}
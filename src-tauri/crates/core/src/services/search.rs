@@ -0,0 +1,335 @@
+use crate::models::repo::{prefixed_repo_columns, Repo, REPO_COLUMNS};
+use crate::services::db::{get_conn, DbError};
+use rusqlite::params_from_iter;
+use serde::{Deserialize, Serialize};
+
+/// 高亮标记
+const MARK_START: &str = "<mark>";
+const MARK_END: &str = "</mark>";
+
+/// 查询时用私用区字符临时标出命中位置，转义 HTML 后再替换为高亮标记
+const HIT_START: char = '\u{E000}';
+const HIT_END: char = '\u{E001}';
+
+/// trigram 分词器可检索的最短词长（字符数）
+const MIN_TRIGRAM_LEN: usize = 3;
+
+/// 参与全文检索的列
+const FTS_COLUMNS: [&str; 7] = [
+    "name", "mn_time", "mine", "work", "factory", "drilling", "notes",
+];
+
+/// 默认返回条数
+const DEFAULT_LIMIT: u32 = 50;
+
+/// 全文检索命中结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoSearchHit {
+    pub repo: Repo,
+    // bm25 相关度，数值越小越相关
+    pub rank: f64,
+    // 钻孔名称，HTML 转义后带 <mark> 标记
+    pub name_highlight: String,
+    // 最匹配字段的上下文摘要，HTML 转义后带 <mark> 标记
+    pub snippet: String,
+}
+
+/// 解析后的检索条件
+struct SearchQuery {
+    // FTS5 MATCH 表达式，所有词之间为 AND 关系
+    match_expr: Option<String>,
+    // 不足三个字符、只能用 LIKE 匹配的短词
+    short_terms: Vec<String>,
+}
+
+impl SearchQuery {
+    fn parse(input: &str) -> SearchQuery {
+        let mut phrases = Vec::new();
+        let mut short_terms = Vec::new();
+
+        for term in input.split_whitespace() {
+            // 去掉引号，避免破坏 FTS5 语法
+            let term = term.replace('"', "");
+            if term.is_empty() {
+                continue;
+            }
+            if term.chars().count() >= MIN_TRIGRAM_LEN {
+                phrases.push(format!("\"{}\"", term));
+            } else {
+                short_terms.push(term);
+            }
+        }
+
+        SearchQuery {
+            match_expr: if phrases.is_empty() {
+                None
+            } else {
+                Some(phrases.join(" "))
+            },
+            short_terms,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.match_expr.is_none() && self.short_terms.is_empty()
+    }
+
+    /// 短词的 LIKE 条件及参数
+    fn like_clause(&self) -> (String, Vec<String>) {
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        for term in &self.short_terms {
+            let any_column = FTS_COLUMNS
+                .iter()
+                .map(|col| format!("repo_fts.{} LIKE ? ESCAPE '\\'", col))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            clauses.push(format!("({})", any_column));
+            let pattern = format!("%{}%", escape_like(term));
+            params.extend(vec![pattern; FTS_COLUMNS.len()]);
+        }
+        (clauses.join(" AND "), params)
+    }
+}
+
+/// 转义 LIKE 通配符（配合 `ESCAPE '\'`），短词按字面匹配
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 与 SQLite 的 LIKE 一致，只忽略 ASCII 字母的大小写
+fn contains_ignore_ascii_case(text: &str, term: &str) -> bool {
+    text.to_ascii_lowercase()
+        .contains(&term.to_ascii_lowercase())
+}
+
+/// 为没有 FTS 辅助函数可用的短词手动标出命中位置，保留原文的大小写
+fn mark_terms(text: &str, terms: &[String]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let hit = terms.iter().find_map(|t| {
+            rest.get(..t.len())
+                .filter(|head| !t.is_empty() && head.eq_ignore_ascii_case(t))
+        });
+        if let Some(head) = hit {
+            result.push(HIT_START);
+            result.push_str(head);
+            result.push(HIT_END);
+            rest = &rest[head.len()..];
+            continue;
+        }
+        let ch = rest.chars().next().unwrap();
+        result.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+
+    result
+}
+
+/// 只有短词时没有 FTS 摘要，取第一个含有短词的字段作为摘要
+fn matching_field(repo: &Repo, terms: &[String]) -> String {
+    let fields = [
+        repo.name.as_str(),
+        repo.mn_time.as_str(),
+        repo.mine.as_str(),
+        repo.work.as_str(),
+        repo.factory.as_str(),
+        repo.drilling.as_str(),
+        repo.notes.as_deref().unwrap_or_default(),
+    ];
+    fields
+        .into_iter()
+        .find(|field| terms.iter().any(|t| contains_ignore_ascii_case(field, t)))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 转义数据库中的原始文本，再把命中位置换成 `<mark>` 标记，结果可直接作为 HTML 显示
+fn to_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            HIT_START => html.push_str(MARK_START),
+            HIT_END => html.push_str(MARK_END),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(ch),
+        }
+    }
+    html
+}
+
+/// 按相关度检索 repo，返回带高亮摘要的结果
+pub async fn search_repos(query: &str, limit: Option<u32>) -> Result<Vec<RepoSearchHit>, DbError> {
+    let parsed = SearchQuery::parse(query);
+    if parsed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let conn_guard = get_conn().await?;
+    let conn = conn_guard.as_ref().unwrap();
+
+    let (like_clause, like_params) = parsed.like_clause();
+    let short_terms = parsed.short_terms.clone();
+    let has_fts = parsed.match_expr.is_some();
+
+    conn.call(move |c| {
+        let mut conditions = Vec::new();
+        let mut params: Vec<String> = Vec::new();
+
+        let sql = if let Some(match_expr) = parsed.match_expr {
+            conditions.push("repo_fts MATCH ?".to_string());
+            params.push(match_expr);
+            if !like_clause.is_empty() {
+                conditions.push(like_clause);
+                params.extend(like_params);
+            }
+            format!(
+                "SELECT {columns},
+                        bm25(repo_fts, 10.0, 2.0, 5.0, 5.0, 3.0, 3.0, 1.0) AS score,
+                        highlight(repo_fts, 0, '{start}', '{end}'),
+                        snippet(repo_fts, -1, '{start}', '{end}', '…', 16)
+                 FROM repo_fts
+                 JOIN repo r ON r.id = repo_fts.rowid
+                 WHERE {cond}
+                 ORDER BY score
+                 LIMIT {limit}",
+                columns = prefixed_repo_columns("r"),
+                start = HIT_START,
+                end = HIT_END,
+                cond = conditions.join(" AND "),
+                limit = limit,
+            )
+        } else {
+            params.extend(like_params);
+            format!(
+                "SELECT {columns},
+                        0.0, r.name, ''
+                 FROM repo_fts
+                 JOIN repo r ON r.id = repo_fts.rowid
+                 WHERE {cond}
+                 ORDER BY r.id DESC
+                 LIMIT {limit}",
+                columns = prefixed_repo_columns("r"),
+                cond = like_clause,
+                limit = limit,
+            )
+        };
+
+        // 排名、高亮列紧跟在 repo 列之后
+        let base = REPO_COLUMNS.split(", ").count();
+        let mut stmt = c.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params.iter()), |row| {
+            Ok(RepoSearchHit {
+                repo: Repo::from_row(row)?,
+                rank: row.get(base)?,
+                name_highlight: row.get(base + 1)?,
+                snippet: row.get(base + 2)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for hit in rows {
+            let mut hit = hit?;
+            if !has_fts {
+                hit.snippet = matching_field(&hit.repo, &short_terms);
+            }
+            if !short_terms.is_empty() {
+                hit.name_highlight = mark_terms(&hit.name_highlight, &short_terms);
+                hit.snippet = mark_terms(&hit.snippet, &short_terms);
+            }
+            hit.name_highlight = to_html(&hit.name_highlight);
+            hit.snippet = to_html(&hit.snippet);
+            result.push(hit);
+        }
+        Ok::<_, rusqlite::Error>(result)
+    })
+    .await
+    .map_err(|e| DbError::Other(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn terms(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_splits_fts_phrases_and_short_terms() {
+        let query = SearchQuery::parse(r#"  ZK-12 "一矿" 东 a"b  """#);
+        assert_eq!(query.match_expr.as_deref(), Some(r#""ZK-12""#));
+        assert_eq!(query.short_terms, terms(&["一矿", "东", "ab"]));
+        assert!(!query.is_empty());
+        assert!(SearchQuery::parse(r#"  "" "#).is_empty());
+    }
+
+    #[test]
+    fn like_wildcards_match_literally() {
+        let c = Connection::open_in_memory().unwrap();
+        let like = |text: &str, term: &str| -> bool {
+            c.query_row(
+                "SELECT ? LIKE ? ESCAPE '\\'",
+                [text, &format!("%{}%", escape_like(term))],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert!(like("完成50%", "0%"));
+        assert!(!like("完成500", "0%"));
+        assert!(like("ZK_1", "K_"));
+        assert!(!like("ZK-1", "K_"));
+        assert!(like(r"C:\data", r"\d"));
+        assert!(like("Zk-1", "zK"));
+    }
+
+    #[test]
+    fn mark_terms_ignores_ascii_case_and_keeps_original_text() {
+        let marked = mark_terms("Zk-1 东翼 zk", &terms(&["zK", "东"]));
+        assert_eq!(
+            to_html(&marked),
+            "<mark>Zk</mark>-1 <mark>东</mark>翼 <mark>zk</mark>"
+        );
+        assert_eq!(mark_terms("abc", &terms(&[""])), "abc");
+    }
+
+    #[test]
+    fn to_html_escapes_text_outside_marks() {
+        let text = format!("<b>{}&{}\"'", HIT_START, HIT_END);
+        assert_eq!(to_html(&text), "&lt;b&gt;<mark>&amp;</mark>&quot;&#39;");
+    }
+
+    #[test]
+    fn snippet_comes_from_matching_field() {
+        let repo = Repo {
+            id: Some(1),
+            uuid: None,
+            name: "ZK-1".to_string(),
+            mn_time: "2024-05-06 08:30:00".to_string(),
+            len: 30,
+            mine: "一矿".to_string(),
+            work: "1201".to_string(),
+            factory: "3#".to_string(),
+            drilling: "ZDY-6000".to_string(),
+            notes: Some("备注".to_string()),
+            mine_id: None,
+            working_face_id: None,
+            drilling_site_id: None,
+            rig_id: None,
+        };
+        assert_eq!(matching_field(&repo, &terms(&["矿"])), "一矿");
+        assert_eq!(matching_field(&repo, &terms(&["zd"])), "ZDY-6000");
+        assert_eq!(matching_field(&repo, &terms(&["注"])), "备注");
+        assert_eq!(matching_field(&repo, &terms(&["无"])), "");
+    }
+}
@@ -0,0 +1,87 @@
+use crate::i18n::{t, tf, Msg};
+use crate::services::router::init_route;
+use anyhow::{anyhow, Result};
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+/// TLS 证书与私钥（PEM 格式）
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// 数据接收服务器参数
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub port: u16,
+    pub tls: Option<TlsConfig>,
+}
+
+/// 在后台启动数据接收服务器，shutdown 完成时优雅退出
+///
+/// 返回实际监听的地址和服务器任务句柄。
+pub fn spawn_server(
+    config: ServerConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), config.port);
+
+    let (addr, server) = match config.tls {
+        None => {
+            let (addr, server) = warp::serve(init_route())
+                .try_bind_with_graceful_shutdown(addr, shutdown)
+                .map_err(|e| {
+                    anyhow!(tf(
+                        Msg::ServerBindFailed,
+                        &[("port", &config.port), ("error", &e)]
+                    ))
+                })?;
+            (addr, tokio::spawn(server))
+        }
+        Some(tls) => spawn_tls_server(addr, tls, shutdown)?,
+    };
+
+    info!("数据服务器已启动: {}", addr);
+    Ok((addr, server))
+}
+
+#[cfg(feature = "tls")]
+fn spawn_tls_server(
+    addr: SocketAddr,
+    tls: TlsConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+    for path in [&tls.cert_path, &tls.key_path] {
+        if !path.is_file() {
+            return Err(anyhow!(tf(
+                Msg::TlsFileMissing,
+                &[("path", &path.display())]
+            )));
+        }
+    }
+    let (addr, server) = warp::serve(init_route())
+        .tls()
+        .cert_path(&tls.cert_path)
+        .key_path(&tls.key_path)
+        .try_bind_with_graceful_shutdown(addr, shutdown)
+        .map_err(|e| {
+            anyhow!(tf(
+                Msg::TlsBindFailed,
+                &[("port", &addr.port()), ("error", &e)]
+            ))
+        })?;
+    Ok((addr, tokio::spawn(server)))
+}
+
+#[cfg(not(feature = "tls"))]
+fn spawn_tls_server(
+    _addr: SocketAddr,
+    _tls: TlsConfig,
+    _shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+    Err(anyhow!(t(Msg::TlsUnsupported)))
+}
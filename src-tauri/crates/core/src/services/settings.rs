@@ -0,0 +1,402 @@
+use crate::i18n::{t, tf, Locale, Msg};
+use crate::services::backup::SnapshotConfig;
+use crate::services::export::CsvOptions;
+use crate::services::pdf::PdfOptions;
+use crate::services::plot::PlotOptions;
+use crate::services::projection::Projection;
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tracing::warn;
+
+/// 当前设置结构版本，结构不兼容变化时递增并在 migrate 中补充升级逻辑
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// 设置文件名（位于应用配置目录）
+const SETTINGS_FILE: &str = "settings.json";
+
+/// 旧版快照配置文件（位于应用数据目录的 snapshots 下），首次加载时并入设置
+const LEGACY_SNAPSHOT_CONFIG: &str = "snapshots/snapshot.json";
+
+/// 设置文件路径
+static SETTINGS_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// 当前生效的设置
+static SETTINGS: OnceCell<RwLock<Settings>> = OnceCell::new();
+
+/// 应用设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    // 后端消息与导出表头的语言
+    pub locale: Locale,
+    pub network: NetworkSettings,
+    pub survey: SurveySettings,
+    pub export: ExportSettings,
+    pub snapshot: SnapshotConfig,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            locale: Locale::default(),
+            network: NetworkSettings::default(),
+            survey: SurveySettings::default(),
+            export: ExportSettings::default(),
+            snapshot: SnapshotConfig::default(),
+        }
+    }
+}
+
+/// 网络设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    // 局域网发现服务监听端口
+    pub discovery_port: u16,
+    // 数据接收服务器端口
+    pub data_port: u16,
+    // 客户端监听广播的端口
+    pub client_port: u16,
+    pub server_name: String,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            discovery_port: 9090,
+            data_port: 8080,
+            client_port: 9091,
+            server_name: "钻孔轨迹仪数据处理系统".to_string(),
+        }
+    }
+}
+
+/// 测量计算设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurveySettings {
+    // 磁偏角（度），东偏为正
+    pub magnetic_declination: f64,
+    pub tolerance: ToleranceSettings,
+}
+
+impl Default for SurveySettings {
+    fn default() -> Self {
+        Self {
+            magnetic_declination: 0.0,
+            tolerance: ToleranceSettings::default(),
+        }
+    }
+}
+
+/// 轨迹偏差容许值，超出时在报表中标记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToleranceSettings {
+    // 左右位移容许值（米）
+    pub lateral: f64,
+    // 上下位移容许值（米）
+    pub vertical: f64,
+    // 俯仰角偏差容许值（度）
+    pub pitch: f64,
+    // 方位角偏差容许值（度）
+    pub heading: f64,
+}
+
+impl Default for ToleranceSettings {
+    fn default() -> Self {
+        Self {
+            lateral: 1.0,
+            vertical: 1.0,
+            pitch: 2.0,
+            heading: 3.0,
+        }
+    }
+}
+
+/// 导出设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportSettings {
+    // 默认导出目录，导出时给出相对路径则以此为基准
+    pub default_dir: Option<String>,
+    // 坐标等计算结果保留的小数位数
+    pub decimal_places: usize,
+    // 默认 Excel 报表模板名称，为空时使用内置模板
+    pub template: Option<String>,
+    // CSV 分隔符与小数点
+    pub csv: CsvOptions,
+    // PDF 报告的公司名称、标志与签字栏
+    pub pdf: PdfOptions,
+    // 轨迹图的默认视图、尺寸与样式
+    pub plot: PlotOptions,
+    // 孔口坐标的投影，KML 导出时用于反算经纬度
+    pub projection: Option<Projection>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            default_dir: None,
+            decimal_places: 5,
+            template: None,
+            csv: CsvOptions::default(),
+            pdf: PdfOptions::default(),
+            plot: PlotOptions::default(),
+            projection: None,
+        }
+    }
+}
+
+impl NetworkSettings {
+    /// 校验网络设置
+    pub fn validate(&self) -> Result<()> {
+        for (label, port) in [
+            (Msg::SettingDiscoveryPort, self.discovery_port),
+            (Msg::SettingDataPort, self.data_port),
+            (Msg::SettingClientPort, self.client_port),
+        ] {
+            if port == 0 {
+                return Err(anyhow!(tf(Msg::SettingPortZero, &[("field", &t(label))])));
+            }
+        }
+        if self.discovery_port == self.data_port {
+            return Err(anyhow!(t(Msg::SettingPortClash)));
+        }
+        if self.server_name.trim().is_empty() {
+            return Err(anyhow!(t(Msg::SettingServerNameEmpty)));
+        }
+        Ok(())
+    }
+}
+
+impl SurveySettings {
+    /// 校验测量计算设置
+    pub fn validate(&self) -> Result<()> {
+        if !self.magnetic_declination.is_finite()
+            || !(-180.0..=180.0).contains(&self.magnetic_declination)
+        {
+            return Err(anyhow!(t(Msg::SettingDeclinationRange)));
+        }
+        let tolerance = &self.tolerance;
+        for (label, value) in [
+            (Msg::FieldToleranceLateral, tolerance.lateral),
+            (Msg::FieldToleranceVertical, tolerance.vertical),
+            (Msg::FieldTolerancePitch, tolerance.pitch),
+            (Msg::FieldToleranceHeading, tolerance.heading),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(anyhow!(tf(
+                    Msg::SettingToleranceNegative,
+                    &[("field", &t(label))]
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ExportSettings {
+    /// 校验导出设置
+    pub fn validate(&self) -> Result<()> {
+        if self.decimal_places > 10 {
+            return Err(anyhow!(t(Msg::SettingDecimalPlaces)));
+        }
+        self.csv.validate()?;
+        self.plot.validate()?;
+        if let Some(projection) = &self.projection {
+            projection.validate()?;
+        }
+        if let Some(dir) = &self.default_dir {
+            if !dir.trim().is_empty() && !Path::new(dir).is_absolute() {
+                return Err(anyhow!(t(Msg::SettingExportDirRelative)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 校验快照设置
+fn validate_snapshot(snapshot: &SnapshotConfig) -> Result<()> {
+    if snapshot.retention == 0 {
+        return Err(anyhow!(t(Msg::SettingSnapshotRetention)));
+    }
+    Ok(())
+}
+
+impl Settings {
+    /// 校验设置取值
+    pub fn validate(&self) -> Result<()> {
+        self.network.validate()?;
+        self.survey.validate()?;
+        self.export.validate()?;
+        validate_snapshot(&self.snapshot)
+    }
+
+    /// 按默认导出目录解析导出路径
+    pub fn resolve_export_path(&self, file_path: &str) -> String {
+        match &self.export.default_dir {
+            Some(dir) if !dir.trim().is_empty() && Path::new(file_path).is_relative() => {
+                Path::new(dir).join(file_path).to_string_lossy().to_string()
+            }
+            _ => file_path.to_string(),
+        }
+    }
+}
+
+/// 把旧版本的设置 JSON 升级到当前结构
+fn migrate(mut value: serde_json::Value, app_data_dir: &Path) -> serde_json::Value {
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+
+    // v0 -> v1：快照配置原先单独保存在 snapshots/snapshot.json
+    if version < 1 && value.get("snapshot").is_none() {
+        let legacy = std::fs::read_to_string(app_data_dir.join(LEGACY_SNAPSHOT_CONFIG))
+            .ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok());
+        if let (Some(object), Some(legacy)) = (value.as_object_mut(), legacy) {
+            object.insert("snapshot".to_string(), legacy);
+        }
+    }
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".into(), SETTINGS_VERSION.into());
+    }
+    value
+}
+
+/// 读取设置中的一节；缺失时使用默认值，类型不符或取值无效时恢复默认值并记录节名
+fn load_section<T: DeserializeOwned + Default>(
+    value: &serde_json::Value,
+    key: &'static str,
+    validate: impl Fn(&T) -> Result<()>,
+    reset: &mut Vec<&'static str>,
+) -> T {
+    let Some(raw) = value.get(key) else {
+        return T::default();
+    };
+    let error = match serde_json::from_value::<T>(raw.clone()) {
+        Ok(section) => match validate(&section) {
+            Ok(()) => return section,
+            Err(e) => e,
+        },
+        Err(e) => e.into(),
+    };
+    warn!("设置项 {} 无效，已恢复默认值: {}", key, error);
+    reset.push(key);
+    T::default()
+}
+
+/// 逐节读取设置，返回设置及被恢复默认值的节名
+fn load_sections(value: &serde_json::Value) -> (Settings, Vec<&'static str>) {
+    let mut reset = Vec::new();
+    let settings = Settings {
+        version: SETTINGS_VERSION,
+        locale: load_section(value, "locale", |_: &Locale| Ok(()), &mut reset),
+        network: load_section(value, "network", NetworkSettings::validate, &mut reset),
+        survey: load_section(value, "survey", SurveySettings::validate, &mut reset),
+        export: load_section(value, "export", ExportSettings::validate, &mut reset),
+        snapshot: load_section(value, "snapshot", validate_snapshot, &mut reset),
+    };
+    (settings, reset)
+}
+
+/// 加载设置文件，缺失的字段使用默认值；升级后立即写回
+///
+/// 文件无法解析时整体使用默认设置，某一节无效时只恢复该节；两种情况都会把原文件另存为
+/// `settings.json.bak` 并写回修复后的设置，不影响程序启动。
+pub fn init_settings(app_config_dir: &Path, app_data_dir: &Path) -> Result<Settings> {
+    std::fs::create_dir_all(app_config_dir)?;
+    let path = app_config_dir.join(SETTINGS_FILE);
+
+    let mut damaged = false;
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(text) => match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(value) if value.is_object() => value,
+            Ok(_) => {
+                warn!("设置文件不是 JSON 对象，使用默认设置");
+                damaged = true;
+                serde_json::json!({})
+            }
+            Err(e) => {
+                warn!("设置文件解析失败，使用默认设置: {}", e);
+                damaged = true;
+                serde_json::json!({})
+            }
+        },
+        Err(_) => serde_json::json!({}),
+    };
+    let stored_version = raw.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if stored_version > SETTINGS_VERSION as u64 {
+        return Err(anyhow!(tf(
+            Msg::SettingsTooNew,
+            &[("version", &stored_version)]
+        )));
+    }
+
+    let (settings, reset) = load_sections(&migrate(raw, app_data_dir));
+    if !reset.is_empty() {
+        damaged = true;
+    }
+    if damaged {
+        let backup = path.with_extension("json.bak");
+        match std::fs::copy(&path, &backup) {
+            Ok(_) => warn!("原设置文件已另存为 {}", backup.display()),
+            Err(e) => warn!("无法备份原设置文件: {}", e),
+        }
+    }
+
+    let _ = SETTINGS_PATH.set(path);
+    let _ = SETTINGS.set(RwLock::new(settings.clone()));
+    if damaged || stored_version < SETTINGS_VERSION as u64 {
+        save(&settings)?;
+    }
+    Ok(settings)
+}
+
+/// 不读写设置文件，直接使用给定设置（无界面服务器由命令行参数提供）
+pub fn init_in_memory(settings: Settings) -> Result<()> {
+    settings.validate()?;
+    let _ = SETTINGS.set(RwLock::new(settings));
+    Ok(())
+}
+
+fn save(settings: &Settings) -> Result<()> {
+    let path = SETTINGS_PATH
+        .get()
+        .ok_or_else(|| anyhow!(t(Msg::SettingsNotInitialized)))?;
+    let text = serde_json::to_string_pretty(settings)?;
+    // 先写临时文件再改名，避免写入中断损坏设置
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// 当前设置；未初始化时返回默认设置
+pub fn current() -> Settings {
+    match SETTINGS.get() {
+        Some(settings) => settings.read().map(|s| s.clone()).unwrap_or_default(),
+        None => Settings::default(),
+    }
+}
+
+/// 校验并保存新设置
+pub fn update(mut settings: Settings) -> Result<Settings> {
+    settings.version = SETTINGS_VERSION;
+    settings.validate()?;
+    save(&settings)?;
+
+    if let Some(current) = SETTINGS.get() {
+        let mut guard = current
+            .write()
+            .map_err(|_| anyhow!(t(Msg::SettingsLockPoisoned)))?;
+        *guard = settings.clone();
+    }
+    Ok(settings)
+}
@@ -0,0 +1,443 @@
+use crate::i18n::{t, tf, Locale, Msg};
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// 报表模板所在子目录（位于应用数据目录）
+const TEMPLATES_DIR: &str = "templates";
+
+/// 内置模板名称，同名文件写入模板目录供用户复制修改
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+const TEMPLATE_EXT: &str = "json";
+
+/// Excel 工作表名称的最大长度
+const MAX_SHEET_NAME: usize = 31;
+
+/// 报表模板目录
+static TEMPLATES_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// 报表中的工作表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SheetKind {
+    // 封面：钻孔信息与计算参数
+    Cover,
+    // 测点数据，列由模板决定
+    Stations,
+    // 轨迹计算
+    Trajectory,
+    // 偏差汇总
+    Summary,
+    // 平面图与剖面图，引用测点数据页的列
+    Charts,
+}
+
+impl SheetKind {
+    /// 未指定名称时使用的本地化名称
+    pub fn default_name(self, locale: Locale) -> &'static str {
+        let msg = match self {
+            SheetKind::Cover => Msg::SheetCover,
+            SheetKind::Stations => Msg::SheetStations,
+            SheetKind::Trajectory => Msg::SheetTrajectory,
+            SheetKind::Summary => Msg::SheetSummary,
+            SheetKind::Charts => Msg::SheetCharts,
+        };
+        msg.text(locale)
+    }
+}
+
+/// 测点数据页可选的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnField {
+    Index,
+    Time,
+    Depth,
+    Pitch,
+    Roll,
+    Heading,
+    DesignPitch,
+    DesignHeading,
+    // 减去磁偏角、与设计方位角同一基准的方位角
+    CorrectedHeading,
+    Lateral,
+    Vertical,
+    DesignLateral,
+    DesignVertical,
+    North,
+    East,
+    Elevation,
+    Dogleg,
+    CadPlan,
+    CadProfile,
+}
+
+/// 列的数值类别，决定默认数字格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Text,
+    Length,
+    Angle,
+    Coordinate,
+}
+
+impl ColumnField {
+    /// 默认表头
+    pub fn header(self) -> Msg {
+        match self {
+            ColumnField::Index => Msg::ColIndex,
+            ColumnField::Time => Msg::ColTime,
+            ColumnField::Depth => Msg::ColDepth,
+            ColumnField::Pitch => Msg::ColPitch,
+            ColumnField::Roll => Msg::ColRoll,
+            ColumnField::Heading => Msg::ColHeading,
+            ColumnField::DesignPitch => Msg::ColDesignPitch,
+            ColumnField::DesignHeading => Msg::ColDesignHeading,
+            ColumnField::CorrectedHeading => Msg::ColCorrectedHeading,
+            ColumnField::Lateral => Msg::ColLateral,
+            ColumnField::Vertical => Msg::ColVertical,
+            ColumnField::DesignLateral => Msg::ColDesignLateral,
+            ColumnField::DesignVertical => Msg::ColDesignVertical,
+            ColumnField::North => Msg::ColNorth,
+            ColumnField::East => Msg::ColEast,
+            ColumnField::Elevation => Msg::ColElevation,
+            ColumnField::Dogleg => Msg::ColDogleg,
+            ColumnField::CadPlan => Msg::ColCadPlan,
+            ColumnField::CadProfile => Msg::ColCadProfile,
+        }
+    }
+
+    /// 默认单位，序号、时间和 CAD 坐标没有单位
+    pub fn unit(self) -> &'static str {
+        match self.kind() {
+            ValueKind::Text => "",
+            ValueKind::Angle if self == ColumnField::Dogleg => "°/30m",
+            ValueKind::Angle => "°",
+            ValueKind::Length | ValueKind::Coordinate => "m",
+        }
+    }
+
+    pub fn kind(self) -> ValueKind {
+        match self {
+            ColumnField::Index
+            | ColumnField::Time
+            | ColumnField::CadPlan
+            | ColumnField::CadProfile => ValueKind::Text,
+            ColumnField::Depth => ValueKind::Length,
+            ColumnField::Pitch
+            | ColumnField::Roll
+            | ColumnField::Heading
+            | ColumnField::DesignPitch
+            | ColumnField::DesignHeading
+            | ColumnField::CorrectedHeading
+            | ColumnField::Dogleg => ValueKind::Angle,
+            ColumnField::Lateral
+            | ColumnField::Vertical
+            | ColumnField::DesignLateral
+            | ColumnField::DesignVertical
+            | ColumnField::North
+            | ColumnField::East
+            | ColumnField::Elevation => ValueKind::Coordinate,
+        }
+    }
+
+    /// 默认列宽
+    pub fn width(self) -> f64 {
+        match self {
+            ColumnField::Index => 6.0,
+            ColumnField::Time => 20.0,
+            ColumnField::Depth | ColumnField::Pitch | ColumnField::Roll | ColumnField::Heading => {
+                10.0
+            }
+            ColumnField::DesignLateral
+            | ColumnField::DesignVertical
+            | ColumnField::CorrectedHeading => 14.0,
+            ColumnField::CadPlan | ColumnField::CadProfile => 24.0,
+            _ => 12.0,
+        }
+    }
+}
+
+/// 模板中的一个工作表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSheet {
+    pub kind: SheetKind,
+    // 工作表名称，不填时按语言使用默认名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// 测点数据页中的一列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateColumn {
+    pub field: ColumnField,
+    // 表头，不填时按语言使用默认表头
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    // 单位，附在表头后；填空字符串表示不显示单位
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    // Excel 数字格式，如 "0.00"；坐标列默认按导出设置的小数位数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+}
+
+impl TemplateColumn {
+    pub fn new(field: ColumnField) -> Self {
+        Self {
+            field,
+            header: None,
+            unit: None,
+            num_format: None,
+            width: None,
+        }
+    }
+
+    /// 表头文本，有单位时为 `深度 (m)` 的形式
+    pub fn header_text(&self, locale: Locale) -> String {
+        let header = self
+            .header
+            .as_deref()
+            .unwrap_or_else(|| self.field.header().text(locale));
+        match self.unit.as_deref().unwrap_or_else(|| self.field.unit()) {
+            "" => header.to_string(),
+            unit => format!("{} ({})", header, unit),
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width.unwrap_or_else(|| self.field.width())
+    }
+}
+
+/// Excel 报表模板
+///
+/// 未出现的字段取内置模板的值，例如只写 `sheets` 即可改工作表名称而保留默认列。
+/// `title_rows` 写在测点数据页表头上方，可使用占位符
+/// `{name}` `{time}` `{mine}` `{face}` `{site}` `{rig}` `{declination}`。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportTemplate {
+    pub name: String,
+    pub description: String,
+    pub title_rows: Vec<String>,
+    pub sheets: Vec<TemplateSheet>,
+    pub columns: Vec<TemplateColumn>,
+}
+
+impl Default for ReportTemplate {
+    fn default() -> Self {
+        let sheet = |kind| TemplateSheet { kind, name: None };
+        Self {
+            name: DEFAULT_TEMPLATE.to_string(),
+            description: "封面、测点数据、轨迹计算、偏差汇总及轨迹图".to_string(),
+            title_rows: Vec::new(),
+            sheets: vec![
+                sheet(SheetKind::Cover),
+                sheet(SheetKind::Stations),
+                sheet(SheetKind::Trajectory),
+                sheet(SheetKind::Summary),
+                sheet(SheetKind::Charts),
+            ],
+            columns: [
+                ColumnField::Index,
+                ColumnField::Time,
+                ColumnField::Depth,
+                ColumnField::Pitch,
+                ColumnField::Heading,
+                ColumnField::DesignPitch,
+                ColumnField::DesignHeading,
+                ColumnField::Lateral,
+                ColumnField::Vertical,
+                ColumnField::DesignLateral,
+                ColumnField::DesignVertical,
+                ColumnField::CadPlan,
+                ColumnField::CadProfile,
+            ]
+            .into_iter()
+            .map(TemplateColumn::new)
+            .collect(),
+        }
+    }
+}
+
+impl ReportTemplate {
+    /// 从 JSON 文件加载并校验模板，未填写名称时取文件名
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| tf(Msg::TemplateReadFailed, &[("path", &path.display())]))?;
+        let mut template: ReportTemplate = serde_json::from_str(&text)
+            .with_context(|| tf(Msg::TemplateParseFailed, &[("path", &path.display())]))?;
+        if template.name.trim().is_empty() || template.name == DEFAULT_TEMPLATE {
+            if let Some(stem) = path.file_stem() {
+                template.name = stem.to_string_lossy().to_string();
+            }
+        }
+        template
+            .validate()
+            .with_context(|| tf(Msg::TemplateInvalid, &[("path", &path.display())]))?;
+        Ok(template)
+    }
+
+    /// 校验工作表与列的定义
+    pub fn validate(&self) -> Result<()> {
+        if self.sheets.is_empty() {
+            return Err(anyhow!(t(Msg::TemplateNoSheets)));
+        }
+        let mut kinds = HashSet::new();
+        let mut names = HashSet::new();
+        for sheet in &self.sheets {
+            if !kinds.insert(sheet.kind) {
+                return Err(anyhow!(tf(
+                    Msg::TemplateSheetKindDuplicate,
+                    &[("kind", &format!("{:?}", sheet.kind))],
+                )));
+            }
+            let name = match &sheet.name {
+                Some(name) => name,
+                None => continue,
+            };
+            if name.trim().is_empty() || name.chars().count() > MAX_SHEET_NAME {
+                return Err(anyhow!(tf(
+                    Msg::TemplateSheetNameLength,
+                    &[("max", &MAX_SHEET_NAME)],
+                )));
+            }
+            if name.contains(['[', ']', ':', '*', '?', '/', '\\']) {
+                return Err(anyhow!(tf(Msg::TemplateSheetNameChars, &[("name", name)])));
+            }
+            if !names.insert(name.to_lowercase()) {
+                return Err(anyhow!(tf(Msg::TemplateSheetNameClash, &[("name", name)])));
+            }
+        }
+
+        if kinds.contains(&SheetKind::Stations) && self.columns.is_empty() {
+            return Err(anyhow!(t(Msg::TemplateNoColumns)));
+        }
+        if kinds.contains(&SheetKind::Charts) {
+            if !kinds.contains(&SheetKind::Stations) {
+                return Err(anyhow!(t(Msg::TemplateChartsNeedStations)));
+            }
+            if self.column_of(ColumnField::Depth).is_none() {
+                return Err(anyhow!(t(Msg::TemplateChartsNeedDepth)));
+            }
+        }
+        for column in &self.columns {
+            if let Some(width) = column.width {
+                if !width.is_finite() || !(0.0..=255.0).contains(&width) {
+                    return Err(anyhow!(t(Msg::TemplateColumnWidth)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 工作表名称
+    pub fn sheet_name(&self, kind: SheetKind, locale: Locale) -> String {
+        self.sheets
+            .iter()
+            .find(|s| s.kind == kind)
+            .and_then(|s| s.name.clone())
+            .unwrap_or_else(|| kind.default_name(locale).to_string())
+    }
+
+    /// 字段在测点数据页中的列号
+    pub fn column_of(&self, field: ColumnField) -> Option<u16> {
+        self.columns
+            .iter()
+            .position(|c| c.field == field)
+            .map(|col| col as u16)
+    }
+}
+
+/// 模板列表项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub description: String,
+    // 模板文件路径，内置模板为空
+    pub path: Option<String>,
+}
+
+/// 初始化模板目录，并写出内置模板作为自定义模板的样例
+pub fn init_templates(app_data_dir: &Path) -> Result<PathBuf> {
+    let dir = app_data_dir.join(TEMPLATES_DIR);
+    std::fs::create_dir_all(&dir)?;
+
+    let sample = dir.join(format!("{}.{}", DEFAULT_TEMPLATE, TEMPLATE_EXT));
+    if !sample.exists() {
+        std::fs::write(
+            &sample,
+            serde_json::to_string_pretty(&ReportTemplate::default())?,
+        )?;
+    }
+    let _ = TEMPLATES_PATH.set(dir.clone());
+    Ok(dir)
+}
+
+/// 模板目录
+pub fn templates_dir() -> Result<PathBuf> {
+    TEMPLATES_PATH
+        .get()
+        .cloned()
+        .ok_or_else(|| anyhow!(t(Msg::TemplatesNotInitialized)))
+}
+
+/// 列出内置模板及模板目录中的模板，无法解析的文件跳过并记录警告
+pub fn list_templates() -> Result<Vec<TemplateInfo>> {
+    let builtin = ReportTemplate::default();
+    let mut templates = vec![TemplateInfo {
+        name: builtin.name,
+        description: builtin.description,
+        path: None,
+    }];
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(templates_dir()?)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == TEMPLATE_EXT))
+        .collect();
+    paths.sort();
+    for path in paths {
+        // 模板目录中的 default.json 仅为样例，内置模板始终使用代码中的定义
+        if path
+            .file_stem()
+            .is_some_and(|stem| stem == DEFAULT_TEMPLATE)
+        {
+            continue;
+        }
+        match ReportTemplate::load(&path) {
+            Ok(template) => templates.push(TemplateInfo {
+                name: template.name,
+                description: template.description,
+                path: Some(path.to_string_lossy().to_string()),
+            }),
+            Err(e) => warn!("跳过无效的报表模板 {}: {:#}", path.display(), e),
+        }
+    }
+    Ok(templates)
+}
+
+/// 按名称或文件路径解析模板；为空或为 `default` 时返回内置模板
+pub fn resolve(template: Option<&str>) -> Result<ReportTemplate> {
+    let template = match template.map(str::trim) {
+        None | Some("") | Some(DEFAULT_TEMPLATE) => return Ok(ReportTemplate::default()),
+        Some(template) => template,
+    };
+
+    let path = Path::new(template);
+    if path.extension().is_some_and(|ext| ext == TEMPLATE_EXT) || path.components().count() > 1 {
+        return ReportTemplate::load(path);
+    }
+
+    let path = templates_dir()?.join(format!("{}.{}", template, TEMPLATE_EXT));
+    if !path.exists() {
+        return Err(anyhow!(tf(Msg::TemplateNotFound, &[("name", &template)])));
+    }
+    ReportTemplate::load(&path)
+}
@@ -0,0 +1,750 @@
+//! WITSML 1.4.1 轨迹（trajectory）对象的读写
+//!
+//! 一个钻孔对应一个 trajectory，井（well）与井眼（wellbore）都取钻孔名称，测点对应 trajectoryStation：
+//! - 井斜角 `incl` 自铅垂向下量起，与俯仰角的换算为 incl = 90° + pitch；
+//! - 方位角按磁北写出（`aziRef` 为 magnetic north）。读入真北或网格北的方位角时，
+//!   按 `magDeclUsed` 与 `gridCorUsed`（网格北相对真北的收敛角）换算回磁方位角；未给出 `aziRef` 时按磁北处理；
+//! - 翻滚角写在重力工具面角 `gtf` 中。
+//!
+//! 矿井、工作面、钻场、钻机、测点时间和设计俯仰角、设计方位角在 WITSML 中没有对应元素，
+//! 写在 `customData` 中（命名空间 `urn:drilltrack:witsml`），本程序之间往返不丢失，其他软件会忽略。
+//!
+//! 读入时只校验本程序用到的部分：必填元素与属性、这些元素最多出现一次、枚举、计量单位和取值范围。
+//! 这不是 XSD 校验：不检查元素顺序，不认识的元素（如 `location`、`matrixCov`）直接忽略，
+//! 用不到的元素也不检查出现次数，因此通过校验的文档不一定符合 1.4.1 XSD。
+
+use crate::calc;
+use crate::i18n::{t, tf, Msg};
+use crate::models::data::DataList;
+use crate::models::repo::Repo;
+use crate::services::archive::ArchiveHole;
+use crate::services::export::ExportOptions;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashSet;
+use std::fmt::{self, Write as _};
+
+/// WITSML 1.x 数据对象的命名空间
+pub const WITSML_NAMESPACE: &str = "http://www.witsml.org/schemas/1series";
+/// 写出的数据模型版本
+pub const WITSML_VERSION: &str = "1.4.1.1";
+/// customData 中本程序扩展字段的命名空间
+const CUSTOM_NAMESPACE: &str = "urn:drilltrack:witsml";
+
+/// `aziRef` 的取值
+const AZI_REFS: [&str; 3] = ["magnetic north", "grid north", "true north"];
+/// 名称类元素与 uid 的最大长度
+const MAX_NAME_LEN: usize = 64;
+
+/// 解析后的 XML 元素，名称为去掉前缀的本地名
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// 子元素的文本，去掉首尾空白，为空时视为没有
+    fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|c| c.text.trim())
+            .filter(|text| !text.is_empty())
+    }
+}
+
+/// 解析 XML 文档，返回根元素
+fn parse_document(xml: &str) -> Result<Element> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+
+    let open = |start: &quick_xml::events::BytesStart| -> Result<Element> {
+        let mut element = Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            ..Element::default()
+        };
+        for attribute in start.attributes() {
+            let attribute = attribute?;
+            element.attributes.push((
+                String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                attribute.unescape_value()?.into_owned(),
+            ));
+        }
+        Ok(element)
+    };
+    let mut close = |element: Element, stack: &mut Vec<Element>| match stack.last_mut() {
+        Some(parent) => parent.children.push(element),
+        None => root = Some(element),
+    };
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(open(&start)?),
+            Event::Empty(start) => {
+                let element = open(&start)?;
+                close(element, &mut stack);
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| anyhow!(t(Msg::WitsmlUnbalanced)))?;
+                close(element, &mut stack);
+            }
+            Event::Text(text) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    root.ok_or_else(|| anyhow!(t(Msg::WitsmlNoRoot)))
+}
+
+/// 长度换算为米
+fn length_in_m(value: f64, uom: &str) -> Option<f64> {
+    match uom {
+        "m" => Some(value),
+        "cm" => Some(value / 100.0),
+        "ft" => Some(value * 0.3048),
+        "ft[US]" => Some(value * 1200.0 / 3937.0),
+        _ => None,
+    }
+}
+
+/// 角度换算为度
+fn angle_in_deg(value: f64, uom: &str) -> Option<f64> {
+    match uom {
+        "dega" => Some(value),
+        "rad" => Some(value.to_degrees()),
+        "gon" => Some(value * 0.9),
+        _ => None,
+    }
+}
+
+/// 时间戳换算为本程序的时间格式 `YYYY-MM-DD HH:MM:SS`，带时区时取该时区的当地时间
+fn parse_timestamp(text: &str) -> Option<String> {
+    let naive = DateTime::parse_from_rfc3339(text)
+        .map(|t| t.naive_local())
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+        .ok()?;
+    Some(naive.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// 本程序的时间换算为带本地时区的 WITSML 时间戳，无法解析时返回 None
+fn format_timestamp(text: &str) -> Option<String> {
+    let naive = NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M:%S%.f").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%S%:z").to_string())
+}
+
+/// 校验时收集问题，每条带上所在的 trajectory 与测点
+struct Checker {
+    problems: Vec<String>,
+    context: String,
+}
+
+impl Checker {
+    fn problem(&mut self, msg: Msg, args: &[(&str, &dyn fmt::Display)]) {
+        self.problems.push(format!("{}{}", self.context, tf(msg, args)));
+    }
+
+    fn required<'a>(&mut self, element: &'a Element, name: &str) -> Option<&'a str> {
+        let text = element.child_text(name);
+        match text {
+            None => self.problem(Msg::WitsmlMissing, &[("name", &name)]),
+            Some(text) if text.chars().count() > MAX_NAME_LEN => self.problem(
+                Msg::WitsmlTooLong,
+                &[("name", &name), ("max", &MAX_NAME_LEN)],
+            ),
+            _ => {}
+        }
+        text
+    }
+
+    fn uid(&mut self, element: &Element, name: &str, required: bool) -> Option<String> {
+        match element.attr(name).map(str::trim) {
+            None | Some("") => {
+                if required {
+                    self.problem(Msg::WitsmlMissingAttr, &[("name", &name)]);
+                }
+                None
+            }
+            Some(uid) if uid.chars().count() > MAX_NAME_LEN => {
+                self.problem(
+                    Msg::WitsmlAttrTooLong,
+                    &[("name", &name), ("max", &MAX_NAME_LEN)],
+                );
+                None
+            }
+            Some(uid) => Some(uid.to_string()),
+        }
+    }
+
+    /// 带计量单位的数值，`convert` 把数值换算为本程序的单位
+    fn measure(
+        &mut self,
+        element: &Element,
+        name: &str,
+        convert: fn(f64, &str) -> Option<f64>,
+    ) -> Option<f64> {
+        let child = element.child(name)?;
+        let value = match child.text.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => value,
+            _ => {
+                self.problem(
+                    Msg::WitsmlNotNumber,
+                    &[("name", &name), ("value", &child.text.trim())],
+                );
+                return None;
+            }
+        };
+        let Some(uom) = child.attr("uom") else {
+            self.problem(Msg::WitsmlMissingUom, &[("name", &name)]);
+            return None;
+        };
+        let converted = convert(value, uom);
+        if converted.is_none() {
+            self.problem(Msg::WitsmlUnsupportedUom, &[("name", &name), ("uom", &uom)]);
+        }
+        converted
+    }
+
+    /// 最多出现一次的子元素，重复时只读第一个
+    fn singles(&mut self, element: &Element, names: &[&str]) {
+        for name in names {
+            if element.children(name).nth(1).is_some() {
+                self.problem(Msg::WitsmlRepeated, &[("name", name)]);
+            }
+        }
+    }
+
+    fn timestamp(&mut self, element: &Element, name: &str) -> Option<String> {
+        let text = element.child_text(name)?;
+        let parsed = parse_timestamp(text);
+        if parsed.is_none() {
+            self.problem(Msg::WitsmlBadTimestamp, &[("name", &name), ("value", &text)]);
+        }
+        parsed
+    }
+}
+
+/// 本程序读取的 trajectory 子元素，XSD 中都最多出现一次
+const TRAJECTORY_SINGLES: [&str; 12] = [
+    "nameWell",
+    "nameWellbore",
+    "name",
+    "dTimTrajStart",
+    "dTimTrajEnd",
+    "mdMn",
+    "mdMx",
+    "magDeclUsed",
+    "gridCorUsed",
+    "aziRef",
+    "customData",
+    "commonData",
+];
+/// 本程序读取的 trajectoryStation 子元素，XSD 中都最多出现一次
+const STATION_SINGLES: [&str; 7] = [
+    "dTimStn",
+    "typeTrajStation",
+    "md",
+    "incl",
+    "azi",
+    "gtf",
+    "customData",
+];
+
+/// customData 中本程序扩展字段的文本
+fn custom_text<'a>(element: &'a Element, group: &str, name: &str) -> Option<&'a str> {
+    element.child("customData")?.child(group)?.child_text(name)
+}
+
+fn custom_number(element: &Element, group: &str, name: &str) -> Option<f64> {
+    custom_text(element, group, name)?.parse().ok()
+}
+
+/// 读取一个 trajectory 对象
+fn read_trajectory(trajectory: &Element, checker: &mut Checker) -> ArchiveHole {
+    checker.singles(trajectory, &TRAJECTORY_SINGLES);
+    checker.uid(trajectory, "uidWell", true);
+    checker.uid(trajectory, "uidWellbore", true);
+    let uid = checker.uid(trajectory, "uid", true);
+    checker.required(trajectory, "nameWell");
+    let name_wellbore = checker
+        .required(trajectory, "nameWellbore")
+        .unwrap_or_default();
+    checker.required(trajectory, "name");
+
+    let azi_ref = trajectory.child_text("aziRef");
+    if let Some(azi_ref) = azi_ref {
+        if !AZI_REFS.contains(&azi_ref) {
+            checker.problem(Msg::WitsmlBadValue, &[("name", &"aziRef"), ("value", &azi_ref)]);
+        }
+    }
+    let declination = checker
+        .measure(trajectory, "magDeclUsed", angle_in_deg)
+        .unwrap_or(0.0);
+    let grid_correction = checker
+        .measure(trajectory, "gridCorUsed", angle_in_deg)
+        .unwrap_or(0.0);
+    // 换算为磁方位角需要加上的角度
+    let to_magnetic = match azi_ref {
+        Some("true north") => -declination,
+        Some("grid north") => grid_correction - declination,
+        _ => 0.0,
+    };
+    let md_max = checker.measure(trajectory, "mdMx", length_in_m);
+    checker.measure(trajectory, "mdMn", length_in_m);
+    let start = checker.timestamp(trajectory, "dTimTrajStart");
+    checker.timestamp(trajectory, "dTimTrajEnd");
+
+    let trajectory_context = checker.context.clone();
+    let mut station_uids = HashSet::new();
+    let mut stations = Vec::new();
+    for (index, station) in trajectory.children("trajectoryStation").enumerate() {
+        checker.context = format!(
+            "{}{}",
+            trajectory_context,
+            tf(Msg::WitsmlStation, &[("no", &(index + 1))])
+        );
+        checker.singles(station, &STATION_SINGLES);
+        if let Some(uid) = checker.uid(station, "uid", false) {
+            if !station_uids.insert(uid.clone()) {
+                checker.problem(Msg::WitsmlDuplicateUid, &[("uid", &uid)]);
+            }
+        }
+        if station.child_text("typeTrajStation").is_none() {
+            checker.problem(Msg::WitsmlMissing, &[("name", &"typeTrajStation")]);
+        }
+        let depth = checker.measure(station, "md", length_in_m);
+        match depth {
+            None if station.child("md").is_none() => {
+                checker.problem(Msg::WitsmlMissing, &[("name", &"md")])
+            }
+            Some(md) if md < 0.0 => {
+                checker.problem(Msg::WitsmlNegative, &[("name", &"md"), ("value", &md)])
+            }
+            _ => {}
+        }
+        let incl = checker.measure(station, "incl", angle_in_deg);
+        if let Some(incl) = incl.filter(|i| !(0.0..=180.0).contains(i)) {
+            checker.problem(
+                Msg::WitsmlOutOfRange,
+                &[("name", &"incl"), ("min", &0), ("max", &180), ("value", &incl)],
+            );
+        }
+        let azi = checker.measure(station, "azi", angle_in_deg);
+        if let Some(azi) = azi.filter(|a| !(0.0..=360.0).contains(a)) {
+            checker.problem(
+                Msg::WitsmlOutOfRange,
+                &[("name", &"azi"), ("min", &0), ("max", &360), ("value", &azi)],
+            );
+        }
+        let roll = checker.measure(station, "gtf", angle_in_deg);
+        // 本程序写出的原始时间文本优先
+        let stamp = checker.timestamp(station, "dTimStn");
+        let time = custom_text(station, "station", "time")
+            .map(str::to_string)
+            .or(stamp);
+
+        stations.push(DataList {
+            id: None,
+            uuid: None,
+            time,
+            depth: depth.unwrap_or_default(),
+            pitch: incl.map(|incl| incl - 90.0),
+            roll,
+            heading: azi.map(|azi| (azi + to_magnetic).rem_euclid(360.0)),
+            repo_id: None,
+            design_pitch: custom_number(station, "station", "designPitch"),
+            design_heading: custom_number(station, "station", "designHeading"),
+        });
+    }
+    checker.context = trajectory_context;
+    if stations.is_empty() {
+        checker.problem(Msg::WitsmlNoStations, &[]);
+    }
+    stations.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+
+    let custom = |name: &str| {
+        custom_text(trajectory, "hole", name)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let md_max = md_max.or_else(|| stations.last().map(|s| s.depth));
+    let repo = Repo {
+        id: None,
+        // 其他软件写出的 uid 不一定是 UUID，此时不作为 UUID 入库，改按身份键匹配
+        uuid: uid.filter(|uid| uuid::Uuid::parse_str(uid).is_ok()),
+        name: name_wellbore.to_string(),
+        mn_time: custom_text(trajectory, "hole", "surveyTime")
+            .map(str::to_string)
+            .or(start)
+            .unwrap_or_else(|| Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+        len: md_max.unwrap_or_default().round() as i32,
+        mine: custom("mine"),
+        work: custom("workingFace"),
+        factory: custom("drillingSite"),
+        drilling: custom("rig"),
+        notes: trajectory
+            .child("commonData")
+            .and_then(|c| c.child_text("comments"))
+            .map(str::to_string),
+        mine_id: None,
+        working_face_id: None,
+        drilling_site_id: None,
+        rig_id: None,
+    };
+    ArchiveHole { repo, stations }
+}
+
+/// 读取 WITSML 1.4.1 trajectorys 文档，每个 trajectory 对应一个钻孔
+///
+/// 文档未通过校验时返回全部问题，不返回部分结果。导入数据库用 `archive::import_holes`，
+/// 与归档导入一样识别重复并处理冲突：trajectory 的 uid 是合法 UUID 时按 UUID 匹配，
+/// 否则按名称、矿井、工作面和施工时间组成的身份键匹配。
+pub fn read_trajectories(xml: &str) -> Result<Vec<ArchiveHole>> {
+    let root = parse_document(xml)
+        .map_err(|e| anyhow!(tf(Msg::WitsmlParseFailed, &[("error", &e)])))?;
+    let mut checker = Checker {
+        problems: Vec::new(),
+        context: String::new(),
+    };
+    if root.name != "trajectorys" {
+        return Err(anyhow!(tf(Msg::WitsmlRootName, &[("name", &root.name)])));
+    }
+    let namespaced = root.attributes.iter().any(|(key, value)| {
+        (key == "xmlns" || key.starts_with("xmlns:")) && value == WITSML_NAMESPACE
+    });
+    if !namespaced {
+        checker.problem(Msg::WitsmlNamespace, &[("namespace", &WITSML_NAMESPACE)]);
+    }
+    match root.attr("version") {
+        Some(version) if version.starts_with("1.4.1") => {}
+        Some(version) => checker.problem(Msg::WitsmlVersion, &[("version", &version)]),
+        None => checker.problem(Msg::WitsmlMissingAttr, &[("name", &"version")]),
+    }
+
+    let mut holes = Vec::new();
+    for (index, trajectory) in root.children("trajectory").enumerate() {
+        checker.context = tf(Msg::WitsmlTrajectory, &[("no", &(index + 1))]);
+        holes.push(read_trajectory(trajectory, &mut checker));
+    }
+    checker.context.clear();
+    if holes.is_empty() {
+        checker.problem(Msg::WitsmlNoTrajectories, &[]);
+    }
+
+    if checker.problems.is_empty() {
+        Ok(holes)
+    } else {
+        Err(anyhow!(tf(
+            Msg::WitsmlInvalid,
+            &[("problems", &checker.problems.join("; "))],
+        )))
+    }
+}
+
+/// 写出带计量单位的数值元素
+fn write_measure(out: &mut String, indent: &str, name: &str, uom: &str, value: String) {
+    let _ = writeln!(
+        out,
+        "{}<{} uom=\"{}\">{}</{}>",
+        indent, name, uom, value, name
+    );
+}
+
+/// 写出文本元素
+fn write_text(out: &mut String, indent: &str, name: &str, text: &str) {
+    let _ = writeln!(out, "{}<{}>{}</{}>", indent, name, escape(text), name);
+}
+
+/// 把一个钻孔写成 trajectory 对象
+fn write_trajectory(out: &mut String, repo: &Repo, stations: &[DataList], options: &ExportOptions) {
+    let dp = options.decimal_places;
+    let number = |value: f64| format!("{:.*}", dp, value);
+    let uid = repo
+        .uuid
+        .clone()
+        .unwrap_or_else(|| repo.id.unwrap_or_default().to_string());
+    let name: String = repo.name.trim().chars().take(MAX_NAME_LEN).collect();
+    let _ = writeln!(
+        out,
+        "  <trajectory uidWell=\"{uid}\" uidWellbore=\"{uid}\" uid=\"{uid}\">",
+        uid = escape(&uid)
+    );
+    write_text(out, "    ", "nameWell", &name);
+    write_text(out, "    ", "nameWellbore", &name);
+    write_text(out, "    ", "name", &name);
+    if let Some(start) = format_timestamp(&repo.mn_time) {
+        write_text(out, "    ", "dTimTrajStart", &start);
+    }
+    if let (Some(first), Some(last)) = (stations.first(), stations.last()) {
+        write_measure(out, "    ", "mdMn", "m", first.depth.to_string());
+        write_measure(out, "    ", "mdMx", "m", last.depth.to_string());
+    }
+    write_measure(
+        out,
+        "    ",
+        "magDeclUsed",
+        "dega",
+        options.magnetic_declination.to_string(),
+    );
+    write_text(out, "    ", "aziRef", "magnetic north");
+
+    let points = calc::trajectory(stations, options.magnetic_declination);
+    for (index, (station, point)) in stations.iter().zip(&points).enumerate() {
+        let _ = writeln!(out, "    <trajectoryStation uid=\"{}\">", index + 1);
+        let station_time = station.time.as_deref().unwrap_or_default();
+        if let Some(time) = format_timestamp(station_time) {
+            write_text(out, "      ", "dTimStn", &time);
+        }
+        write_text(out, "      ", "typeTrajStation", "magnetic MWD");
+        write_measure(out, "      ", "md", "m", station.depth.to_string());
+        write_measure(out, "      ", "tvd", "m", number(-point.elevation));
+        if let Some(pitch) = station.pitch {
+            write_measure(out, "      ", "incl", "dega", (90.0 + pitch).to_string());
+        }
+        if let Some(heading) = station.heading {
+            write_measure(out, "      ", "azi", "dega", heading.to_string());
+        }
+        if let Some(roll) = station.roll {
+            write_measure(out, "      ", "gtf", "dega", roll.to_string());
+        }
+        write_measure(out, "      ", "dispNs", "m", number(point.north));
+        write_measure(out, "      ", "dispEw", "m", number(point.east));
+        write_measure(out, "      ", "dls", "dega/30m", number(point.dogleg));
+
+        let custom = [
+            (
+                "time",
+                station.time.clone().filter(|t| !t.trim().is_empty()),
+            ),
+            ("designPitch", station.design_pitch.map(|v| v.to_string())),
+            (
+                "designHeading",
+                station.design_heading.map(|v| v.to_string()),
+            ),
+        ];
+        if custom.iter().any(|(_, value)| value.is_some()) {
+            let _ = writeln!(out, "      <customData>");
+            let _ = writeln!(
+                out,
+                "        <dt:station xmlns:dt=\"{}\">",
+                CUSTOM_NAMESPACE
+            );
+            for (name, value) in custom {
+                if let Some(value) = value {
+                    write_text(out, "          ", &format!("dt:{}", name), &value);
+                }
+            }
+            let _ = writeln!(out, "        </dt:station>");
+            let _ = writeln!(out, "      </customData>");
+        }
+        let _ = writeln!(out, "    </trajectoryStation>");
+    }
+
+    let _ = writeln!(out, "    <commonData>");
+    write_text(out, "      ", "sourceName", "DrillTrack");
+    if let Some(notes) = repo.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        write_text(out, "      ", "comments", notes);
+    }
+    let _ = writeln!(out, "    </commonData>");
+    let _ = writeln!(out, "    <customData>");
+    let _ = writeln!(out, "      <dt:hole xmlns:dt=\"{}\">", CUSTOM_NAMESPACE);
+    for (name, value) in [
+        ("mine", &repo.mine),
+        ("workingFace", &repo.work),
+        ("drillingSite", &repo.factory),
+        ("rig", &repo.drilling),
+        ("surveyTime", &repo.mn_time),
+    ] {
+        write_text(out, "        ", &format!("dt:{}", name), value);
+    }
+    let _ = writeln!(out, "      </dt:hole>");
+    let _ = writeln!(out, "    </customData>");
+    let _ = writeln!(out, "  </trajectory>");
+}
+
+/// 多个钻孔的 WITSML 1.4.1 trajectorys 文档
+pub fn to_witsml(holes: &[(&Repo, &[DataList])], options: &ExportOptions) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<trajectorys xmlns=\"{}\" version=\"{}\">",
+        WITSML_NAMESPACE, WITSML_VERSION
+    );
+    for (repo, stations) in holes {
+        write_trajectory(&mut out, repo, stations, options);
+    }
+    out.push_str("</trajectorys>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn repo() -> Repo {
+        Repo {
+            id: Some(7),
+            uuid: Some("0b7c6f1e-5d2a-4c3b-9f8e-7a6b5c4d3e2f".to_string()),
+            name: "ZK-1 <西>".to_string(),
+            mn_time: "2024-05-06 08:30:00".to_string(),
+            len: 30,
+            mine: "一矿".to_string(),
+            work: "1201".to_string(),
+            factory: "3#".to_string(),
+            drilling: "ZDY-6000".to_string(),
+            notes: Some("备注 & notes".to_string()),
+            mine_id: None,
+            working_face_id: None,
+            drilling_site_id: None,
+            rig_id: None,
+        }
+    }
+
+    fn station(depth: f64, pitch: f64, roll: Option<f64>, heading: f64) -> DataList {
+        DataList {
+            id: None,
+            uuid: None,
+            time: Some(format!("2024-05-06 08:{:02}:00", depth as i32)),
+            depth,
+            pitch: Some(pitch),
+            roll,
+            heading: Some(heading),
+            repo_id: None,
+            design_pitch: Some(-5.0),
+            design_heading: Some(88.0),
+        }
+    }
+
+    fn stations() -> Vec<DataList> {
+        vec![
+            station(10.0, -3.5, Some(12.0), 87.25),
+            station(20.0, 4.0, None, 359.5),
+            station(30.0, -89.0, Some(270.0), 0.5),
+        ]
+    }
+
+    fn export(stations: &[DataList]) -> String {
+        let options = ExportOptions {
+            magnetic_declination: 2.5,
+            ..Default::default()
+        };
+        let repo = repo();
+        to_witsml(&[(&repo, stations)], &options)
+    }
+
+    #[test]
+    fn round_trip_keeps_hole_and_stations() {
+        let stations = stations();
+        let holes = read_trajectories(&export(&stations)).unwrap();
+
+        assert_eq!(holes.len(), 1);
+        let hole = &holes[0];
+        let original = repo();
+        assert_eq!(hole.repo.uuid, original.uuid);
+        assert_eq!(hole.repo.name, original.name);
+        assert_eq!(hole.repo.mn_time, original.mn_time);
+        assert_eq!(hole.repo.len, 30);
+        assert_eq!(hole.repo.mine, original.mine);
+        assert_eq!(hole.repo.work, original.work);
+        assert_eq!(hole.repo.factory, original.factory);
+        assert_eq!(hole.repo.drilling, original.drilling);
+        assert_eq!(hole.repo.notes, original.notes);
+        assert_eq!(hole.stations.len(), stations.len());
+        for (read, written) in hole.stations.iter().zip(&stations) {
+            assert!((read.depth - written.depth).abs() < EPS);
+            // incl = 90° + pitch 往返后还原为俯仰角
+            assert!((read.pitch.unwrap() - written.pitch.unwrap()).abs() < EPS);
+            assert!((read.heading.unwrap() - written.heading.unwrap()).abs() < EPS);
+            assert_eq!(read.roll, written.roll);
+            assert_eq!(read.time, written.time);
+            assert_eq!(read.design_pitch, written.design_pitch);
+            assert_eq!(read.design_heading, written.design_heading);
+        }
+    }
+
+    #[test]
+    fn incl_in_radians_converts_to_pitch() {
+        let stations = [station(10.0, -30.0, None, 90.0)];
+        let xml = export(&stations).replace(
+            r#"<incl uom="dega">60</incl>"#,
+            &format!(r#"<incl uom="rad">{}</incl>"#, 60f64.to_radians()),
+        );
+        assert!(xml.contains(r#"<incl uom="rad">"#));
+
+        let holes = read_trajectories(&xml).unwrap();
+        assert!((holes[0].stations[0].pitch.unwrap() + 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn true_and_grid_north_convert_to_magnetic() {
+        let stations = [station(10.0, 0.0, None, 1.0)];
+        let xml = export(&stations);
+
+        // 真北方位角 = 磁方位角 + 磁偏角
+        let true_north = xml.replace("magnetic north", "true north");
+        let holes = read_trajectories(&true_north).unwrap();
+        assert!((holes[0].stations[0].heading.unwrap() - 358.5).abs() < EPS);
+
+        // 网格北方位角 = 真北方位角 - 收敛角
+        let grid_north = xml.replace("magnetic north", "grid north").replace(
+            "<aziRef>",
+            "<gridCorUsed uom=\"dega\">1.5</gridCorUsed>\n    <aziRef>",
+        );
+        let holes = read_trajectories(&grid_north).unwrap();
+        assert!((holes[0].stations[0].heading.unwrap() - 0.0).abs() < EPS);
+    }
+
+    #[test]
+    fn repeated_single_element_is_rejected() {
+        let xml = export(&stations()).replace(
+            "<aziRef>magnetic north</aziRef>",
+            "<aziRef>magnetic north</aziRef>\n    <aziRef>true north</aziRef>",
+        );
+        let error = read_trajectories(&xml).unwrap_err().to_string();
+        assert!(error.contains("aziRef"), "{}", error);
+    }
+
+    #[test]
+    fn non_uuid_uid_is_not_kept_as_uuid() {
+        let xml = export(&stations()).replace("0b7c6f1e-5d2a-4c3b-9f8e-7a6b5c4d3e2f", "ZK-1");
+        let holes = read_trajectories(&xml).unwrap();
+        assert_eq!(holes[0].repo.uuid, None);
+        assert_eq!(holes[0].repo.identity_key(), repo().identity_key());
+    }
+}
@@ -0,0 +1,276 @@
+use crate::i18n::{t, tf, Msg};
+use crate::services::backup;
+use crate::services::db::{self, DbError};
+use chrono::Local;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// 工作区配置文件名
+const WORKSPACE_CONFIG_FILE: &str = "workspace.json";
+
+/// 项目数据库所在子目录
+const PROJECTS_DIR: &str = "projects";
+
+/// 默认项目名称（沿用旧版的 database.db 文件名）
+const DEFAULT_PROJECT: &str = "database";
+
+const PROJECT_EXT: &str = "db";
+
+/// 最近打开项目最多保留的条数
+const MAX_RECENT: usize = 10;
+
+/// 应用数据目录
+static APP_DATA_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// 工作区配置
+static WORKSPACE_CONFIG: OnceCell<Mutex<WorkspaceConfig>> = OnceCell::new();
+
+/// 最近打开的项目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub name: String,
+    pub path: String,
+    pub last_opened: String,
+}
+
+/// 工作区配置：当前数据库路径及最近打开记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    pub current_path: Option<String>,
+    #[serde(default)]
+    pub recent: Vec<RecentProject>,
+}
+
+/// 项目信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub path: String,
+    // 是否为当前打开的项目
+    pub current: bool,
+    // 文件是否仍然存在（最近记录中的外部文件可能已被移动）
+    pub exists: bool,
+    pub last_opened: Option<String>,
+}
+
+fn other(message: String) -> DbError {
+    DbError::Other(anyhow::anyhow!(message))
+}
+
+/// 初始化工作区，返回启动时应打开的数据库路径
+///
+/// 首次运行时若工作目录下存在旧版 database.db，会复制到应用数据目录作为默认项目。
+pub fn init_workspace(app_data_dir: &Path) -> Result<String, DbError> {
+    let projects_dir = app_data_dir.join(PROJECTS_DIR);
+    std::fs::create_dir_all(&projects_dir).map_err(|e| DbError::Other(e.into()))?;
+
+    let config: WorkspaceConfig = std::fs::read_to_string(app_data_dir.join(WORKSPACE_CONFIG_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    let default_path = project_path_in(&projects_dir, DEFAULT_PROJECT);
+    if !default_path.exists() {
+        if let Ok(legacy) = std::env::current_dir().map(|dir| dir.join("database.db")) {
+            if legacy.is_file() {
+                std::fs::copy(&legacy, &default_path).map_err(|e| DbError::Other(e.into()))?;
+                info!("已迁移旧版数据库: {} -> {}", legacy.display(), default_path.display());
+            }
+        }
+    }
+
+    // 上次打开的项目文件不存在时回退到默认项目
+    let path = config
+        .current_path
+        .clone()
+        .filter(|p| Path::new(p).is_file())
+        .unwrap_or_else(|| default_path.to_string_lossy().to_string());
+
+    let _ = APP_DATA_DIR.set(app_data_dir.to_path_buf());
+    let _ = WORKSPACE_CONFIG.set(Mutex::new(config));
+    Ok(path)
+}
+
+fn projects_dir() -> Result<PathBuf, DbError> {
+    APP_DATA_DIR
+        .get()
+        .map(|dir| dir.join(PROJECTS_DIR))
+        .ok_or_else(|| other(t(Msg::WorkspaceNotInitialized).to_string()))
+}
+
+fn project_path_in(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.{}", name, PROJECT_EXT))
+}
+
+/// 默认项目数据库路径；工作区未初始化时使用工作目录下的 database.db
+pub fn default_db_path() -> Result<String, DbError> {
+    let path = match projects_dir() {
+        Ok(dir) => project_path_in(&dir, DEFAULT_PROJECT),
+        Err(_) => std::env::current_dir()
+            .map_err(|e| other(tf(Msg::CurrentDirFailed, &[("error", &e)])))?
+            .join("database.db"),
+    };
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 校验项目名称，只允许作为文件名使用的字符
+fn validate_project_name(name: &str) -> Result<String, DbError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DbError::Invalid(t(Msg::ProjectNameEmpty).to_string()));
+    }
+    if name.starts_with('.')
+        || name
+            .chars()
+            .any(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control())
+    {
+        return Err(DbError::Invalid(tf(Msg::ProjectNameInvalid, &[("name", &name)])));
+    }
+    Ok(name.to_string())
+}
+
+/// 由数据库文件路径得到项目名称
+fn project_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// 保存工作区配置
+async fn save_config(update: impl FnOnce(&mut WorkspaceConfig)) -> Result<(), DbError> {
+    let dir = APP_DATA_DIR
+        .get()
+        .ok_or_else(|| other(t(Msg::WorkspaceNotInitialized).to_string()))?;
+    let config_mutex = WORKSPACE_CONFIG.get_or_init(|| Mutex::new(WorkspaceConfig::default()));
+    let mut config = config_mutex.lock().await;
+    update(&mut config);
+
+    let text = serde_json::to_string_pretty(&*config).map_err(|e| DbError::Other(e.into()))?;
+    std::fs::write(dir.join(WORKSPACE_CONFIG_FILE), text).map_err(|e| DbError::Other(e.into()))
+}
+
+/// 记录为当前项目，并移到最近列表最前
+pub async fn record_opened(path: &str) -> Result<(), DbError> {
+    let entry = RecentProject {
+        name: project_name(Path::new(path)),
+        path: path.to_string(),
+        last_opened: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    save_config(|config| {
+        config.current_path = Some(entry.path.clone());
+        config.recent.retain(|r| r.path != entry.path);
+        config.recent.insert(0, entry);
+        config.recent.truncate(MAX_RECENT);
+    })
+    .await
+}
+
+/// 关闭当前数据库并打开指定数据库文件
+async fn switch_to(path: &str) -> Result<ProjectInfo, DbError> {
+    // init_db 成功后才会替换全局连接，失败时保持原数据库可用
+    db::init_db(path).await?;
+    record_opened(path).await?;
+    current_project()
+        .await?
+        .ok_or_else(|| other(t(Msg::OpenProjectFailed).to_string()))
+}
+
+/// 当前打开的项目
+pub async fn current_project() -> Result<Option<ProjectInfo>, DbError> {
+    let status = db::get_db_status().await;
+    let path = match status.path {
+        Some(path) if status.initialized => path,
+        _ => return Ok(None),
+    };
+    let last_opened = match WORKSPACE_CONFIG.get() {
+        Some(config) => config
+            .lock()
+            .await
+            .recent
+            .iter()
+            .find(|r| r.path == path)
+            .map(|r| r.last_opened.clone()),
+        None => None,
+    };
+
+    Ok(Some(ProjectInfo {
+        name: project_name(Path::new(&path)),
+        exists: Path::new(&path).is_file(),
+        current: true,
+        path,
+        last_opened,
+    }))
+}
+
+/// 在项目目录中新建项目并切换过去
+pub async fn create_project(name: &str) -> Result<ProjectInfo, DbError> {
+    let name = validate_project_name(name)?;
+    let path = project_path_in(&projects_dir()?, &name);
+    if path.exists() {
+        return Err(DbError::Invalid(tf(Msg::ProjectExists, &[("name", &name)])));
+    }
+    switch_to(&path.to_string_lossy()).await
+}
+
+/// 按名称切换到项目目录中的项目
+pub async fn switch_project(name: &str) -> Result<ProjectInfo, DbError> {
+    let name = validate_project_name(name)?;
+    let path = project_path_in(&projects_dir()?, &name);
+    if !path.is_file() {
+        return Err(DbError::NotFound(tf(Msg::ProjectNotFound, &[("name", &name)])));
+    }
+    switch_to(&path.to_string_lossy()).await
+}
+
+/// 打开任意位置的 DrillTrack 数据库文件
+pub async fn open_project(path: &str) -> Result<ProjectInfo, DbError> {
+    backup::validate_backup(path)?;
+    switch_to(path).await
+}
+
+/// 列出项目目录中的全部项目
+pub async fn list_projects() -> Result<Vec<ProjectInfo>, DbError> {
+    let dir = projects_dir()?;
+    let current = db::get_db_status().await.path;
+    let recent = list_recent_projects().await;
+
+    let mut projects = Vec::new();
+    for entry in std::fs::read_dir(&dir).map_err(|e| DbError::Other(e.into()))? {
+        let entry = entry.map_err(|e| DbError::Other(e.into()))?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(PROJECT_EXT) {
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        projects.push(ProjectInfo {
+            name: project_name(&path),
+            current: current.as_deref() == Some(path_str.as_str()),
+            exists: true,
+            last_opened: recent
+                .iter()
+                .find(|r| r.path == path_str)
+                .map(|r| r.last_opened.clone()),
+            path: path_str,
+        });
+    }
+
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(projects)
+}
+
+/// 最近打开的项目，最新的在前
+pub async fn list_recent_projects() -> Vec<RecentProject> {
+    match WORKSPACE_CONFIG.get() {
+        Some(config) => config.lock().await.recent.clone(),
+        None => Vec::new(),
+    }
+}
+
+/// 从最近列表中移除（不删除文件）
+pub async fn remove_recent_project(path: &str) -> Result<Vec<RecentProject>, DbError> {
+    save_config(|config| config.recent.retain(|r| r.path != path)).await?;
+    Ok(list_recent_projects().await)
+}
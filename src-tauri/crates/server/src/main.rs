@@ -0,0 +1,142 @@
+//! 无界面数据接收服务器
+//!
+//! 在办公室常开的机器上运行局域网发现、数据接收路由和数据库，不依赖桌面环境，日志输出到标准输出。
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use drilltrack_core::i18n::Locale;
+use drilltrack_core::services::db;
+use drilltrack_core::services::discovery::{self, DiscoveryConfig};
+use drilltrack_core::services::server::{self, ServerConfig, TlsConfig};
+use drilltrack_core::services::settings::{self, NetworkSettings, Settings};
+use std::path::PathBuf;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Parser)]
+#[command(name = "drilltrack-server", version, about = "钻孔轨迹仪数据接收服务器")]
+struct Args {
+    /// 数据接收服务器端口
+    #[arg(short, long, env = "DRILLTRACK_PORT", default_value_t = NetworkSettings::default().data_port)]
+    port: u16,
+
+    /// 数据库文件路径，不存在时自动创建
+    #[arg(long, env = "DRILLTRACK_DB", default_value = "database.db")]
+    db: PathBuf,
+
+    /// 局域网发现服务端口
+    #[arg(long, default_value_t = NetworkSettings::default().discovery_port)]
+    discovery_port: u16,
+
+    /// 客户端监听广播的端口
+    #[arg(long, default_value_t = NetworkSettings::default().client_port)]
+    client_port: u16,
+
+    /// 广播中的服务器名称
+    #[arg(long, default_value_t = NetworkSettings::default().server_name)]
+    name: String,
+
+    /// 不启动局域网发现服务
+    #[arg(long)]
+    no_discovery: bool,
+
+    /// TLS 证书文件（PEM），与 --tls-key 同时指定时启用 HTTPS
+    #[arg(long, requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// TLS 私钥文件（PEM）
+    #[arg(long, requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// 导出 WITSML 时使用的磁偏角（度），东偏为正
+    #[arg(
+        long,
+        env = "DRILLTRACK_DECLINATION",
+        default_value_t = 0.0,
+        allow_negative_numbers = true
+    )]
+    declination: f64,
+
+    /// 接口返回消息和导出文件使用的语言：zh-CN 或 en-US
+    #[arg(long, env = "DRILLTRACK_LOCALE", default_value = "zh-CN")]
+    locale: Locale,
+
+    /// 日志级别，RUST_LOG 环境变量优先
+    #[arg(long, default_value = "info")]
+    log_level: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("{},hyper=info", args.log_level)));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
+    // 服务器不读取桌面端的设置文件，导出用到的设置由命令行参数提供
+    let mut current = Settings {
+        locale: args.locale,
+        ..Settings::default()
+    };
+    current.survey.magnetic_declination = args.declination;
+    settings::init_in_memory(current).context("命令行参数无效")?;
+
+    let db_path = args.db.to_string_lossy().to_string();
+    db::init_db(&db_path)
+        .await
+        .with_context(|| format!("数据库初始化失败: {}", db_path))?;
+    info!("数据库已打开: {}", db_path);
+
+    let tls = match (args.tls_cert, args.tls_key) {
+        (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+            cert_path,
+            key_path,
+        }),
+        _ => None,
+    };
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let (addr, server_task) = server::spawn_server(
+        ServerConfig {
+            port: args.port,
+            tls,
+        },
+        async move {
+            let _ = shutdown_rx.await;
+        },
+    )?;
+    info!("数据接收地址: {}://{}", scheme, addr);
+
+    let discovery = if args.no_discovery {
+        None
+    } else {
+        let handle = discovery::start_discovery(DiscoveryConfig {
+            port: args.discovery_port,
+            client_port: args.client_port,
+            http_port: addr.port(),
+            server_name: args.name,
+        })
+        .await?;
+        info!("局域网发现服务已启动，本机地址: {:?}", handle.ip_addresses);
+        Some(handle)
+    };
+
+    tokio::signal::ctrl_c()
+        .await
+        .context("无法监听退出信号")?;
+    info!("收到退出信号，正在关闭");
+
+    if let Some(handle) = discovery {
+        handle.stop();
+    }
+    let _ = shutdown_tx.send(());
+    if let Err(e) = server_task.await {
+        warn!("数据服务器退出异常: {}", e);
+    }
+    db::close_db().await?;
+    info!("已退出");
+    Ok(())
+}
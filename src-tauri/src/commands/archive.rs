@@ -0,0 +1,35 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::services::archive::{
+    self, ArchiveManifest, ArchivePreview, ConflictPolicy, ImportReport,
+};
+
+// 导出所选 repo 到归档文件
+#[tauri::command]
+pub async fn export_archive(
+    repo_ids: Vec<i32>,
+    file_path: String,
+) -> Result<ArchiveManifest, AppError> {
+    archive::export_archive(&repo_ids, &file_path)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ExportArchiveFailed)))
+}
+
+// 预览归档内容及与当前数据库的冲突
+#[tauri::command]
+pub async fn inspect_archive(file_path: String) -> Result<ArchivePreview, AppError> {
+    archive::inspect_archive(&file_path)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::InspectArchiveFailed)))
+}
+
+// 导入归档
+#[tauri::command]
+pub async fn import_archive(
+    file_path: String,
+    on_conflict: Option<ConflictPolicy>,
+) -> Result<ImportReport, AppError> {
+    archive::import_archive(&file_path, on_conflict.unwrap_or(ConflictPolicy::Skip))
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ImportArchiveFailed)))
+}
@@ -0,0 +1,42 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::services::backup::{self, BackupInfo, SnapshotEntry};
+
+// 在线备份数据库到指定路径
+#[tauri::command]
+pub async fn backup_database(target_path: String) -> Result<BackupInfo, AppError> {
+    backup::backup_to(&target_path)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::BackupDatabaseFailed)))
+}
+
+// 校验备份文件
+#[tauri::command]
+pub fn validate_backup_file(path: String) -> Result<BackupInfo, AppError> {
+    backup::validate_backup(&path)
+        .map_err(|e| AppError::from(e).context(t(Msg::ValidateBackupFailed)))
+}
+
+// 从备份恢复数据库
+#[tauri::command]
+pub async fn restore_database(source_path: String) -> Result<BackupInfo, AppError> {
+    backup::restore_from(&source_path)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::RestoreDatabaseFailed)))
+}
+
+// 列出当前项目的每日快照
+#[tauri::command]
+pub async fn list_snapshots() -> Result<Vec<SnapshotEntry>, AppError> {
+    backup::list_snapshots()
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ListSnapshotsFailed)))
+}
+
+// 立即生成当前项目的当天快照
+#[tauri::command]
+pub async fn create_snapshot() -> Result<Option<SnapshotEntry>, AppError> {
+    backup::take_daily_snapshot()
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::CreateSnapshotFailed)))
+}
@@ -1,25 +1,28 @@
+use crate::error::{AppError, ErrorCode};
+use crate::i18n::{t, Msg};
 use crate::models::data::DataList;
 use crate::models::excel::ExcelData;
 use crate::models::repo::Repo;
 use crate::models::user::User;
 use crate::services::db::{self, DbStatus};
+use crate::services::search::{self, RepoSearchHit};
+use crate::services::settings;
+use crate::services::templates::{self, TemplateInfo};
+use crate::services::workspace;
 
 // 初始化数据库
 #[tauri::command]
-pub async fn init_database(db_path: Option<String>) -> Result<DbStatus, String> {
+pub async fn init_database(db_path: Option<String>) -> Result<DbStatus, AppError> {
     let path = match db_path {
         Some(p) => p,
-        None => {
-            // 使用默认路径
-            let app_dir =
-                std::env::current_dir().map_err(|e| format!("无法获取当前目录: {}", e))?;
-            app_dir.join("database.db").to_string_lossy().to_string()
-        }
+        // 使用应用数据目录下的默认项目
+        None => workspace::default_db_path()
+            .map_err(|e| AppError::from(e).context(t(Msg::InitDatabaseFailed)))?,
     };
 
     match db::init_db(&path).await {
         Ok(_) => Ok(db::get_db_status().await),
-        Err(e) => Err(format!("数据库初始化失败: {}", e)),
+        Err(e) => Err(AppError::from(e).context(t(Msg::InitDatabaseFailed))),
     }
 }
 
@@ -31,78 +34,136 @@ pub async fn get_db_status() -> DbStatus {
 
 // 关闭数据库连接
 #[tauri::command]
-pub async fn close_database() -> Result<(), String> {
+pub async fn close_database() -> Result<(), AppError> {
     db::close_db()
         .await
-        .map_err(|e| format!("关闭数据库失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::CloseDatabaseFailed)))
 }
 
 // 获取所有用户
 #[tauri::command]
-pub async fn get_all_users() -> Result<Vec<User>, String> {
+pub async fn get_all_users() -> Result<Vec<User>, AppError> {
     User::find_all()
         .await
-        .map_err(|e| format!("获取用户列表失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::ListUsersFailed)))
 }
 
 // 根据ID获取用户
 #[tauri::command]
-pub async fn get_user_by_id(id: i64) -> Result<Option<User>, String> {
+pub async fn get_user_by_id(id: i64) -> Result<Option<User>, AppError> {
     User::find_by_id(id)
         .await
-        .map_err(|e| format!("获取用户失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::GetUserFailed)))
 }
 
 // 搜索用户
 #[tauri::command]
-pub async fn search_users(query: String) -> Result<Vec<User>, String> {
+pub async fn search_users(query: String) -> Result<Vec<User>, AppError> {
     User::search_by_name(&query)
         .await
-        .map_err(|e| format!("搜索用户失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::SearchUsersFailed)))
 }
 
 // 创建或更新用户
 #[tauri::command]
-pub async fn save_user(user: User) -> Result<i64, String> {
+pub async fn save_user(user: User) -> Result<i64, AppError> {
     user.save()
         .await
-        .map_err(|e| format!("保存用户失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::SaveUserFailed)))
 }
 
 // 删除用户
 #[tauri::command]
-pub async fn delete_user(id: i64) -> Result<bool, String> {
+pub async fn delete_user(id: i64) -> Result<bool, AppError> {
     User::delete(id)
         .await
-        .map_err(|e| format!("删除用户失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::DeleteUserFailed)))
 }
 
 // 获取所有repo
 #[tauri::command]
-pub async fn get_all_repos() -> Result<Vec<Repo>, String> {
+pub async fn get_all_repos() -> Result<Vec<Repo>, AppError> {
     db::query_all_repos()
         .await
-        .map_err(|e| format!("获取repo列表失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::ListReposFailed)))
 }
 
 // 根据 repo_id 获取 data_list 数据
 #[tauri::command]
-pub async fn get_data_list_by_repo_id(repo_id: i32) -> Result<Vec<DataList>, String> {
+pub async fn get_data_list_by_repo_id(repo_id: i32) -> Result<Vec<DataList>, AppError> {
     db::query_data_list_by_repo_id(repo_id)
         .await
-        .map_err(|e| format!("获取 data_list 数据失败: {}", e))
+        .map_err(|e| AppError::from(e).context(t(Msg::GetDataListFailed)))
 }
 
+// 根据 UUID 获取 repo
+#[tauri::command]
+pub async fn get_repo_by_uuid(uuid: String) -> Result<Option<Repo>, AppError> {
+    Repo::query_repo_by_uuid(uuid)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::GetRepoFailed)))
+}
+
+// 全文检索 repo
+#[tauri::command]
+pub async fn search_repos(
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<RepoSearchHit>, AppError> {
+    search::search_repos(&query, limit)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::SearchReposFailed)))
+}
+
+// 更新 repo 备注
+#[tauri::command]
+pub async fn update_repo_notes(id: i32, notes: Option<String>) -> Result<bool, AppError> {
+    Repo::update_notes(id, notes)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::UpdateNotesFailed)))
+}
+
+// 导出 Excel，未指定磁偏角或报表模板时使用应用设置
 #[tauri::command]
 pub async fn save_data_to_excel(
     file_path: String,
     data: Vec<DataList>,
-    magnetic_declination: String,
-) -> Result<(), String> {
+    magnetic_declination: Option<String>,
+    template: Option<String>,
+) -> Result<(), AppError> {
+    let current = settings::current();
+    let template = templates::resolve(template.or(current.export.template.clone()).as_deref())
+        .map_err(|e| {
+            AppError::new(ErrorCode::InvalidInput, t(Msg::LoadTemplateFailed))
+                .with_details(format!("{:#}", e))
+        })?;
+    // 封面上的钻孔信息；查询失败时仍导出数据
+    let repo = match data.first().and_then(|row| row.repo_id) {
+        Some(repo_id) => Repo::query_repo_by_id(repo_id).await.ok().flatten(),
+        None => None,
+    };
     let excel_data = ExcelData {
-        file_path,
+        file_path: current.resolve_export_path(&file_path),
         data,
-        magnetic_declination,
+        magnetic_declination: magnetic_declination
+            .unwrap_or_else(|| current.survey.magnetic_declination.to_string()),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        repo,
+        tolerance: current.survey.tolerance,
+        template,
     };
-    excel_data.save_to_excel().await
+    excel_data
+        .save_to_excel()
+        .await
+        .map_err(|e| {
+            AppError::new(ErrorCode::Export, t(Msg::ExportExcelFailed))
+                .with_details(format!("{:#}", e))
+        })
+}
+
+// 列出可选的 Excel 报表模板（内置模板及模板目录中的 JSON 文件）
+#[tauri::command]
+pub fn list_report_templates() -> Result<Vec<TemplateInfo>, AppError> {
+    templates::list_templates().map_err(|e| AppError::from(e).context(t(Msg::ListTemplatesFailed)))
 }
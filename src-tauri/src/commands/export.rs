@@ -0,0 +1,291 @@
+use crate::calc;
+use crate::error::{AppError, ErrorCode};
+use crate::i18n::{t, Msg};
+use crate::models::repo::{Repo, RepoFilter};
+use crate::services::db;
+use crate::services::dxf::{Collar, DxfOptions};
+use crate::services::export::{
+    self, BatchExportRequest, BatchProgress, BatchReport, CancelFlag, CsvOptions, ExportFormat,
+    ExportOptions,
+};
+use crate::services::pdf::PdfOptions;
+use crate::services::plot::{PlotFormat, PlotOptions};
+use crate::services::projection::Projection;
+use crate::services::{settings, templates};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex as TokioMutex;
+
+/// 批量导出进度事件，载荷为 `BatchProgress`
+pub const BATCH_PROGRESS_EVENT: &str = "batch-export-progress";
+
+// 导出状态：正在进行的批量导出的取消标记
+#[derive(Default)]
+pub struct ExportState {
+    batch: TokioMutex<Option<CancelFlag>>,
+}
+
+pub fn init<R: tauri::Runtime>(app: &mut tauri::App<R>) -> Result<(), Box<dyn std::error::Error>> {
+    app.manage(ExportState::default());
+    Ok(())
+}
+
+// 批量导出钻孔到一个工作簿或一个目录，未指定磁偏角或报表模板时使用应用设置
+//
+// 请求中的孔口坐标与投影用于 kml 等格式，未指定投影时使用应用设置；单个钻孔写出失败时记入结果并继续。
+#[tauri::command]
+pub async fn batch_export(
+    app: AppHandle,
+    state: State<'_, ExportState>,
+    mut request: BatchExportRequest,
+    magnetic_declination: Option<f64>,
+    template: Option<String>,
+) -> Result<BatchReport, AppError> {
+    let current = settings::current();
+    let template = templates::resolve(template.or(current.export.template.clone()).as_deref())
+        .map_err(|e| {
+            AppError::new(ErrorCode::InvalidInput, t(Msg::LoadTemplateFailed))
+                .with_details(format!("{:#}", e))
+        })?;
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        tolerance: current.survey.tolerance.clone(),
+        template,
+        csv: current.export.csv,
+        pdf: current.export.pdf.clone(),
+        plot: current.export.plot.clone(),
+        projection: current.export.projection,
+        ..ExportOptions::default()
+    };
+    request.path = current.resolve_export_path(&request.path);
+
+    let cancel = CancelFlag::default();
+    {
+        let mut batch = state.batch.lock().await;
+        if batch.is_some() {
+            return Err(AppError::new(ErrorCode::Export, t(Msg::BatchExportRunning)));
+        }
+        *batch = Some(cancel.clone());
+    }
+
+    let result = export::export_batch(&request, &options, &cancel, |progress: &BatchProgress| {
+        let _ = app.emit(BATCH_PROGRESS_EVENT, progress.clone());
+    })
+    .await;
+    *state.batch.lock().await = None;
+
+    result.map_err(|e| AppError::from(e).context(t(Msg::BatchExportFailed)))
+}
+
+// 取消正在进行的批量导出，没有进行中的导出时返回 false
+#[tauri::command]
+pub async fn cancel_batch_export(state: State<'_, ExportState>) -> Result<bool, AppError> {
+    match state.batch.lock().await.as_ref() {
+        Some(cancel) => {
+            cancel.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// 把所选钻孔的平面图和剖面图导出到一个 DXF 文件
+//
+// collars 为各钻孔的孔口坐标（按钻孔 ID），dxf 为深度标注间隔与文字高度。
+#[tauri::command]
+pub async fn export_dxf(
+    filter: RepoFilter,
+    file_path: String,
+    collars: Option<HashMap<i32, Collar>>,
+    dxf: Option<DxfOptions>,
+    magnetic_declination: Option<f64>,
+) -> Result<BatchReport, AppError> {
+    let current = settings::current();
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        collars: collars.unwrap_or_default(),
+        dxf: dxf.unwrap_or_default(),
+        ..ExportOptions::default()
+    };
+    let path = current.resolve_export_path(&file_path);
+    export::export_dxf(filter, Path::new(&path), &options)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ExportDxfFailed)))
+}
+
+// 把所选钻孔画到同一张平面图、剖面图或等轴测图，输出 SVG 或 PNG
+//
+// plot 为视图、尺寸与样式，未指定时使用应用设置；collars 为各钻孔的孔口坐标（按钻孔 ID）。
+#[tauri::command]
+pub async fn export_plot(
+    filter: RepoFilter,
+    file_path: String,
+    format: PlotFormat,
+    plot: Option<PlotOptions>,
+    collars: Option<HashMap<i32, Collar>>,
+    magnetic_declination: Option<f64>,
+) -> Result<BatchReport, AppError> {
+    let current = settings::current();
+    let plot = plot.unwrap_or_else(|| current.export.plot.clone());
+    plot.validate().map_err(|e| {
+        AppError::invalid_input(t(Msg::ExportPlotFailed)).with_details(e.to_string())
+    })?;
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        collars: collars.unwrap_or_default(),
+        plot,
+        ..ExportOptions::default()
+    };
+    let path = current.resolve_export_path(&file_path);
+    export::export_plot(filter, Path::new(&path), format, &options)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ExportPlotFailed)))
+}
+
+// 把所选钻孔导出到一个 KML 文件，按矿井、工作面分文件夹，用于在地图上概览地面钻孔
+//
+// collars 为各钻孔的孔口坐标（按钻孔 ID），没有孔口坐标的钻孔跳过；
+// projection 为孔口坐标的投影，未指定时使用应用设置。
+#[tauri::command]
+pub async fn export_kml(
+    filter: RepoFilter,
+    file_path: String,
+    collars: Option<HashMap<i32, Collar>>,
+    projection: Option<Projection>,
+    magnetic_declination: Option<f64>,
+) -> Result<BatchReport, AppError> {
+    let current = settings::current();
+    let projection = projection.or(current.export.projection).ok_or_else(|| {
+        AppError::invalid_input(t(Msg::ExportKmlFailed)).with_details(t(Msg::KmlNeedsProjection))
+    })?;
+    projection.validate().map_err(|e| {
+        AppError::invalid_input(t(Msg::ExportKmlFailed)).with_details(e.to_string())
+    })?;
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        tolerance: current.survey.tolerance.clone(),
+        collars: collars.unwrap_or_default(),
+        projection: Some(projection),
+        ..ExportOptions::default()
+    };
+    let path = current.resolve_export_path(&file_path);
+    export::export_kml(filter, Path::new(&path), &options)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ExportKmlFailed)))
+}
+
+// 导出单个钻孔的测点数据与轨迹计算结果，返回写出的文件路径
+//
+// format 可为 xlsx、csv、json、geojson、las、witsml、kml、dxf、scr、svg 或 png，轨迹图使用应用设置中的视图与样式，
+// kml 使用应用设置中的投影；
+// csv 未指定时使用应用设置中的分隔符与小数点，
+// collar 为孔口坐标，用于 GeoJSON、KML、DXF 与轨迹图。
+#[tauri::command]
+pub async fn export_repo(
+    repo_id: i32,
+    format: ExportFormat,
+    file_path: String,
+    csv: Option<CsvOptions>,
+    collar: Option<Collar>,
+    magnetic_declination: Option<f64>,
+    template: Option<String>,
+) -> Result<String, AppError> {
+    let current = settings::current();
+    let template = templates::resolve(template.or(current.export.template.clone()).as_deref())
+        .map_err(|e| {
+            AppError::new(ErrorCode::InvalidInput, t(Msg::LoadTemplateFailed))
+                .with_details(format!("{:#}", e))
+        })?;
+    let csv = csv.unwrap_or(current.export.csv);
+    csv.validate().map_err(|e| {
+        AppError::invalid_input(t(Msg::ExportRepoFailed)).with_details(e.to_string())
+    })?;
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        tolerance: current.survey.tolerance.clone(),
+        template,
+        collars: collar
+            .map(|c| HashMap::from([(repo_id, c)]))
+            .unwrap_or_default(),
+        csv,
+        pdf: current.export.pdf.clone(),
+        plot: current.export.plot.clone(),
+        projection: current.export.projection,
+        ..ExportOptions::default()
+    };
+
+    export_single(repo_id, format, &file_path, &options, Msg::ExportRepoFailed).await
+}
+
+// 生成单个钻孔的 PDF 测量报告，返回写出的文件路径
+//
+// pdf 为公司名称、标志图片和签字栏，未指定时使用应用设置。
+// 中文使用 STSong-Light，字体不嵌入文件：查看报告的阅读器需装有 Adobe 亚洲字体包
+// 或能替换该字体，否则中文无法显示。
+#[tauri::command]
+pub async fn export_pdf_report(
+    repo_id: i32,
+    file_path: String,
+    pdf: Option<PdfOptions>,
+    collar: Option<Collar>,
+    magnetic_declination: Option<f64>,
+) -> Result<String, AppError> {
+    let current = settings::current();
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        tolerance: current.survey.tolerance.clone(),
+        collars: collar
+            .map(|c| HashMap::from([(repo_id, c)]))
+            .unwrap_or_default(),
+        pdf: pdf.unwrap_or_else(|| current.export.pdf.clone()),
+        ..ExportOptions::default()
+    };
+    export_single(
+        repo_id,
+        ExportFormat::Pdf,
+        &file_path,
+        &options,
+        Msg::ExportPdfFailed,
+    )
+    .await
+}
+
+/// 读取钻孔及测点并导出为一个文件，测点数据校验未通过时不导出
+async fn export_single(
+    repo_id: i32,
+    format: ExportFormat,
+    file_path: &str,
+    options: &ExportOptions,
+    failed: Msg,
+) -> Result<String, AppError> {
+    let repo = Repo::query_repo_by_id(repo_id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(failed)))?
+        .ok_or_else(|| AppError::not_found(t(Msg::RepoNotFound)))?;
+    let stations = db::query_data_list_by_repo_id(repo_id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(failed)))?;
+    let problems = calc::validate_stations(&stations);
+    if !problems.is_empty() {
+        return Err(AppError::invalid_input(t(failed)).with_details(problems.join("; ")));
+    }
+
+    let path = settings::current().resolve_export_path(file_path);
+    export::export_hole(&repo, &stations, format, Path::new(&path), options)
+        .await
+        .map_err(|e| AppError::from(e).context(t(failed)))?;
+    Ok(path)
+}
@@ -1,5 +1,8 @@
 use std::fs;
+use tracing::debug;
 
+use crate::error::{AppError, ErrorCode};
+use crate::i18n::{t, Msg};
 use crate::utils::license::{generate_hardware_id, verify_license_file, get_license_path, get_license_info, is_license_expired, License};
 
 // 根据硬件信息获取唯一的机器ID
@@ -10,9 +13,9 @@ pub fn get_machine_id() -> String {
 
 // 导出机器ID到文件
 #[tauri::command]
-pub fn export_machine_id(file_path: &str) -> Result<(), String> {
+pub fn export_machine_id(file_path: &str) -> Result<(), AppError> {
     let machine_id = generate_hardware_id();
-    fs::write(file_path, machine_id).map_err(|e| e.to_string())
+    fs::write(file_path, machine_id).map_err(|e| AppError::from(e).context(t(Msg::ExportMachineIdFailed)))
 }
 
 // 检查应用程序是否已激活
@@ -28,8 +31,8 @@ pub fn check_activation() -> bool {
     if !path.exists() {
         return false;
     }
-    println!("路径地址：{:?}",path);
-    return true;
+    debug!("路径地址：{:?}",path);
+    true
    /*  // 发布模式：验证许可证内容
     let public_key = include_bytes!("../../keys/public_key.der");
     match verify_license_file(&path, public_key) {
@@ -40,11 +43,11 @@ pub fn check_activation() -> bool {
 
 // 使用提供的密钥激活许可证
 #[tauri::command]
-pub fn activate_license(license_key: &str) -> Result<bool, String> {
+pub fn activate_license(license_key: &str) -> Result<bool, AppError> {
     // 从base64解码许可证密钥
     let license_data = match data_encoding::BASE64.decode(license_key.as_bytes()) {
         Ok(data) => data,
-        Err(_) => return Err("无效的许可证密钥格式".into()),
+        Err(e) => return Err(AppError::invalid_input(t(Msg::InvalidLicenseKey)).with_details(e)),
     };
 
     // 保存许可证文件
@@ -52,44 +55,41 @@ pub fn activate_license(license_key: &str) -> Result<bool, String> {
         Some(path) => {
             if let Some(parent) = path.parent() {
                 if !parent.exists() {
-                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    fs::create_dir_all(parent)?;
                 }
             }
 
-            fs::write(&path, &license_data).map_err(|e| e.to_string())?;
+            fs::write(&path, &license_data)?;
 
             // 在生产模式下使用正确的验证
             let public_key = include_bytes!("../../keys/public_key.der");
-            match verify_license_file(&path, public_key) {
-                Ok(valid) => Ok(valid),
-                Err(e) => Err(e),
-            }
+            verify_license_file(&path, public_key).map_err(license_invalid)
         }
-        None => Err("无法确定许可证文件路径".into()),
+        None => Err(AppError::new(ErrorCode::Io, t(Msg::LicensePathUnknown))),
     }
 }
 
 // 获取许可证信息
 #[tauri::command]
-pub fn get_license_info_command() -> Result<License, String> {
+pub fn get_license_info_command() -> Result<License, AppError> {
     let path = match get_license_path() {
         Some(p) => p,
-        None => return Err("无法确定许可证文件路径".into()),
+        None => return Err(AppError::new(ErrorCode::Io, t(Msg::LicensePathUnknown))),
     };
 
     if !path.exists() {
-        return Err("许可证文件不存在".into());
+        return Err(AppError::new(ErrorCode::LicenseMissing, t(Msg::LicenseMissing)));
     }
 
     // 获取许可证信息
-    let license = get_license_info(&path)?;
+    let license = get_license_info(&path).map_err(license_invalid)?;
     
     // 验证许可证是否有效
     let public_key = include_bytes!("../../keys/public_key.der");
-    let is_valid = verify_license_file(&path, public_key).map_err(|e| e.to_string())?;
+    let is_valid = verify_license_file(&path, public_key).map_err(license_invalid)?;
     
     if !is_valid {
-        return Err("许可证无效".into());
+        return Err(AppError::new(ErrorCode::LicenseInvalid, t(Msg::LicenseInvalid)));
     }
     
     Ok(license)
@@ -97,29 +97,29 @@ pub fn get_license_info_command() -> Result<License, String> {
 
 // 检查许可证是否过期
 #[tauri::command]
-pub fn is_license_expired_command() -> Result<bool, String> {
+pub fn is_license_expired_command() -> Result<bool, AppError> {
     let path = match get_license_path() {
         Some(p) => p,
-        None => return Err("无法确定许可证文件路径".into()),
+        None => return Err(AppError::new(ErrorCode::Io, t(Msg::LicensePathUnknown))),
     };
 
     if !path.exists() {
-        return Err("许可证文件不存在".into());
+        return Err(AppError::new(ErrorCode::LicenseMissing, t(Msg::LicenseMissing)));
     }
 
     // 获取许可证信息
-    let license = get_license_info(&path)?;
+    let license = get_license_info(&path).map_err(license_invalid)?;
     
     Ok(is_license_expired(&license))
 }
 
 // 从文件导入许可证
 #[tauri::command]
-pub fn import_license_from_file(file_path: &str) -> Result<bool, String> {
+pub fn import_license_from_file(file_path: &str) -> Result<bool, AppError> {
     // 读取许可证文件
     let license_data = match fs::read(file_path) {
         Ok(data) => data,
-        Err(e) => return Err(format!("无法读取许可证文件: {}", e)),
+        Err(e) => return Err(AppError::from(e).context(t(Msg::LicenseReadFailed))),
     };
 
     // 保存许可证文件到应用程序目录
@@ -127,11 +127,11 @@ pub fn import_license_from_file(file_path: &str) -> Result<bool, String> {
         Some(path) => {
             if let Some(parent) = path.parent() {
                 if !parent.exists() {
-                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    fs::create_dir_all(parent)?;
                 }
             }
 
-            fs::write(&path, &license_data).map_err(|e| e.to_string())?;
+            fs::write(&path, &license_data)?;
 
             // 验证许可证文件
             let public_key = include_bytes!("../../keys/public_key.der");
@@ -142,16 +142,21 @@ pub fn import_license_from_file(file_path: &str) -> Result<bool, String> {
                     } else {
                         // 如果验证失败，删除导入的许可证
                         let _ = fs::remove_file(&path);
-                        Err("许可证无效或与此机器不匹配".into())
+                        Err(AppError::new(ErrorCode::LicenseInvalid, t(Msg::LicenseMismatch)))
                     }
                 }
                 Err(e) => {
                     // 如果验证出错，删除导入的许可证
                     let _ = fs::remove_file(&path);
-                    Err(e)
+                    Err(license_invalid(e))
                 }
             }
         }
-        None => Err("无法确定许可证文件路径".into()),
+        None => Err(AppError::new(ErrorCode::Io, t(Msg::LicensePathUnknown))),
     }
 }
+
+// 许可证校验失败
+fn license_invalid(details: String) -> AppError {
+    AppError::new(ErrorCode::LicenseInvalid, t(Msg::LicenseVerifyFailed)).with_details(details)
+}
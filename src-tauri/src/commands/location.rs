@@ -0,0 +1,87 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::models::location::{Location, LocationLevel, MineNode};
+use crate::models::repo::Repo;
+
+// 列出某层级的节点
+#[tauri::command]
+pub async fn list_locations(
+    level: LocationLevel,
+    parent_id: Option<i64>,
+) -> Result<Vec<Location>, AppError> {
+    Location::list(level, parent_id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ListLocationsFailed)))
+}
+
+// 新建节点
+#[tauri::command]
+pub async fn create_location(
+    level: LocationLevel,
+    name: String,
+    parent_id: Option<i64>,
+) -> Result<i64, AppError> {
+    Location::create(level, name, parent_id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::CreateLocationFailed)))
+}
+
+// 重命名节点
+#[tauri::command]
+pub async fn rename_location(
+    level: LocationLevel,
+    id: i64,
+    name: String,
+) -> Result<bool, AppError> {
+    Location::rename(level, id, name)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::RenameLocationFailed)))
+}
+
+// 删除节点
+#[tauri::command]
+pub async fn delete_location(level: LocationLevel, id: i64) -> Result<bool, AppError> {
+    Location::delete(level, id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::DeleteLocationFailed)))
+}
+
+// 合并两个同级节点
+#[tauri::command]
+pub async fn merge_locations(
+    level: LocationLevel,
+    source_id: i64,
+    target_id: i64,
+) -> Result<(), AppError> {
+    Location::merge(level, source_id, target_id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::MergeLocationsFailed)))
+}
+
+// 获取矿井 / 工作面 / 钻场树
+#[tauri::command]
+pub async fn get_location_tree() -> Result<Vec<MineNode>, AppError> {
+    Location::tree()
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::GetLocationTreeFailed)))
+}
+
+// 获取某节点下的 repo
+#[tauri::command]
+pub async fn get_repos_by_location(level: LocationLevel, id: i64) -> Result<Vec<Repo>, AppError> {
+    Location::repos(level, id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::GetLocationReposFailed)))
+}
+
+// 指定 repo 所在钻场和钻机
+#[tauri::command]
+pub async fn assign_repo_location(
+    repo_id: i32,
+    drilling_site_id: Option<i64>,
+    rig_id: Option<i64>,
+) -> Result<bool, AppError> {
+    Location::assign_repo(repo_id, drilling_site_id, rig_id)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::AssignRepoLocationFailed)))
+}
@@ -0,0 +1,13 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::services::logging::{self, LogLine};
+
+// 获取最近的日志行，供技术支持界面查看
+#[tauri::command]
+pub fn get_recent_logs(
+    limit: Option<usize>,
+    level: Option<String>,
+) -> Result<Vec<LogLine>, AppError> {
+    logging::recent_logs(limit, level.as_deref())
+        .map_err(|e| AppError::from(e).context(t(Msg::ReadLogsFailed)))
+}
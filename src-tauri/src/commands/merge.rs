@@ -0,0 +1,19 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::services::merge::{self, MergeReport};
+
+// 预览合并结果（不写入）
+#[tauri::command]
+pub async fn preview_database_merge(source_path: String) -> Result<MergeReport, AppError> {
+    merge::merge_database(&source_path, true)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::PreviewMergeFailed)))
+}
+
+// 合并另一个数据库到当前数据库
+#[tauri::command]
+pub async fn merge_database(source_path: String) -> Result<MergeReport, AppError> {
+    merge::merge_database(&source_path, false)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::MergeDatabaseFailed)))
+}
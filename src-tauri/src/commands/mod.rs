@@ -1,3 +1,12 @@
+pub mod archive;
+pub mod backup;
+pub mod export;
 pub mod license;
+pub mod location;
+pub mod logging;
+pub mod merge;
 pub mod network;
+pub mod settings;
+pub mod witsml;
+pub mod workspace;
 pub mod db; 
\ No newline at end of file
@@ -1,15 +1,15 @@
-use crate::services::router::init_route;
+use crate::error::{AppError, ErrorCode};
+use crate::i18n::{t, Msg};
+use crate::services::discovery::{self, DiscoveryConfig, DiscoveryHandle};
+use crate::services::server::{self, ServerConfig};
+use crate::services::settings;
 use anyhow::Result;
-use chrono;
 use serde::{Deserialize, Serialize};
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::sync::{Arc, Mutex};
 use tauri::Manager;
 use tauri::State;
-use tokio::net::UdpSocket;
 use tokio::sync::mpsc;
 use tokio::sync::Mutex as TokioMutex;
-use tokio::time::Duration;
+use tracing::info;
 
 // 局域网发现服务状态
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -33,75 +33,40 @@ pub struct DataServerStatus {
 pub struct NetworkState {
     discovery: TokioMutex<DiscoveryStatus>,
     data_server: TokioMutex<DataServerStatus>,
-    discovery_tx: TokioMutex<Option<mpsc::Sender<()>>>, // 用于关闭发现服务
-    server_tx: TokioMutex<Option<mpsc::Sender<()>>>,    // 用于关闭服务器
+    discovery_handle: TokioMutex<Option<DiscoveryHandle>>, // 用于关闭发现服务
+    server_tx: TokioMutex<Option<mpsc::Sender<()>>>,       // 用于关闭服务器
 }
 
 impl Default for NetworkState {
     fn default() -> Self {
+        // 端口与服务器名称取自应用设置
+        let network = settings::current().network;
         Self {
             discovery: TokioMutex::new(DiscoveryStatus {
                 active: false,
-                port: 9090,
-                server_name: "钻孔轨迹仪数据处理系统".to_string(),
+                port: network.discovery_port,
+                server_name: network.server_name,
                 discovered_clients: Vec::new(),
             }),
             data_server: TokioMutex::new(DataServerStatus {
                 running: false,
-                port: 8080,
+                port: network.data_port,
                 received_data_count: 0,
                 ip_address: "127.0.0.1".to_string(),
             }),
-            discovery_tx: TokioMutex::new(None),
+            discovery_handle: TokioMutex::new(None),
             server_tx: TokioMutex::new(None),
         }
     }
 }
 
-// 获取本机所有IPv4地址
-fn get_local_ipv4_addresses() -> Vec<String> {
-    match local_ip_address::list_afinet_netifas() {
-        Ok(netifas) => {
-            let addresses: Vec<String> = netifas
-                .iter()
-                .filter_map(|(_, addr)| {
-                    if let std::net::IpAddr::V4(ipv4) = addr {
-                        let octets = ipv4.octets();
-                        // 只保留 192.168.x.x，排除 loopback/169.254/10.x/172.16-31
-                        if octets[0] == 192 && octets[1] == 168 {
-                            return Some(ipv4.to_string());
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            // 没有找到非回环地址时，添加环回地址
-            if addresses.is_empty() {
-                return vec!["127.0.0.1".to_string()];
-            }
-
-            // 打印所有找到的地址
-            println!("本机IP地址: {:?}", addresses);
-            addresses
-        }
-        Err(e) => {
-            println!("获取IP地址错误: {:?}", e);
-            vec!["127.0.0.1".to_string()]
-        }
-    }
-}
-
 // 启动局域网发现服务
 #[tauri::command]
 pub async fn start_discovery_service(
     port: Option<u16>,
     server_name: Option<String>,
     network_state: State<'_, NetworkState>,
-) -> Result<DiscoveryStatus, String> {
+) -> Result<DiscoveryStatus, AppError> {
     let mut discovery_state = network_state.discovery.lock().await;
 
     // 如果发现服务已经在运行，返回当前状态
@@ -109,170 +74,32 @@ pub async fn start_discovery_service(
         return Ok(discovery_state.clone());
     }
 
-    // 设置参数
-    let discovery_port = port.unwrap_or(9090);
-    let name = server_name.unwrap_or("钻孔轨迹仪数据处理系统".to_string());
-    discovery_state.port = discovery_port;
-    discovery_state.server_name = name.clone();
-    discovery_state.active = true;
-    discovery_state.discovered_clients.clear();
+    // 设置参数，未指定时使用应用设置
+    let network = settings::current().network;
+    let config = DiscoveryConfig {
+        port: port.unwrap_or(network.discovery_port),
+        client_port: network.client_port,
+        // 提前获取数据服务器端口，随广播告知客户端
+        http_port: network_state.data_server.lock().await.port,
+        server_name: server_name.unwrap_or(network.server_name),
+    };
 
-    // 获取本机IP地址
-    let ip_addresses = get_local_ipv4_addresses();
-    if ip_addresses.is_empty() {
-        return Err("无法获取本机IP地址".to_string());
-    }
+    let handle = discovery::start_discovery(config.clone())
+        .await
+        .map_err(|e| {
+            AppError::new(ErrorCode::Network, t(Msg::StartDiscoveryFailed)).with_details(e)
+        })?;
 
     // 更新数据服务器IP地址
-    let mut data_server = network_state.data_server.lock().await;
-    data_server.ip_address = ip_addresses[0].clone();
-    drop(data_server);
+    network_state.data_server.lock().await.ip_address = handle.ip_addresses[0].clone();
 
-    // 创建一个通道用于关闭发现服务
-    let (tx, mut rx) = mpsc::channel::<()>(1);
-
-    // 记录已发现的客户端
-    let clients = Arc::new(TokioMutex::new(Vec::<String>::new()));
-    let clients_clone = clients.clone();
-
-    // 创建UDP套接字用于广播
-    let socket = match UdpSocket::bind("0.0.0.0:0").await {
-        Ok(s) => s,
-        Err(e) => return Err(format!("无法创建UDP套接字: {}", e)),
-    };
-
-    // 设置为广播模式
-    if let Err(e) = socket.set_broadcast(true) {
-        return Err(format!("无法设置广播模式: {}", e));
-    }
-
-    // 提前获取数据服务器端口，避免在异步任务中使用network_state
-    let http_port = network_state.data_server.lock().await.port;
-
-    let server_info = serde_json::json!({
-        "type": "server_announce",
-        "name": name.clone(),
-        "http_port": http_port,
-        "discovery_port": discovery_port,
-        "ip": ip_addresses[0].clone(),
-        "all_ips": ip_addresses,  // 提供所有可用IP
-        "version": "1.0",
-        "timestamp": chrono::Utc::now().timestamp_millis()
-    })
-    .to_string();
-
-    println!("启动广播，发送信息: {}", server_info);
-
-    // 使用Arc包装UdpSocket以便在不同任务间共享
-    let socket = Arc::new(socket);
-    let socket_sender = socket.clone();
-
-    tokio::spawn(async move {
-        let broadcast_addr = "255.255.255.255:9091"; // 客户端监听端口
-        let mut interval = tokio::time::interval(Duration::from_secs(2));
-
-        loop {
-            tokio::select! {
-                _ = rx.recv() => {
-                    println!("停止发现服务广播");
-                    break;
-                }
-                _ = interval.tick() => {
-                    if let Err(e) = socket_sender.send_to(server_info.as_bytes(), broadcast_addr).await {
-                        println!("广播消息发送失败: {}", e);
-                    } else {
-                        // 打印发送成功信息，但不要每次都打印完整消息
-                        println!("已发送广播消息");
-                    }
-                }
-            }
-        }
-    });
-
-    // 启动监听客户端消息的线程
-    // 提前获取所需数据的副本
-    let name_clone = name.clone();
-    let http_port = network_state.data_server.lock().await.port;
-
-    tokio::spawn(async move {
-        let mut buf = [0u8; 1024];
-        let listen_addr = format!("0.0.0.0:{}", discovery_port);
-
-        // 绑定UDP监听端口
-        let listen_socket = match UdpSocket::bind(&listen_addr).await {
-            Ok(s) => s,
-            Err(e) => {
-                println!("无法绑定UDP监听端口 {}: {}", discovery_port, e);
-                return;
-            }
-        };
-
-        loop {
-            match listen_socket.recv_from(&mut buf).await {
-                Ok((size, addr)) => {
-                    if let Ok(msg) = std::str::from_utf8(&buf[..size]) {
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(msg) {
-                            if json["type"] == "client_discovery" {
-                                println!("收到客户端发现请求: {} 来自 {}", msg, addr);
-
-                                // 记录客户端地址
-                                let client_addr = addr.ip().to_string();
-                                let mut clients = clients.lock().await;
-                                if !clients.contains(&client_addr) {
-                                    clients.push(client_addr);
-                                }
-
-                                // 直接回复客户端 - 使用事先获取的数据
-                                let response = serde_json::json!({
-                                    "type": "server_response",
-                                    "name": name_clone,
-                                    "http_port": http_port,
-                                    "ip": ip_addresses[0].clone(),    // 添加服务器IP
-                                    "discovery_port": discovery_port, // 添加发现服务端口
-                                    "timestamp": chrono::Utc::now().timestamp_millis()
-                                })
-                                .to_string();
-
-                                println!("发送回复: {} 到 {}", response, addr);
-
-                                if let Err(e) =
-                                    listen_socket.send_to(response.as_bytes(), addr).await
-                                {
-                                    println!("回复客户端失败: {}", e);
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("接收UDP消息失败: {}", e);
-                }
-            }
-        }
-    });
-
-    // 启动定期更新发现客户端的任务
-    let discovery_status = Arc::new(TokioMutex::new(discovery_state.clone()));
-    let clients_for_update = clients_clone.clone();
-
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(5));
-        loop {
-            interval.tick().await;
-            let clients = clients_for_update.lock().await;
-            let mut status = discovery_status.lock().await;
-            status.discovered_clients = clients.clone();
-            if !status.active {
-                break;
-            }
-        }
-    });
+    discovery_state.port = config.port;
+    discovery_state.server_name = config.server_name;
+    discovery_state.active = true;
+    discovery_state.discovered_clients.clear();
 
-    // 保存通道发送端
-    {
-        let mut tx_guard = network_state.discovery_tx.lock().await;
-        *tx_guard = Some(tx);
-    }
+    // 保存服务句柄
+    *network_state.discovery_handle.lock().await = Some(handle);
 
     Ok(discovery_state.clone())
 }
@@ -281,7 +108,7 @@ pub async fn start_discovery_service(
 #[tauri::command]
 pub async fn stop_discovery_service(
     network_state: State<'_, NetworkState>,
-) -> Result<DiscoveryStatus, String> {
+) -> Result<DiscoveryStatus, AppError> {
     let mut discovery_state = network_state.discovery.lock().await;
 
     // 如果发现服务没有运行，直接返回状态
@@ -290,13 +117,8 @@ pub async fn stop_discovery_service(
     }
 
     // 发送停止信号
-    {
-        let mut tx_guard = network_state.discovery_tx.lock().await;
-        if let Some(tx) = tx_guard.take() {
-            if let Err(e) = tx.send(()).await {
-                return Err(format!("无法发送停止信号: {}", e));
-            }
-        }
+    if let Some(handle) = network_state.discovery_handle.lock().await.take() {
+        handle.stop();
     }
 
     // 更新状态
@@ -310,8 +132,11 @@ pub async fn stop_discovery_service(
 #[tauri::command]
 pub async fn get_discovery_status(
     network_state: State<'_, NetworkState>,
-) -> Result<DiscoveryStatus, String> {
-    let discovery_state = network_state.discovery.lock().await;
+) -> Result<DiscoveryStatus, AppError> {
+    let mut discovery_state = network_state.discovery.lock().await;
+    if let Some(handle) = network_state.discovery_handle.lock().await.as_ref() {
+        discovery_state.discovered_clients = handle.clients().await;
+    }
     Ok(discovery_state.clone())
 }
 
@@ -319,7 +144,7 @@ pub async fn get_discovery_status(
 #[tauri::command]
 pub async fn get_data_server_status(
     network_state: State<'_, NetworkState>,
-) -> Result<DataServerStatus, String> {
+) -> Result<DataServerStatus, AppError> {
     let server_state = network_state.data_server.lock().await;
     Ok(server_state.clone())
 }
@@ -329,7 +154,7 @@ pub async fn get_data_server_status(
 pub async fn start_data_server(
     port: Option<u16>,
     network_state: State<'_, NetworkState>,
-) -> Result<DataServerStatus, String> {
+) -> Result<DataServerStatus, AppError> {
     let mut server_state = network_state.data_server.lock().await;
 
     // 如果服务器已经在运行，返回当前状态
@@ -338,71 +163,45 @@ pub async fn start_data_server(
     }
 
     // 获取本机IP地址
-    let ip_addresses = get_local_ipv4_addresses();
+    let ip_addresses = discovery::local_ipv4_addresses();
     if !ip_addresses.is_empty() {
         server_state.ip_address = ip_addresses[0].clone();
     }
 
-    // 设置端口
-    let server_port = port.unwrap_or(8080);
-    server_state.port = server_port;
-    server_state.running = true;
-    server_state.received_data_count = 0;
+    // 设置端口，未指定时使用应用设置
+    let server_port = port.unwrap_or(settings::current().network.data_port);
 
     // 创建一个通道用于关闭服务器
     let (tx, mut rx) = mpsc::channel::<()>(1);
 
-    // 创建一个非同步可变引用的计数器
-    let counter = Arc::new(Mutex::new(0usize));
-    let counter_clone = counter.clone();
+    // 在后台启动服务器
+    server::spawn_server(
+        ServerConfig {
+            port: server_port,
+            tls: None,
+        },
+        async move {
+            rx.recv().await;
+            info!("关闭数据服务器");
+        },
+    )
+    .map_err(|e| AppError::new(ErrorCode::Network, t(Msg::StartServerFailed)).with_details(e))?;
 
-    // 创建REST API路由
-    let routes = init_route();
-    // 获取服务器地址
-    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), server_port);
-
-    // 启动服务器
-    let (_, server) = warp::serve(routes).bind_with_graceful_shutdown(addr, async move {
-        rx.recv().await;
-        println!("关闭数据服务器");
-    });
-
-    // 在后台线程中运行服务器
-    tokio::spawn(server);
+    server_state.port = server_port;
+    server_state.running = true;
+    server_state.received_data_count = 0;
 
     // 保存通道发送端
-    let mut tx_guard = network_state.server_tx.lock().await;
-    *tx_guard = Some(tx);
-
-    // 创建一个新的数据服务器状态，以避免生命周期问题
-    let data_server = Arc::new(TokioMutex::new(server_state.clone()));
-
-    // 启动定期更新计数器的任务
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(1));
-        loop {
-            interval.tick().await;
-            let count = *counter_clone.lock().unwrap();
-            let mut server_state = data_server.lock().await;
-            if server_state.running {
-                server_state.received_data_count = count;
-            } else {
-                break;
-            }
-        }
-    });
-
-    drop(server_state);
+    *network_state.server_tx.lock().await = Some(tx);
 
-    // 返回服务器状态
-    Ok(network_state.data_server.lock().await.clone())
+    Ok(server_state.clone())
 }
 
 // 停止数据服务器
 #[tauri::command]
 pub async fn stop_data_server(
     network_state: State<'_, NetworkState>,
-) -> Result<DataServerStatus, String> {
+) -> Result<DataServerStatus, AppError> {
     let mut server_state = network_state.data_server.lock().await;
 
     // 如果服务器没有运行，直接返回状态
@@ -415,7 +214,9 @@ pub async fn stop_data_server(
         let mut tx_guard = network_state.server_tx.lock().await;
         if let Some(tx) = tx_guard.take() {
             if let Err(e) = tx.send(()).await {
-                return Err(format!("无法发送停止信号: {}", e));
+                return Err(
+                    AppError::new(ErrorCode::Network, t(Msg::StopSignalFailed)).with_details(e)
+                );
             }
         }
     }
@@ -0,0 +1,23 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::services::backup;
+use crate::services::settings::{self, Settings};
+
+// 获取应用设置
+#[tauri::command]
+pub fn get_settings() -> Settings {
+    settings::current()
+}
+
+// 校验并保存应用设置，随后按新的保留份数清理旧快照
+#[tauri::command]
+pub fn update_settings(settings: Settings) -> Result<Settings, AppError> {
+    settings
+        .validate()
+        .map_err(|e| AppError::invalid_input(t(Msg::SaveSettingsFailed)).with_details(e))?;
+    let saved = settings::update(settings)
+        .map_err(|e| AppError::from(e).context(t(Msg::SaveSettingsFailed)))?;
+    backup::rotate_snapshots(saved.snapshot.retention)
+        .map_err(|e| AppError::from(e).context(t(Msg::RotateSnapshotsFailed)))?;
+    Ok(saved)
+}
@@ -0,0 +1,58 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::models::repo::RepoFilter;
+use crate::services::archive::{self, ConflictPolicy, HolePreview, ImportReport};
+use crate::services::export::{self, BatchReport, ExportOptions};
+use crate::services::{settings, witsml};
+use std::path::Path;
+
+/// 读取并校验 WITSML 文件，校验未通过时给出全部问题
+fn read_witsml(file_path: &str) -> Result<Vec<archive::ArchiveHole>, AppError> {
+    let xml = std::fs::read_to_string(file_path)
+        .map_err(|e| AppError::from(e).context(t(Msg::ImportWitsmlFailed)))?;
+    witsml::read_trajectories(&xml)
+        .map_err(|e| AppError::invalid_input(t(Msg::ImportWitsmlFailed)).with_details(e))
+}
+
+// 预览 WITSML 文件中的轨迹及与当前数据库的冲突
+#[tauri::command]
+pub async fn inspect_witsml(file_path: String) -> Result<Vec<HolePreview>, AppError> {
+    let holes = read_witsml(&file_path)?;
+    archive::preview_holes(holes)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ImportWitsmlFailed)))
+}
+
+// 导入 WITSML 1.4.1 轨迹，每个 trajectory 导入为一个钻孔；冲突处理方式默认跳过
+//
+// 只校验导入用到的元素，不做 XSD 校验：元素顺序不检查，不认识的元素忽略。
+#[tauri::command]
+pub async fn import_witsml(
+    file_path: String,
+    on_conflict: Option<ConflictPolicy>,
+) -> Result<ImportReport, AppError> {
+    let holes = read_witsml(&file_path)?;
+    archive::import_holes(holes, on_conflict.unwrap_or(ConflictPolicy::Skip))
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ImportWitsmlFailed)))
+}
+
+// 把所选钻孔导出到一个 WITSML 1.4.1 文件，每个钻孔一个 trajectory
+#[tauri::command]
+pub async fn export_witsml(
+    filter: RepoFilter,
+    file_path: String,
+    magnetic_declination: Option<f64>,
+) -> Result<BatchReport, AppError> {
+    let current = settings::current();
+    let options = ExportOptions {
+        magnetic_declination: magnetic_declination.unwrap_or(current.survey.magnetic_declination),
+        decimal_places: current.export.decimal_places,
+        locale: current.locale,
+        ..ExportOptions::default()
+    };
+    let path = current.resolve_export_path(&file_path);
+    export::export_witsml(filter, Path::new(&path), &options)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ExportWitsmlFailed)))
+}
@@ -0,0 +1,57 @@
+use crate::error::AppError;
+use crate::i18n::{t, Msg};
+use crate::services::workspace::{self, ProjectInfo, RecentProject};
+
+// 获取当前打开的项目
+#[tauri::command]
+pub async fn get_current_project() -> Result<Option<ProjectInfo>, AppError> {
+    workspace::current_project()
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::GetCurrentProjectFailed)))
+}
+
+// 列出项目目录中的项目
+#[tauri::command]
+pub async fn list_projects() -> Result<Vec<ProjectInfo>, AppError> {
+    workspace::list_projects()
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::ListProjectsFailed)))
+}
+
+// 新建项目并切换
+#[tauri::command]
+pub async fn create_project(name: String) -> Result<ProjectInfo, AppError> {
+    workspace::create_project(&name)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::CreateProjectFailed)))
+}
+
+// 切换到指定名称的项目
+#[tauri::command]
+pub async fn switch_project(name: String) -> Result<ProjectInfo, AppError> {
+    workspace::switch_project(&name)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::SwitchProjectFailed)))
+}
+
+// 打开任意位置的数据库文件
+#[tauri::command]
+pub async fn open_project(path: String) -> Result<ProjectInfo, AppError> {
+    workspace::open_project(&path)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::OpenProjectFailed)))
+}
+
+// 获取最近打开的项目
+#[tauri::command]
+pub async fn list_recent_projects() -> Vec<RecentProject> {
+    workspace::list_recent_projects().await
+}
+
+// 从最近打开列表中移除
+#[tauri::command]
+pub async fn remove_recent_project(path: String) -> Result<Vec<RecentProject>, AppError> {
+    workspace::remove_recent_project(&path)
+        .await
+        .map_err(|e| AppError::from(e).context(t(Msg::RemoveRecentProjectFailed)))
+}
@@ -7,21 +7,44 @@ mod utils {
     pub mod license;
 }
 mod commands;
-mod models;
-mod services;
 
+// 数据库、数据接收路由等核心功能位于 drilltrack-core，与无界面服务器共用
+use drilltrack_core::{calc, error, i18n, models, services};
+
+use commands::archive::{export_archive, import_archive, inspect_archive};
+use commands::backup::{
+    backup_database, create_snapshot, list_snapshots, restore_database, validate_backup_file,
+};
 use commands::db::{
     close_database, delete_user, get_all_repos, get_all_users, get_data_list_by_repo_id,
-    get_db_status, get_user_by_id, init_database, save_data_to_excel, save_user, search_users,
+    get_db_status, get_repo_by_uuid, get_user_by_id, init_database, list_report_templates,
+    save_data_to_excel, save_user, search_repos, search_users, update_repo_notes,
+};
+use commands::export::{
+    batch_export, cancel_batch_export, export_dxf, export_kml, export_pdf_report, export_plot,
+    export_repo,
 };
 use commands::license::{
     activate_license, check_activation, export_machine_id, get_license_info_command,
     get_machine_id, import_license_from_file, is_license_expired_command,
 };
+use commands::location::{
+    assign_repo_location, create_location, delete_location, get_location_tree,
+    get_repos_by_location, list_locations, merge_locations, rename_location,
+};
+use commands::merge::{merge_database, preview_database_merge};
 use commands::network::{
     get_data_server_status, get_discovery_status, start_data_server, start_discovery_service,
     stop_data_server, stop_discovery_service,
 };
+use commands::logging::get_recent_logs;
+use commands::settings::{get_settings, update_settings};
+use commands::witsml::{export_witsml, import_witsml, inspect_witsml};
+use commands::workspace::{
+    create_project, get_current_project, list_projects, list_recent_projects, open_project,
+    remove_recent_project, switch_project,
+};
+use tauri::Manager;
 
 fn main() {
     tauri::Builder::default()
@@ -30,21 +53,46 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .setup(|app| {
+            // 初始化日志，后续初始化过程中的警告写入日志文件
+            let app_data_dir = app.path().app_data_dir()?;
+            let log_dir = services::logging::init_logging(&app_data_dir)?;
+            tracing::info!(
+                version = env!("CARGO_PKG_VERSION"),
+                "程序启动，日志目录: {}",
+                log_dir.display()
+            );
+
+            // 加载应用设置，网络模块的默认端口依赖设置
+            services::settings::init_settings(&app.path().app_config_dir()?, &app_data_dir)?;
+
+            // 初始化报表模板目录
+            services::templates::init_templates(&app_data_dir)?;
+
             // 初始化网络模块
             commands::network::init(app)?;
 
-            // 初始化数据库
-            let app_dir =
-                std::env::current_dir().map_err(|e| format!("无法获取当前目录: {}", e))?;
-            let db_path = app_dir.join("database.db").to_string_lossy().to_string();
-            tauri::async_runtime::block_on(init_database(Some(db_path)))?;
+            // 初始化导出状态，用于取消批量导出
+            commands::export::init(app)?;
+
+            // 初始化工作区并打开上次使用的项目数据库
+            let db_path = services::workspace::init_workspace(&app_data_dir)?;
+            tauri::async_runtime::block_on(async {
+                init_database(Some(db_path.clone())).await?;
+                services::workspace::record_opened(&db_path)
+                    .await
+                    .map_err(|e| format!("记录当前项目失败: {}", e))
+            })?;
+
+            // 初始化每日快照
+            services::backup::init_snapshots(&app_data_dir)?;
+            tauri::async_runtime::spawn(services::backup::run_snapshot_scheduler());
 
             Ok(())
         })
         .on_window_event(|_, event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event {
                 // 在窗口关闭时关闭数据库连接
-                println!("关闭数据库连接");
+                tracing::info!("关闭数据库连接");
                 let _ = tauri::async_runtime::block_on(close_database());
             }
         })
@@ -74,7 +122,57 @@ fn main() {
             delete_user,
             get_all_repos,
             get_data_list_by_repo_id,
+            get_repo_by_uuid,
+            search_repos,
+            update_repo_notes,
             save_data_to_excel,
+            list_report_templates,
+            batch_export,
+            cancel_batch_export,
+            export_dxf,
+            export_repo,
+            export_pdf_report,
+            export_plot,
+            export_kml,
+            // 矿井 / 工作面 / 钻场 / 钻机
+            list_locations,
+            create_location,
+            rename_location,
+            delete_location,
+            merge_locations,
+            get_location_tree,
+            get_repos_by_location,
+            assign_repo_location,
+            // 备份与恢复
+            backup_database,
+            validate_backup_file,
+            restore_database,
+            list_snapshots,
+            create_snapshot,
+            // 归档导入导出
+            export_archive,
+            inspect_archive,
+            import_archive,
+            // WITSML 轨迹交换
+            inspect_witsml,
+            import_witsml,
+            export_witsml,
+            // 数据库合并
+            preview_database_merge,
+            merge_database,
+            // 应用设置
+            get_settings,
+            update_settings,
+            // 日志
+            get_recent_logs,
+            // 项目工作区
+            get_current_project,
+            list_projects,
+            create_project,
+            switch_project,
+            open_project,
+            list_recent_projects,
+            remove_recent_project,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
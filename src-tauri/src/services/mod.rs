@@ -1,2 +0,0 @@
-pub mod db; 
-pub mod router;
\ No newline at end of file
@@ -18,7 +18,7 @@ interface DrillingDataContextType {
   activeTab: string
   setActiveTab: (tab: string) => void
   loadDataFromFile: (filePath: string) => Promise<void>
-  saveDataToFile: (filePath: string) => Promise<void>
+  saveDataToFile: (filePath: string, template?: string) => Promise<void>
   repos: Repo[]
   setRepos: (value: Repo[] | ((prev: Repo[]) => Repo[])) => void
   selectedRepoId: string
@@ -60,15 +60,16 @@ export function DrillingDataProvider({ children }: { children: ReactNode }) {
       setIsLoading(false)
     }
   }
-  // 保存数据到文件
-  const saveDataToFile = async (filePath: string) => {
+  // 保存数据到文件，template 为报表模板名称，不传时使用设置中的默认模板
+  const saveDataToFile = async (filePath: string, template?: string) => {
     try {
       setIsLoading(true)
       // 调用 Tauri 的 Rust 函数来保存文件
       await invoke("save_data_to_excel", { 
         filePath, 
         data: drillingData,
-        magneticDeclination: selectedMagneticDeclination 
+        magneticDeclination: selectedMagneticDeclination,
+        template: template ?? null
       })
       console.log("数据已保存到:", filePath)
       setIsLoading(false)
@@ -0,0 +1,32 @@
+// 后端命令返回的错误，code 稳定不变，可据此判断错误类型
+export type ErrorCode =
+  | 'DB_NOT_INITIALIZED'
+  | 'DB_CONSTRAINT'
+  | 'DB_BUSY'
+  | 'DATABASE'
+  | 'NOT_FOUND'
+  | 'INVALID_INPUT'
+  | 'IO'
+  | 'LICENSE_MISSING'
+  | 'LICENSE_INVALID'
+  | 'NETWORK'
+  | 'EXPORT'
+  | 'INTERNAL';
+
+export interface AppError {
+  code: ErrorCode;
+  message: string;
+  details?: string | null;
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return typeof err === 'object' && err !== null && 'code' in err && 'message' in err;
+}
+
+// 转为可显示的提示文字
+export function errorMessage(err: unknown): string {
+  if (isAppError(err)) {
+    return err.details ? `${err.message}: ${err.details}` : err.message;
+  }
+  return String(err);
+}
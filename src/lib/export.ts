@@ -0,0 +1,276 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
+
+export type ExportFormat =
+  | 'xlsx'
+  | 'csv'
+  | 'dxf'
+  | 'scr'
+  | 'json'
+  | 'geojson'
+  | 'pdf'
+  | 'svg'
+  | 'png'
+  | 'las'
+  | 'witsml'
+  | 'kml';
+
+// workbook：一个工作簿（索引页 + 每孔一页）；folder：每个钻孔单独成文件
+export type BatchMode = 'workbook' | 'folder';
+
+export interface RepoFilter {
+  ids?: number[];
+  mine?: string;
+  working_face?: string;
+  // YYYY-MM-DD，含两端
+  from?: string;
+  to?: string;
+}
+
+export interface BatchExportRequest {
+  filter?: RepoFilter;
+  mode: BatchMode;
+  // 工作簿文件路径或输出目录
+  path: string;
+  formats?: ExportFormat[];
+  // 孔口坐标，键为钻孔 ID；kml 格式没有孔口坐标的钻孔跳过
+  collars?: Record<number, Collar>;
+  // 孔口坐标的投影，未指定时使用应用设置；kml 格式必须有投影
+  projection?: Projection;
+}
+
+export interface BatchProgress {
+  done: number;
+  total: number;
+  repo_id?: number;
+  repo_name: string;
+}
+
+export interface BatchReport {
+  total: number;
+  exported: number;
+  skipped: { repo_id?: number; repo_name: string; problems: string[] }[];
+  files: string[];
+  cancelled: boolean;
+}
+
+export interface TemplateInfo {
+  name: string;
+  description: string;
+  path?: string | null;
+}
+
+// 可选的 Excel 报表模板
+export async function listReportTemplates(): Promise<TemplateInfo[]> {
+  return await invoke('list_report_templates');
+}
+
+// 批量导出，导出过程中通过 onProgress 回报进度
+export async function batchExport(
+  request: BatchExportRequest,
+  onProgress?: (progress: BatchProgress) => void,
+  options: { magneticDeclination?: number; template?: string } = {},
+): Promise<BatchReport> {
+  let unlisten: UnlistenFn | undefined;
+  if (onProgress) {
+    unlisten = await listen<BatchProgress>('batch-export-progress', (event) => onProgress(event.payload));
+  }
+  try {
+    return await invoke('batch_export', {
+      request,
+      magneticDeclination: options.magneticDeclination ?? null,
+      template: options.template ?? null,
+    });
+  } finally {
+    unlisten?.();
+  }
+}
+
+// 取消正在进行的批量导出
+export async function cancelBatchExport(): Promise<boolean> {
+  return await invoke('cancel_batch_export');
+}
+
+// 孔口坐标（米），X 为东、Y 为北
+export interface Collar {
+  east: number;
+  north: number;
+  elevation: number;
+}
+
+export interface DxfOptions {
+  // 深度标注间隔（米），0 表示每个测点都标注
+  label_interval?: number;
+  text_height?: number;
+}
+
+// 把所选钻孔的平面图和剖面图导出到一个 DXF 文件，collars 以钻孔 ID 为键
+export async function exportDxf(
+  filter: RepoFilter,
+  filePath: string,
+  collars?: Record<number, Collar>,
+  dxf?: DxfOptions,
+): Promise<BatchReport> {
+  return await invoke('export_dxf', { filter, filePath, collars: collars ?? null, dxf: dxf ?? null });
+}
+
+export interface CsvOptions {
+  // 字段分隔符，如 ','、';' 或 '\t'
+  delimiter: string;
+  // 小数点，'.' 或 ','
+  decimal_separator: string;
+}
+
+// 导出单个钻孔，返回写出的文件路径；witsml 写出扩展名为 .xml 的 WITSML 1.4.1 trajectory，kml 需要 collar 和设置中的投影；csv 未指定时使用设置中的分隔符与小数点，collar 用于 GeoJSON、DXF 与轨迹图
+export async function exportRepo(
+  repoId: number,
+  format: ExportFormat,
+  filePath: string,
+  options: {
+    csv?: CsvOptions;
+    collar?: Collar;
+    magneticDeclination?: number;
+    template?: string;
+  } = {},
+): Promise<string> {
+  return await invoke('export_repo', {
+    repoId,
+    format,
+    filePath,
+    csv: options.csv ?? null,
+    collar: options.collar ?? null,
+    magneticDeclination: options.magneticDeclination ?? null,
+    template: options.template ?? null,
+  });
+}
+
+export interface SignatureBlock {
+  // 栏目名称，如“审核人”
+  label: string;
+  // 预先印上的姓名，为空时留给手写
+  name?: string | null;
+}
+
+export interface PdfOptions {
+  company?: string | null;
+  // 公司标志图片（JPEG 或 PNG）路径
+  logo?: string | null;
+  // 未指定时为测量人、审核人、批准人，空数组表示不印签字栏
+  signatures?: SignatureBlock[] | null;
+}
+
+// 生成单个钻孔的 PDF 测量报告，返回写出的文件路径；pdf 未指定时使用设置中的页眉与签字栏
+export async function exportPdfReport(
+  repoId: number,
+  filePath: string,
+  options: { pdf?: PdfOptions; collar?: Collar; magneticDeclination?: number } = {},
+): Promise<string> {
+  return await invoke('export_pdf_report', {
+    repoId,
+    filePath,
+    pdf: options.pdf ?? null,
+    collar: options.collar ?? null,
+    magneticDeclination: options.magneticDeclination ?? null,
+  });
+}
+
+// plan：平面图；profile：沿第一个钻孔设计方位的剖面图；isometric：等轴测图
+export type PlotView = 'plan' | 'profile' | 'isometric';
+
+export interface PlotOptions {
+  view?: PlotView;
+  // 图片尺寸（像素），200 ~ 8000，宽高乘积不超过 4096×4096
+  width?: number;
+  height?: number;
+  grid?: boolean;
+  stations?: boolean;
+  design?: boolean;
+  // 剖面图的高程放大倍数
+  vertical_exaggeration?: number;
+}
+
+// 把所选钻孔画到同一张轨迹图（SVG 或 PNG），plot 未指定时使用设置中的视图与样式
+export async function exportPlot(
+  filter: RepoFilter,
+  filePath: string,
+  format: 'svg' | 'png',
+  options: { plot?: PlotOptions; collars?: Record<number, Collar>; magneticDeclination?: number } = {},
+): Promise<BatchReport> {
+  return await invoke('export_plot', {
+    filter,
+    filePath,
+    format,
+    plot: options.plot ?? null,
+    collars: options.collars ?? null,
+    magneticDeclination: options.magneticDeclination ?? null,
+  });
+}
+
+// 孔口坐标的横轴墨卡托（高斯-克吕格）投影，用于 KML 反算经纬度
+export interface Projection {
+  ellipsoid?: 'cgcs2000' | 'wgs84' | 'beijing54' | 'xian80';
+  // 中央子午线经度（度）
+  central_meridian: number;
+  // 高斯投影为 1（默认），UTM 为 0.9996
+  scale_factor?: number;
+  // 默认 500000；东坐标含带号时计入带号，如 39500000
+  false_easting?: number;
+  false_northing?: number;
+}
+
+// 把所选钻孔导出到一个 KML 文件，按矿井、工作面分文件夹；没有孔口坐标的钻孔跳过，projection 未指定时使用设置中的投影
+export async function exportKml(
+  filter: RepoFilter,
+  filePath: string,
+  collars: Record<number, Collar>,
+  options: { projection?: Projection; magneticDeclination?: number } = {},
+): Promise<BatchReport> {
+  return await invoke('export_kml', {
+    filter,
+    filePath,
+    collars,
+    projection: options.projection ?? null,
+    magneticDeclination: options.magneticDeclination ?? null,
+  });
+}
+
+// skip：保留本地数据；replace：用导入的数据覆盖；keep_both：两份都保留
+export type ConflictPolicy = 'skip' | 'replace' | 'keep_both';
+
+export interface HolePreview {
+  uuid?: string | null;
+  name: string;
+  mine: string;
+  work: string;
+  mn_time: string;
+  station_count: number;
+  status: 'new' | 'duplicate' | 'conflict';
+  existing_id?: number | null;
+}
+
+export interface ImportReport {
+  imported: number;
+  replaced: number;
+  duplicates: HolePreview[];
+  conflicts: HolePreview[];
+  new_repo_ids: number[];
+}
+
+// 预览 WITSML 1.4.1 文件中的轨迹及与当前数据库的冲突，校验未通过时抛出全部问题
+export async function inspectWitsml(filePath: string): Promise<HolePreview[]> {
+  return await invoke('inspect_witsml', { filePath });
+}
+
+// 导入 WITSML 1.4.1 轨迹，每个 trajectory 导入为一个钻孔；onConflict 默认 skip
+export async function importWitsml(filePath: string, onConflict?: ConflictPolicy): Promise<ImportReport> {
+  return await invoke('import_witsml', { filePath, onConflict: onConflict ?? null });
+}
+
+// 把所选钻孔导出到一个 WITSML 1.4.1 文件，每个钻孔一个 trajectory
+export async function exportWitsml(
+  filter: RepoFilter,
+  filePath: string,
+  magneticDeclination?: number,
+): Promise<BatchReport> {
+  return await invoke('export_witsml', { filter, filePath, magneticDeclination: magneticDeclination ?? null });
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { save } from '@tauri-apps/plugin-dialog';
+import { errorMessage } from '../lib/errors';
 
 const Activation = () => {
   const [machineId, setMachineId] = useState<string>('');
@@ -48,7 +49,7 @@ const Activation = () => {
         setActivationError('许可证验证失败');
       }
     } catch (error) {
-      setActivationError(`激活错误: ${error}`);
+      setActivationError(`激活错误: ${errorMessage(error)}`);
     } finally {
       setActivating(false);
     }
@@ -67,7 +68,7 @@ const Activation = () => {
         alert('机器ID已成功导出！');
       }
     } catch (error) {
-      alert(`导出失败: ${error}`);
+      alert(`导出失败: ${errorMessage(error)}`);
     }
   };
 
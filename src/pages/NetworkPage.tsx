@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core'
 import { getAllRepos, Repo } from '../lib/db';
+import { errorMessage } from '../lib/errors';
 import { HelpCircle } from 'lucide-react';
 import {
   Dialog,
@@ -100,7 +101,7 @@ const NetworkPage: React.FC = () => {
       setDiscoveryStatus(result);
       setSuccess('局域网发现服务已启动');
     } catch (err: any) {
-      setError(err.toString());
+      setError(errorMessage(err));
     }
   };
 
@@ -114,7 +115,7 @@ const NetworkPage: React.FC = () => {
       setDiscoveryStatus(result);
       setSuccess('局域网发现服务已停止');
     } catch (err: any) {
-      setError(err.toString());
+      setError(errorMessage(err));
     }
   };
 
@@ -137,7 +138,7 @@ const NetworkPage: React.FC = () => {
       setServerStatus(result);
       setSuccess('数据服务器已启动');
     } catch (err: any) {
-      setError(err.toString());
+      setError(errorMessage(err));
     }
   };
 
@@ -151,7 +152,7 @@ const NetworkPage: React.FC = () => {
       setServerStatus(result);
       setSuccess('数据服务器已停止');
     } catch (err: any) {
-      setError(err.toString());
+      setError(errorMessage(err));
     }
   };
 
@@ -162,7 +163,7 @@ const NetworkPage: React.FC = () => {
       const data = await getAllRepos();
       setRepos(data);
     } catch (err: any) {
-      setRepoError('获取仓库数据失败: ' + err.toString());
+      setRepoError('获取仓库数据失败: ' + errorMessage(err));
     } finally {
       setRepoLoading(false);
     }